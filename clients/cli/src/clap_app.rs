@@ -14,7 +14,10 @@ use {
         ArgConstant,
     },
     solana_sdk::{instruction::AccountMeta, pubkey::Pubkey},
-    spl_token_2022::instruction::{AuthorityType, MAX_SIGNERS, MIN_SIGNERS},
+    spl_token_2022::{
+        extension::{cpi_guard::MAX_ALLOWED_PROGRAMS, ExtensionType},
+        instruction::{AuthorityType, MAX_SIGNERS, MAX_WEIGHTED_SIGNERS, MIN_SIGNERS},
+    },
     std::{fmt, str::FromStr},
     strum::IntoEnumIterator,
     strum_macros::{AsRefStr, EnumIter, EnumString, IntoStaticStr},
@@ -126,6 +129,7 @@ pub enum CommandName {
     SetInterestRate,
     Transfer,
     Burn,
+    Recover,
     Mint,
     Freeze,
     Thaw,
@@ -168,6 +172,7 @@ pub enum CommandName {
     ApplyPendingBalance,
     UpdateGroupAddress,
     UpdateMemberAddress,
+    AddMintExtension,
 }
 impl fmt::Display for CommandName {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -273,6 +278,27 @@ impl TryFrom<CliAuthorityType> for AuthorityType {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum CliMintExtension {
+    MetadataPointer,
+    GroupPointer,
+    GroupMemberPointer,
+    Pausable,
+    ScaledUiAmount,
+}
+impl From<CliMintExtension> for ExtensionType {
+    fn from(extension: CliMintExtension) -> Self {
+        match extension {
+            CliMintExtension::MetadataPointer => ExtensionType::MetadataPointer,
+            CliMintExtension::GroupPointer => ExtensionType::GroupPointer,
+            CliMintExtension::GroupMemberPointer => ExtensionType::GroupMemberPointer,
+            CliMintExtension::Pausable => ExtensionType::Pausable,
+            CliMintExtension::ScaledUiAmount => ExtensionType::ScaledUiAmount,
+        }
+    }
+}
+
 pub fn owner_address_arg<'a>() -> Arg<'a> {
     Arg::with_name(OWNER_ADDRESS_ARG.name)
         .long(OWNER_ADDRESS_ARG.long)
@@ -350,16 +376,14 @@ pub fn multisig_signer_arg<'a>() -> Arg<'a> {
         .takes_value(true)
         .multiple(true)
         .min_values(0_usize)
-        .max_values(MAX_SIGNERS)
+        .max_values(MAX_WEIGHTED_SIGNERS)
         .help(MULTISIG_SIGNER_ARG.help)
 }
 
 fn is_multisig_minimum_signers(string: &str) -> Result<(), String> {
-    let v = u8::from_str(string).map_err(|e| e.to_string())? as usize;
+    let v = u16::from_str(string).map_err(|e| e.to_string())? as usize;
     if v < MIN_SIGNERS {
         Err(format!("must be at least {}", MIN_SIGNERS))
-    } else if v > MAX_SIGNERS {
-        Err(format!("must be at most {}", MAX_SIGNERS))
     } else {
         Ok(())
     }
@@ -434,15 +458,17 @@ impl offline::ArgsConfig for SignOnlyNeedsTransferLamports {
 
 pub fn minimum_signers_help_string() -> String {
     format!(
-        "The minimum number of signers required to allow the operation. [{} <= M <= N]",
+        "The minimum number of signers required to allow the operation. [{} <= M <= N] \
+         With --weights, the minimum total weight of the signers instead.",
         MIN_SIGNERS
     )
 }
 
 pub fn multisig_member_help_string() -> String {
     format!(
-        "The public keys for each of the N signing members of this account. [{} <= N <= {}]",
-        MIN_SIGNERS, MAX_SIGNERS
+        "The public keys for each of the N signing members of this account. \
+         [{} <= N <= {}, or up to {} with --weights]",
+        MIN_SIGNERS, MAX_SIGNERS, MAX_WEIGHTED_SIGNERS
     )
 }
 
@@ -785,6 +811,18 @@ pub fn app<'a>(
                             "Permanently force tokens to be non-transferable. They may still be burned."
                         ),
                 )
+                .arg(
+                    Arg::with_name("recovery_authority")
+                        .long("recovery-authority")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("ADDRESS")
+                        .takes_value(true)
+                        .requires("enable_non_transferable")
+                        .help(
+                            "Specify an authority that may move non-transferable tokens \
+                            between accounts, e.g. to recover them from a lost wallet."
+                        ),
+                )
                 .arg(
                     Arg::with_name("default_account_state")
                         .long("default-account-state")
@@ -1066,6 +1104,27 @@ pub fn app<'a>(
                     .takes_value(true)
                     .help("Specify the metadata update authority keypair. Defaults to the client keypair.")
                 )
+                .arg(
+                    Arg::with_name("refund_destination")
+                        .long("refund-destination")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("REFUND_ACCOUNT_ADDRESS")
+                        .takes_value(true)
+                        .requires("mint_authority")
+                        .help("The address of the account to receive the rent freed if the mint account shrinks")
+                )
+                .arg(
+                    Arg::with_name("mint_authority")
+                        .long("mint-authority")
+                        .value_name("KEYPAIR")
+                        .validator(|s| is_valid_signer(s))
+                        .takes_value(true)
+                        .requires("refund_destination")
+                        .help(
+                            "Specify the mint authority keypair approving the rent refund. \
+                             This may be a keypair file or the ASK keyword."
+                        ),
+                )
                 .nonce_args(true)
                 .arg(transfer_lamports_arg())
                 .offline_args_config(&SignOnlyNeedsTransferLamports{}),
@@ -1251,9 +1310,24 @@ pub fn app<'a>(
                         .index(2)
                         .required(true)
                         .min_values(MIN_SIGNERS)
-                        .max_values(MAX_SIGNERS)
+                        .max_values(MAX_WEIGHTED_SIGNERS)
                         .help(multisig_member_help),
                 )
+                .arg(
+                    Arg::with_name("weights")
+                        .long("weights")
+                        .value_name("WEIGHT")
+                        .value_parser(clap::value_parser!(u8))
+                        .takes_value(true)
+                        .multiple(true)
+                        .min_values(MIN_SIGNERS)
+                        .max_values(MAX_WEIGHTED_SIGNERS)
+                        .help(
+                            "Create a weighted multisig, giving each MULTISIG_MEMBER, in order, \
+                             the specified signing weight. MINIMUM_SIGNERS is then the total \
+                             weight required to allow the operation."
+                        ),
+                )
                 .arg(
                     Arg::with_name("address_keypair")
                         .long("address-keypair")
@@ -1494,6 +1568,51 @@ pub fn app<'a>(
                 .arg(memo_arg())
                 .offline_args_config(&SignOnlyNeedsFullMintSpec{}),
         )
+        .subcommand(
+            SubCommand::with_name(CommandName::Recover.into())
+                .about("Move non-transferable tokens using the mint's recovery authority")
+                .arg(
+                    Arg::with_name("source")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("SOURCE_TOKEN_ACCOUNT_ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The token account address to recover tokens from"),
+                )
+                .arg(
+                    Arg::with_name("destination")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("DESTINATION_TOKEN_ACCOUNT_ADDRESS")
+                        .takes_value(true)
+                        .index(2)
+                        .required(true)
+                        .help("The token account address to move the recovered tokens to"),
+                )
+                .arg(
+                    Arg::with_name("amount")
+                        .value_parser(Amount::parse)
+                        .value_name("TOKEN_AMOUNT")
+                        .takes_value(true)
+                        .index(3)
+                        .required(true)
+                        .help("Amount to recover, in tokens; accepts keyword ALL"),
+                )
+                .arg(
+                    Arg::with_name("recovery_authority")
+                        .long("recovery-authority")
+                        .validator(|s| is_valid_signer(s))
+                        .value_name("KEYPAIR")
+                        .takes_value(true)
+                        .help(
+                            "Specify the mint's recovery authority. \
+                            This may be a keypair file or the ASK keyword. \
+                            Defaults to the client keypair.",
+                        ),
+                )
+                .arg(multisig_signer_arg())
+                .nonce_args(true),
+        )
         .subcommand(
             SubCommand::with_name(CommandName::Mint.into())
                 .about("Mint new tokens")
@@ -1716,6 +1835,15 @@ pub fn app<'a>(
                         .required(true)
                         .help("The token account address of delegate"),
                 )
+                .arg(
+                    Arg::with_name("expires_at")
+                        .long("expires-at")
+                        .value_parser(clap::value_parser!(i64))
+                        .value_name("UNIX_TIMESTAMP")
+                        .takes_value(true)
+                        .help("Unix timestamp at which the approval expires. \
+                               Adds room for the expiry to the account if needed.")
+                )
                 .arg(
                     owner_keypair_arg()
                 )
@@ -1993,7 +2121,7 @@ pub fn app<'a>(
         )
         .subcommand(
             SubCommand::with_name(CommandName::Gc.into())
-                .about("Cleanup unnecessary token accounts")
+                .about("Cleanup unnecessary token accounts and unused account extensions")
                 .arg(owner_keypair_arg())
                 .arg(
                     Arg::with_name("close_empty_associated_accounts")
@@ -2071,6 +2199,22 @@ pub fn app<'a>(
                         .required(true)
                         .help("The address of the token account to enable CPI Guard for")
                 )
+                .arg(
+                    Arg::with_name("allow_program")
+                        .long("allow-program")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("PROGRAM_ID")
+                        .takes_value(true)
+                        .multiple(true)
+                        .min_values(0)
+                        .max_values(MAX_ALLOWED_PROGRAMS)
+                        .help(
+                            "Replace the account's allowlist with the given programs, which may \
+                             still perform guarded operations when invoking the token program \
+                             directly from the transaction. Pass the flag without programs to \
+                             clear the allowlist."
+                        ),
+                )
                 .arg(
                     owner_address_arg()
                 )
@@ -2262,6 +2406,45 @@ pub fn app<'a>(
                 .arg(multisig_signer_arg())
                 .nonce_args(true)
         )
+        .subcommand(
+            SubCommand::with_name(CommandName::AddMintExtension.into())
+                .about("Add extensions to an existing mint. The new extensions are \
+                    configured with the mint authority as their authority.")
+                .arg(
+                    Arg::with_name("token")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("TOKEN_MINT_ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The address of the token mint to add extensions to"),
+                )
+                .arg(
+                    Arg::with_name("extensions")
+                        .value_name("EXTENSION")
+                        .takes_value(true)
+                        .possible_values(CliMintExtension::iter().map(Into::<&str>::into).collect::<Vec<_>>())
+                        .index(2)
+                        .required(true)
+                        .multiple(true)
+                        .help("The extensions to add to the mint"),
+                )
+                .arg(
+                    Arg::with_name("mint_authority")
+                        .long("mint-authority")
+                        .alias("owner")
+                        .value_name("KEYPAIR")
+                        .validator(|s| is_valid_signer(s))
+                        .takes_value(true)
+                        .help(
+                            "Specify the mint authority keypair. \
+                             This may be a keypair file or the ASK keyword. \
+                             Defaults to the client keypair."
+                        ),
+                )
+                .arg(multisig_signer_arg())
+                .nonce_args(true)
+        )
         .subcommand(
             SubCommand::with_name(CommandName::WithdrawWithheldTokens.into())
                 .about("Withdraw withheld transfer fee tokens from mint and / or account(s)")
@@ -14,7 +14,9 @@ use {
     serde::Serialize,
     solana_account_decoder::{
         parse_account_data::SplTokenAdditionalData,
-        parse_token::{get_token_account_mint, parse_token_v2, TokenAccountType, UiAccountState},
+        parse_token::{
+            get_token_account_mint, parse_token_v2, TokenAccountType, UiAccountState, UiTokenAmount,
+        },
         UiAccountData,
     },
     solana_clap_v3_utils::{
@@ -28,14 +30,17 @@ use {
     solana_client::rpc_request::TokenAccountsFilter,
     solana_remote_wallet::remote_wallet::RemoteWalletManager,
     solana_sdk::{
+        account::from_account,
+        clock::Clock,
         instruction::AccountMeta,
         native_token::*,
         program_option::COption,
         pubkey::Pubkey,
         signature::{Keypair, Signer},
-        system_program,
+        system_program, sysvar,
     },
     spl_associated_token_account_client::address::get_associated_token_address_with_program_id,
+    spl_pod::bytemuck::pod_from_bytes,
     spl_token_2022::{
         extension::{
             confidential_transfer::{
@@ -54,16 +59,21 @@ use {
             metadata_pointer::MetadataPointer,
             mint_close_authority::MintCloseAuthority,
             permanent_delegate::PermanentDelegate,
+            reallocate::check_account_extension_removable,
+            scaled_ui_amount::ScaledUiAmountConfig,
             transfer_fee::{TransferFeeAmount, TransferFeeConfig},
             transfer_hook::TransferHook,
             BaseStateWithExtensions, ExtensionType, StateWithExtensionsOwned,
         },
+        instruction::MAX_SIGNERS,
+        pod::PodWeightedMultisig,
         solana_zk_sdk::encryption::{
             auth_encryption::AeKey,
             elgamal::{self, ElGamalKeypair},
             pod::elgamal::PodElGamalPubkey,
         },
-        state::{Account, AccountState, Mint},
+        state::{Account, AccountState, Mint, PackedSizeOf},
+        ui_amount::amount_to_ui_amount_for_mint,
     },
     spl_token_client::{
         client::{ProgramRpcClientSendTransaction, RpcClientResponse},
@@ -251,6 +261,7 @@ async fn command_create_token(
     enable_freeze: bool,
     enable_close: bool,
     enable_non_transferable: bool,
+    recovery_authority: Option<Pubkey>,
     enable_permanent_delegate: bool,
     memo: Option<String>,
     metadata_address: Option<Pubkey>,
@@ -303,6 +314,16 @@ async fn command_create_token(
         extensions.push(ExtensionInitializationParams::NonTransferable);
     }
 
+    if let Some(recovery_authority) = recovery_authority {
+        assert!(
+            enable_non_transferable,
+            "Recovery authority requires the token to be non-transferable"
+        );
+        extensions.push(ExtensionInitializationParams::NonTransferableRecovery {
+            authority: Some(recovery_authority),
+        });
+    }
+
     if let Some(state) = default_account_state {
         assert!(
             enable_freeze,
@@ -609,6 +630,7 @@ async fn command_initialize_metadata(
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn command_update_metadata(
     config: &Config<'_>,
     token_pubkey: Pubkey,
@@ -616,9 +638,13 @@ async fn command_update_metadata(
     field: Field,
     value: Option<String>,
     transfer_lamports: Option<u64>,
+    refund: Option<(Pubkey, Pubkey)>,
     bulk_signers: Vec<Arc<dyn Signer>>,
 ) -> CommandResult {
     let token = token_client_from_config(config, &token_pubkey, None)?;
+    let refund = refund
+        .as_ref()
+        .map(|(refund_destination, mint_authority)| (refund_destination, mint_authority));
 
     let res = if let Some(value) = value {
         token
@@ -628,6 +654,7 @@ async fn command_update_metadata(
                 field,
                 value,
                 transfer_lamports,
+                refund,
                 &bulk_signers,
             )
             .await?
@@ -637,6 +664,7 @@ async fn command_update_metadata(
                 &authority,
                 key,
                 true, // idempotent
+                refund,
                 &bulk_signers,
             )
             .await?
@@ -902,30 +930,46 @@ async fn command_create_account(
 async fn command_create_multisig(
     config: &Config<'_>,
     multisig: Arc<dyn Signer>,
-    minimum_signers: u8,
+    minimum_signers: u16,
     multisig_members: Vec<Pubkey>,
+    weights: Option<Vec<u8>>,
 ) -> CommandResult {
-    println_display(
-        config,
-        format!(
-            "Creating {}/{} multisig {} under program {}",
-            minimum_signers,
-            multisig_members.len(),
-            multisig.pubkey(),
-            config.program_id,
-        ),
-    );
-
     // default is safe here because create_multisig doesn't use it
     let token = token_client_from_config(config, &Pubkey::default(), None)?;
+    let multisig_members = multisig_members.iter().collect::<Vec<_>>();
 
-    let res = token
-        .create_multisig(
-            &*multisig,
-            &multisig_members.iter().collect::<Vec<_>>(),
-            minimum_signers,
-        )
-        .await?;
+    let res = if let Some(weights) = weights {
+        let total_weight: u16 = weights.iter().map(|weight| *weight as u16).sum();
+        println_display(
+            config,
+            format!(
+                "Creating weighted {}/{} multisig {} under program {}",
+                minimum_signers,
+                total_weight,
+                multisig.pubkey(),
+                config.program_id,
+            ),
+        );
+
+        token
+            .create_weighted_multisig(&*multisig, &multisig_members, &weights, minimum_signers)
+            .await?
+    } else {
+        println_display(
+            config,
+            format!(
+                "Creating {}/{} multisig {} under program {}",
+                minimum_signers,
+                multisig_members.len(),
+                multisig.pubkey(),
+                config.program_id,
+            ),
+        );
+
+        token
+            .create_multisig(&*multisig, &multisig_members, minimum_signers as u8)
+            .await?
+    };
 
     let tx_return = finish_tx(config, &res, false).await?;
     Ok(match tx_return {
@@ -1335,6 +1379,27 @@ async fn command_transfer(
     let maybe_fee =
         ui_fee.map(|v| amount_to_raw_amount(v, mint_info.decimals, None, "EXPECTED_FEE"));
 
+    // the mint may charge a tiered fee, so compare with the fee it computes
+    // rather than its flat rate. Fee-exempt accounts are charged nothing, so
+    // only note the difference.
+    if let (Some(fee), false) = (maybe_fee, config.sign_only) {
+        let mint_fee = token.calculate_transfer_fee(transfer_balance).await?;
+        if fee != mint_fee {
+            println_display(
+                config,
+                format!(
+                    "Note: the expected fee is {}, but the mint charges {} on this transfer \
+                     unless an account is fee exempt",
+                    spl_token_2022::amount_to_ui_amount_string_trimmed(fee, mint_info.decimals),
+                    spl_token_2022::amount_to_ui_amount_string_trimmed(
+                        mint_fee,
+                        mint_info.decimals
+                    ),
+                ),
+            );
+        }
+    }
+
     // determine whether recipient is a token account or an expected owner of one
     let recipient_is_token_account = if !config.sign_only {
         // in online mode we can fetch it and see
@@ -1812,6 +1877,59 @@ async fn command_burn(
     })
 }
 
+async fn command_recover(
+    config: &Config<'_>,
+    source: Pubkey,
+    destination: Pubkey,
+    recovery_authority: Pubkey,
+    ui_amount: Amount,
+    bulk_signers: BulkSigners,
+) -> CommandResult {
+    let mint_address = config.check_account(&source, None).await?;
+    let mint_info = config.get_mint_info(&mint_address, None).await?;
+    config
+        .check_account(&destination, Some(mint_address))
+        .await?;
+
+    let token = token_client_from_config(config, &mint_info.address, Some(mint_info.decimals))?;
+
+    let amount = match ui_amount {
+        Amount::Raw(ui_amount) => ui_amount,
+        Amount::Decimal(ui_amount) => spl_token::ui_amount_to_amount(ui_amount, mint_info.decimals),
+        Amount::All => token.get_account_info(&source).await?.base.amount,
+    };
+
+    println_display(
+        config,
+        format!(
+            "Recover {} tokens\n  Source: {}\n  Destination: {}",
+            spl_token::amount_to_ui_amount(amount, mint_info.decimals),
+            source,
+            destination
+        ),
+    );
+
+    let res = token
+        .recover(
+            &source,
+            &destination,
+            &recovery_authority,
+            amount,
+            &bulk_signers,
+        )
+        .await?;
+
+    let tx_return = finish_tx(config, &res, false).await?;
+    Ok(match tx_return {
+        TransactionReturnData::CliSignature(signature) => {
+            config.output_format.formatted_string(&signature)
+        }
+        TransactionReturnData::CliSignOnlyData(sign_only_data) => {
+            config.output_format.formatted_string(&sign_only_data)
+        }
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn command_mint(
     config: &Config<'_>,
@@ -2070,6 +2188,7 @@ async fn command_approve(
     mint_address: Option<Pubkey>,
     mint_decimals: Option<u8>,
     use_unchecked_instruction: bool,
+    expires_at: Option<i64>,
     bulk_signers: BulkSigners,
 ) -> CommandResult {
     let mint_address = config.check_account(&account, mint_address).await?;
@@ -2090,10 +2209,20 @@ async fn command_approve(
             delegate
         ),
     );
+    if let Some(expires_at) = expires_at {
+        println_display(config, format!("  Expires at: {}", expires_at));
+    }
 
     let token = token_client_from_config(config, &mint_info.address, decimals)?;
     let res = token
-        .approve(&account, &delegate, &owner, amount, &bulk_signers)
+        .approve(
+            &account,
+            &delegate,
+            &owner,
+            amount,
+            expires_at,
+            &bulk_signers,
+        )
         .await?;
 
     let tx_return = finish_tx(config, &res, false).await?;
@@ -2276,18 +2405,57 @@ async fn command_close_mint(
     })
 }
 
+// The UI amount reported by the RPC node for scaled or interest-bearing mints is
+// computed with floats, so recompute it with the program's exact conversion
+async fn exact_ui_token_amount(
+    config: &Config<'_>,
+    mint: &Pubkey,
+    mut token_amount: UiTokenAmount,
+) -> Result<UiTokenAmount, Error> {
+    let mint_account = config.rpc_client.get_account(mint).await?;
+    if mint_account.owner != spl_token_2022::id() {
+        return Ok(token_amount);
+    }
+    let mint_state = StateWithExtensionsOwned::<Mint>::unpack(mint_account.data)
+        .map_err(|_| format!("Could not deserialize token mint {}", mint))?;
+    if mint_state.get_extension::<InterestBearingConfig>().is_err()
+        && mint_state.get_extension::<ScaledUiAmountConfig>().is_err()
+    {
+        return Ok(token_amount);
+    }
+
+    let clock_account = config.rpc_client.get_account(&sysvar::clock::id()).await?;
+    let clock = from_account::<Clock, _>(&clock_account).ok_or("Could not deserialize clock")?;
+    let amount = U256::from_str(&token_amount.amount)?;
+    token_amount.ui_amount_string = amount_to_ui_amount_for_mint(
+        &mint_state,
+        mint_state.base.decimals,
+        amount,
+        clock.unix_timestamp,
+    )
+    .ok_or_else(|| format!("Could not convert amount {} for mint {}", amount, mint))?;
+    Ok(token_amount)
+}
+
 async fn command_balance(config: &Config<'_>, address: Pubkey) -> CommandResult {
     let balance = config
         .rpc_client
         .get_token_account_balance(&address)
         .await
         .map_err(|_| format!("Could not find token account {}", address))?;
+    let account = config.rpc_client.get_account(&address).await?;
+    let balance = if let Some(mint) = get_token_account_mint(&account.data) {
+        exact_ui_token_amount(config, &mint, balance).await?
+    } else {
+        balance
+    };
     let cli_token_amount = CliTokenAmount { amount: balance };
     Ok(config.output_format.formatted_string(&cli_token_amount))
 }
 
 async fn command_supply(config: &Config<'_>, token: Pubkey) -> CommandResult {
     let supply = config.rpc_client.get_token_supply(&token).await?;
+    let supply = exact_ui_token_amount(config, &token, supply).await?;
     let cli_token_amount = CliTokenAmount { amount: supply };
     Ok(config.output_format.formatted_string(&cli_token_amount))
 }
@@ -2359,6 +2527,28 @@ async fn command_address(
 async fn command_display(config: &Config<'_>, address: Pubkey) -> CommandResult {
     let account_data = config.get_account_checked(&address).await?;
 
+    if account_data.owner == config.program_id
+        && PodWeightedMultisig::is_weighted_multisig(&account_data.data)
+    {
+        let multisig = pod_from_bytes::<PodWeightedMultisig>(&account_data.data)?;
+        let cli_output = CliWeightedMultisig {
+            address: address.to_string(),
+            program_id: config.program_id.to_string(),
+            threshold: multisig.threshold.into(),
+            total_weight: multisig.total_weight(),
+            signers: multisig.signers[..multisig.n as usize]
+                .iter()
+                .zip(multisig.weights.iter())
+                .map(|(pubkey, weight)| CliWeightedMultisigSigner {
+                    pubkey: pubkey.to_string(),
+                    weight: *weight,
+                })
+                .collect(),
+        };
+
+        return Ok(config.output_format.formatted_string(&cli_output));
+    }
+
     let (additional_data, has_permanent_delegate) =
         if let Some(mint_address) = get_token_account_mint(&account_data.data) {
             let mint_account = config.get_account_checked(&mint_address).await?;
@@ -2495,6 +2685,7 @@ async fn command_gc(
             token.create_associated_token_account(&owner).await?;
         }
 
+        let addresses = accounts.keys().copied().collect::<Vec<_>>();
         for (address, (amount, frozen, close_authority)) in accounts {
             let is_associated = address == associated_token_account;
 
@@ -2579,6 +2770,62 @@ async fn command_gc(
                 });
             };
         }
+
+        // shrink the accounts left open by removing their disabled or empty
+        // extensions, which requires knowing which accounts were closed
+        if config.sign_only {
+            continue;
+        }
+        let mint_state = token.get_mint_info().await?;
+        let required_extension_types =
+            ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types()?);
+        for address in addresses {
+            let account = match config
+                .rpc_client
+                .get_account_with_commitment(&address, config.rpc_client.commitment())
+                .await?
+                .value
+            {
+                Some(account) => account,
+                None => continue,
+            };
+            let account_state = StateWithExtensionsOwned::<Account>::unpack(account.data)?;
+            let removable_extension_types = account_state
+                .get_extension_types()?
+                .into_iter()
+                .filter(|extension_type| {
+                    !required_extension_types.contains(extension_type)
+                        && check_account_extension_removable(&account_state, *extension_type)
+                            .is_ok()
+                })
+                .collect::<Vec<_>>();
+            if removable_extension_types.is_empty() {
+                continue;
+            }
+
+            println_display(
+                config,
+                format!("Removing unused extensions from account {}", address),
+            );
+            let res = token
+                .remove_account_extensions(
+                    &address,
+                    &owner,
+                    &removable_extension_types,
+                    &bulk_signers,
+                )
+                .await?;
+            let tx_return = finish_tx(config, &res, false).await?;
+
+            results.push(match tx_return {
+                TransactionReturnData::CliSignature(signature) => {
+                    config.output_format.formatted_string(&signature)
+                }
+                TransactionReturnData::CliSignOnlyData(sign_only_data) => {
+                    config.output_format.formatted_string(&sign_only_data)
+                }
+            });
+        }
     }
 
     Ok(results.join(""))
@@ -2719,13 +2966,15 @@ async fn command_required_transfer_memos(
     })
 }
 
-// both enables and disables cpi guard, via enable_guard bool
+// both enables and disables cpi guard, via enable_guard bool, optionally
+// replacing the allowlist when enabling
 async fn command_cpi_guard(
     config: &Config<'_>,
     token_account_address: Pubkey,
     owner: Pubkey,
     bulk_signers: BulkSigners,
     enable_guard: bool,
+    allowed_programs: Option<Vec<Pubkey>>,
 ) -> CommandResult {
     if config.sign_only {
         panic!("Config can not be sign-only for enabling/disabling required transfer memos.");
@@ -2745,7 +2994,7 @@ async fn command_cpi_guard(
             .lock_cpi
             .into();
 
-        if extension_state == enable_guard {
+        if extension_state == enable_guard && allowed_programs.is_none() {
             return Ok(format!(
                 "CPI Guard was already {}",
                 if extension_state {
@@ -2771,6 +3020,18 @@ async fn command_cpi_guard(
         }
     }
 
+    if let Some(allowed_programs) = allowed_programs {
+        let res = token
+            .set_cpi_guard_allowlist(
+                &token_account_address,
+                &owner,
+                &allowed_programs,
+                &bulk_signers,
+            )
+            .await?;
+        finish_tx(config, &res, false).await?;
+    }
+
     let res = if enable_guard {
         token
             .enable_cpi_guard(&token_account_address, &owner, &bulk_signers)
@@ -2837,6 +3098,37 @@ async fn command_update_pointer_address(
     })
 }
 
+async fn command_add_mint_extension(
+    config: &Config<'_>,
+    token_pubkey: Pubkey,
+    mint_authority: Pubkey,
+    extension_types: Vec<ExtensionType>,
+    bulk_signers: BulkSigners,
+) -> CommandResult {
+    println_display(
+        config,
+        format!(
+            "Adding extensions {:?} to mint {}",
+            extension_types, token_pubkey
+        ),
+    );
+
+    let token = token_client_from_config(config, &token_pubkey, None)?;
+    let res = token
+        .reallocate_mint(&mint_authority, &extension_types, &bulk_signers)
+        .await?;
+
+    let tx_return = finish_tx(config, &res, false).await?;
+    Ok(match tx_return {
+        TransactionReturnData::CliSignature(signature) => {
+            config.output_format.formatted_string(&signature)
+        }
+        TransactionReturnData::CliSignOnlyData(sign_only_data) => {
+            config.output_format.formatted_string(&sign_only_data)
+        }
+    })
+}
+
 async fn command_update_default_account_state(
     config: &Config<'_>,
     token_pubkey: Pubkey,
@@ -3579,6 +3871,7 @@ pub async fn process_command<'a>(
             let metadata_address = value_t!(arg_matches, "metadata_address", Pubkey).ok();
             let group_address = value_t!(arg_matches, "group_address", Pubkey).ok();
             let member_address = value_t!(arg_matches, "member_address", Pubkey).ok();
+            let recovery_authority = value_t!(arg_matches, "recovery_authority", Pubkey).ok();
 
             let transfer_fee = arg_matches.values_of("transfer_fee").map(|mut v| {
                 println_display(config,"transfer-fee has been deprecated and will be removed in a future release. Please specify --transfer-fee-basis-points and --transfer-fee-maximum-fee with a UI amount".to_string());
@@ -3629,6 +3922,7 @@ pub async fn process_command<'a>(
                 arg_matches.is_present("enable_freeze"),
                 arg_matches.is_present("enable_close"),
                 arg_matches.is_present("enable_non_transferable"),
+                recovery_authority,
                 arg_matches.is_present("enable_permanent_delegate"),
                 memo,
                 metadata_address,
@@ -3725,7 +4019,17 @@ pub async fn process_command<'a>(
             let transfer_lamports = arg_matches
                 .get_one::<u64>(TRANSFER_LAMPORTS_ARG.name)
                 .copied();
-            let bulk_signers = vec![authority_signer];
+            let mut bulk_signers = vec![authority_signer];
+            let refund = if let Some(refund_destination) =
+                pubkey_of_signer(arg_matches, "refund_destination", &mut wallet_manager).unwrap()
+            {
+                let (mint_authority_signer, mint_authority) =
+                    config.signer_or_default(arg_matches, "mint_authority", &mut wallet_manager);
+                push_signer_with_dedup(mint_authority_signer, &mut bulk_signers);
+                Some((refund_destination, mint_authority))
+            } else {
+                None
+            };
 
             command_update_metadata(
                 config,
@@ -3734,6 +4038,7 @@ pub async fn process_command<'a>(
                 field,
                 value,
                 transfer_lamports,
+                refund,
                 bulk_signers,
             )
             .await
@@ -3832,24 +4137,53 @@ pub async fn process_command<'a>(
         (CommandName::CreateMultisig, arg_matches) => {
             let minimum_signers = arg_matches
                 .get_one("minimum_signers")
-                .map(|v: &String| v.parse::<u8>().unwrap())
+                .map(|v: &String| v.parse::<u16>().unwrap())
                 .unwrap();
             let multisig_members =
                 pubkeys_of_multiple_signers(arg_matches, "multisig_member", &mut wallet_manager)
                     .unwrap_or_else(print_error_and_exit)
                     .unwrap();
-            if minimum_signers as usize > multisig_members.len() {
-                eprintln!(
-                    "error: MINIMUM_SIGNERS cannot be greater than the number \
-                          of MULTISIG_MEMBERs passed"
-                );
-                exit(1);
+            let weights = arg_matches
+                .get_many::<u8>("weights")
+                .map(|weights| weights.copied().collect::<Vec<_>>());
+            if let Some(weights) = &weights {
+                if weights.len() != multisig_members.len() {
+                    eprintln!(
+                        "error: the number of WEIGHTs must match the number \
+                              of MULTISIG_MEMBERs passed"
+                    );
+                    exit(1);
+                }
+                let total_weight: u16 = weights.iter().map(|weight| *weight as u16).sum();
+                if minimum_signers > total_weight {
+                    eprintln!(
+                        "error: MINIMUM_SIGNERS cannot be greater than the total \
+                              WEIGHT of the MULTISIG_MEMBERs passed"
+                    );
+                    exit(1);
+                }
+            } else {
+                if multisig_members.len() > MAX_SIGNERS {
+                    eprintln!(
+                        "error: at most {} MULTISIG_MEMBERs can be passed without --weights",
+                        MAX_SIGNERS
+                    );
+                    exit(1);
+                }
+                if minimum_signers as usize > multisig_members.len() {
+                    eprintln!(
+                        "error: MINIMUM_SIGNERS cannot be greater than the number \
+                              of MULTISIG_MEMBERs passed"
+                    );
+                    exit(1);
+                }
             }
 
             let (signer, _) = get_signer(arg_matches, "address_keypair", &mut wallet_manager)
                 .unwrap_or_else(new_throwaway_signer);
 
-            command_create_multisig(config, signer, minimum_signers, multisig_members).await
+            command_create_multisig(config, signer, minimum_signers, multisig_members, weights)
+                .await
         }
         (CommandName::Authorize, arg_matches) => {
             let address = pubkey_of_signer(arg_matches, "address", &mut wallet_manager)
@@ -3991,6 +4325,31 @@ pub async fn process_command<'a>(
             )
             .await
         }
+        (CommandName::Recover, arg_matches) => {
+            let source = pubkey_of_signer(arg_matches, "source", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let destination = pubkey_of_signer(arg_matches, "destination", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+
+            let (recovery_authority_signer, recovery_authority) =
+                config.signer_or_default(arg_matches, "recovery_authority", &mut wallet_manager);
+            if config.multisigner_pubkeys.is_empty() {
+                push_signer_with_dedup(recovery_authority_signer, &mut bulk_signers);
+            }
+
+            let amount = *arg_matches.get_one::<Amount>("amount").unwrap();
+            command_recover(
+                config,
+                source,
+                destination,
+                recovery_authority,
+                amount,
+                bulk_signers,
+            )
+            .await
+        }
         (CommandName::Mint, arg_matches) => {
             let (mint_authority_signer, mint_authority) =
                 config.signer_or_default(arg_matches, "mint_authority", &mut wallet_manager);
@@ -4131,6 +4490,7 @@ pub async fn process_command<'a>(
                 .get_one(MINT_DECIMALS_ARG.name)
                 .map(|v: &String| v.parse::<u8>().unwrap());
             let use_unchecked_instruction = arg_matches.is_present("use_unchecked_instruction");
+            let expires_at = arg_matches.get_one::<i64>("expires_at").copied();
             command_approve(
                 config,
                 account,
@@ -4140,6 +4500,7 @@ pub async fn process_command<'a>(
                 mint_address,
                 mint_decimals,
                 use_unchecked_instruction,
+                expires_at,
                 bulk_signers,
             )
             .await
@@ -4320,7 +4681,24 @@ pub async fn process_command<'a>(
             // Since account is required argument it will always be present
             let token_account =
                 config.pubkey_or_default(arg_matches, "account", &mut wallet_manager)?;
-            command_cpi_guard(config, token_account, owner, bulk_signers, true).await
+            let allowed_programs = if arg_matches.is_present("allow_program") {
+                Some(
+                    pubkeys_of_multiple_signers(arg_matches, "allow_program", &mut wallet_manager)
+                        .unwrap_or_else(print_error_and_exit)
+                        .unwrap_or_default(),
+                )
+            } else {
+                None
+            };
+            command_cpi_guard(
+                config,
+                token_account,
+                owner,
+                bulk_signers,
+                true,
+                allowed_programs,
+            )
+            .await
         }
         (CommandName::DisableCpiGuard, arg_matches) => {
             let (owner_signer, owner) =
@@ -4331,7 +4709,7 @@ pub async fn process_command<'a>(
             // Since account is required argument it will always be present
             let token_account =
                 config.pubkey_or_default(arg_matches, "account", &mut wallet_manager)?;
-            command_cpi_guard(config, token_account, owner, bulk_signers, false).await
+            command_cpi_guard(config, token_account, owner, bulk_signers, false, None).await
         }
         (CommandName::UpdateDefaultAccountState, arg_matches) => {
             // Since account is required argument it will always be present
@@ -4427,6 +4805,26 @@ pub async fn process_command<'a>(
             )
             .await
         }
+        (CommandName::AddMintExtension, arg_matches) => {
+            // Since account is required argument it will always be present
+            let token = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+
+            let (mint_authority_signer, mint_authority) =
+                config.signer_or_default(arg_matches, "mint_authority", &mut wallet_manager);
+            if config.multisigner_pubkeys.is_empty() {
+                push_signer_with_dedup(mint_authority_signer, &mut bulk_signers);
+            }
+            let extension_types = arg_matches
+                .values_of("extensions")
+                .unwrap()
+                .map(|extension| CliMintExtension::from_str(extension).unwrap().into())
+                .collect::<Vec<_>>();
+
+            command_add_mint_extension(config, token, mint_authority, extension_types, bulk_signers)
+                .await
+        }
         (CommandName::WithdrawWithheldTokens, arg_matches) => {
             let (authority_signer, authority) = config.signer_or_default(
                 arg_matches,
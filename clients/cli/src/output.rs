@@ -188,6 +188,53 @@ impl fmt::Display for CliMultisig {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliWeightedMultisig {
+    pub(crate) address: String,
+    pub(crate) program_id: String,
+    pub(crate) threshold: u16,
+    pub(crate) total_weight: u16,
+    pub(crate) signers: Vec<CliWeightedMultisigSigner>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliWeightedMultisigSigner {
+    pub(crate) pubkey: String,
+    pub(crate) weight: u8,
+}
+
+impl QuietDisplay for CliWeightedMultisig {}
+impl VerboseDisplay for CliWeightedMultisig {}
+
+impl fmt::Display for CliWeightedMultisig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let n = self.signers.len();
+
+        writeln!(f)?;
+        writeln!(f, "{}", style("SPL Token Weighted Multisig").bold())?;
+        writeln_name_value(f, "  Address:", &self.address)?;
+        writeln_name_value(f, "  Program:", &self.program_id)?;
+        writeln_name_value(
+            f,
+            "  Threshold:",
+            &format!("{}/{}", self.threshold, self.total_weight),
+        )?;
+        writeln!(f, "  {}", style("Signers:").bold())?;
+        let width = if n >= 9 { 4 } else { 3 };
+        for (i, signer) in self.signers.iter().enumerate() {
+            let title = format!("  {1:>0$}:", width, i + 1);
+            writeln_name_value(
+                f,
+                &title,
+                &format!("{}  (weight {})", signer.pubkey, signer.weight),
+            )?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct CliTokenAccount {
@@ -20,7 +20,7 @@ use {
         extension::{
             confidential_transfer::{ConfidentialTransferAccount, ConfidentialTransferMint},
             confidential_transfer_fee::ConfidentialTransferFeeConfig,
-            cpi_guard::CpiGuard,
+            cpi_guard::{CpiGuard, CpiGuardAllowlist},
             default_account_state::DefaultAccountState,
             group_member_pointer::GroupMemberPointer,
             group_pointer::GroupPointer,
@@ -2053,6 +2053,33 @@ async fn cpi_guard(test_validator: &TestValidator, payer: &Keypair) {
     let enabled: bool = cpi_guard.lock_cpi.into();
     assert!(enabled);
 
+    // enable with an allowlist works on an enabled account
+    let allowed_program = Pubkey::new_unique();
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::EnableCpiGuard.into(),
+            &token_account.to_string(),
+            "--allow-program",
+            &allowed_program.to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+    let extensions = StateWithExtensionsOwned::<Account>::unpack(
+        config
+            .rpc_client
+            .get_account(&token_account)
+            .await
+            .unwrap()
+            .data,
+    )
+    .unwrap();
+    let allowlist = extensions.get_extension::<CpiGuardAllowlist>().unwrap();
+    assert!(allowlist.contains(&allowed_program));
+
     // disable works
     process_test_command(
         &config,
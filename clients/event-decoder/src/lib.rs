@@ -0,0 +1,542 @@
+#![deny(missing_docs)]
+
+//! Decoder for the structured events logged by the Token-2022 program
+//!
+//! Events are only meaningful for successful transactions: a failed
+//! transaction may still log events for state changes that were rolled back.
+
+use {
+    base64::{engine::general_purpose::STANDARD, Engine},
+    spl_token_2022::{
+        event::{
+            BurnEvent, ConfidentialBurnEvent, ConfidentialDepositEvent, ConfidentialMintEvent,
+            ConfidentialWithdrawEvent, Event, EventType, FreezeAccountEvent,
+            HarvestWithheldTokensEvent, MintToEvent, MultiDelegateApproveEvent,
+            MultiDelegateRevokeEvent, SetAuthorityEvent, SweepWithheldTokensEvent,
+            ThawAccountEvent, TransferEvent, UpdateMultiplierEvent, UpdatePauseStateEvent,
+            UpdateRateEvent, VestingLockEvent, WithdrawWithheldTokensEvent, EVENT_HEADER_LEN,
+        },
+        solana_program::pubkey::Pubkey,
+    },
+    std::mem::size_of,
+    thiserror::Error,
+};
+
+/// Prefix of the log messages written by `sol_log_data`
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Errors that may be returned while decoding events
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum EventDecodeError {
+    /// The event header or body is missing
+    #[error("Event is missing its header or body")]
+    MissingField,
+    /// A field is not valid base64
+    #[error("Event field is not valid base64")]
+    InvalidBase64,
+    /// The event header is malformed
+    #[error("Event header is malformed")]
+    InvalidHeader,
+    /// The event type is not known to this decoder
+    #[error("Unknown event type {0}")]
+    UnknownEventType(u8),
+    /// The event version is not supported
+    #[error("Unsupported event version {0}")]
+    UnsupportedVersion(u8),
+    /// The event body is shorter than its type requires
+    #[error("Event body is too short")]
+    InvalidLength,
+}
+
+/// Event logged by the Token-2022 program
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenEvent {
+    /// Tokens were minted to an account
+    MintTo(MintToEvent),
+    /// Tokens were burned from an account
+    Burn(BurnEvent),
+    /// Tokens were moved between accounts
+    Transfer(TransferEvent),
+    /// An authority was changed
+    SetAuthority(SetAuthorityEvent),
+    /// An account was frozen
+    FreezeAccount(FreezeAccountEvent),
+    /// An account was thawed
+    ThawAccount(ThawAccountEvent),
+    /// The pause state of a mint changed
+    UpdatePauseState(UpdatePauseStateEvent),
+    /// The UI amount multiplier of a mint changed
+    UpdateMultiplier(UpdateMultiplierEvent),
+    /// The interest rate of a mint changed
+    UpdateRate(UpdateRateEvent),
+    /// Tokens were deposited into a confidential pending balance
+    ConfidentialDeposit(ConfidentialDepositEvent),
+    /// Tokens were withdrawn from a confidential available balance
+    ConfidentialWithdraw(ConfidentialWithdrawEvent),
+    /// Tokens were minted confidentially
+    ConfidentialMint(ConfidentialMintEvent),
+    /// Tokens were burned confidentially
+    ConfidentialBurn(ConfidentialBurnEvent),
+    /// Withheld transfer fees were harvested to the mint
+    HarvestWithheldTokens(HarvestWithheldTokensEvent),
+    /// Withheld transfer fees were withdrawn
+    WithdrawWithheldTokens(WithdrawWithheldTokensEvent),
+    /// Withheld transfer fees were swept to the fee recipient
+    SweepWithheldTokens(SweepWithheldTokensEvent),
+    /// Tokens in an account were locked under a vesting schedule
+    VestingLock(VestingLockEvent),
+    /// A delegate was approved in an account's delegate table
+    MultiDelegateApprove(MultiDelegateApproveEvent),
+    /// A delegate was removed from an account's delegate table
+    MultiDelegateRevoke(MultiDelegateRevokeEvent),
+}
+
+/// Decode the body of an event. Newer versions only append fields, so any
+/// trailing bytes are ignored.
+fn decode_body<E: Event>(body: &[u8]) -> Result<E, EventDecodeError> {
+    body.get(..size_of::<E>())
+        .map(bytemuck::pod_read_unaligned)
+        .ok_or(EventDecodeError::InvalidLength)
+}
+
+/// Decode an event from the raw header and body fields passed to
+/// `sol_log_data`
+pub fn decode_event(header: &[u8], body: &[u8]) -> Result<TokenEvent, EventDecodeError> {
+    if header.len() != EVENT_HEADER_LEN {
+        return Err(EventDecodeError::InvalidHeader);
+    }
+    let event_type = EventType::try_from(header[0])
+        .map_err(|_| EventDecodeError::UnknownEventType(header[0]))?;
+    if header[1] == 0 {
+        return Err(EventDecodeError::UnsupportedVersion(header[1]));
+    }
+    Ok(match event_type {
+        EventType::MintTo => TokenEvent::MintTo(decode_body(body)?),
+        EventType::Burn => TokenEvent::Burn(decode_body(body)?),
+        EventType::Transfer => TokenEvent::Transfer(decode_body(body)?),
+        EventType::SetAuthority => TokenEvent::SetAuthority(decode_body(body)?),
+        EventType::FreezeAccount => TokenEvent::FreezeAccount(decode_body(body)?),
+        EventType::ThawAccount => TokenEvent::ThawAccount(decode_body(body)?),
+        EventType::UpdatePauseState => TokenEvent::UpdatePauseState(decode_body(body)?),
+        EventType::UpdateMultiplier => TokenEvent::UpdateMultiplier(decode_body(body)?),
+        EventType::UpdateRate => TokenEvent::UpdateRate(decode_body(body)?),
+        EventType::ConfidentialDeposit => TokenEvent::ConfidentialDeposit(decode_body(body)?),
+        EventType::ConfidentialWithdraw => TokenEvent::ConfidentialWithdraw(decode_body(body)?),
+        EventType::ConfidentialMint => TokenEvent::ConfidentialMint(decode_body(body)?),
+        EventType::ConfidentialBurn => TokenEvent::ConfidentialBurn(decode_body(body)?),
+        EventType::HarvestWithheldTokens => TokenEvent::HarvestWithheldTokens(decode_body(body)?),
+        EventType::WithdrawWithheldTokens => TokenEvent::WithdrawWithheldTokens(decode_body(body)?),
+        EventType::SweepWithheldTokens => TokenEvent::SweepWithheldTokens(decode_body(body)?),
+        EventType::VestingLock => TokenEvent::VestingLock(decode_body(body)?),
+        EventType::MultiDelegateApprove => TokenEvent::MultiDelegateApprove(decode_body(body)?),
+        EventType::MultiDelegateRevoke => TokenEvent::MultiDelegateRevoke(decode_body(body)?),
+    })
+}
+
+/// Decode an event from the base64 fields of a `Program data:` log message,
+/// without the prefix
+pub fn decode_program_data(data: &str) -> Result<TokenEvent, EventDecodeError> {
+    let mut fields = data.split(' ').map(|field| {
+        STANDARD
+            .decode(field)
+            .map_err(|_| EventDecodeError::InvalidBase64)
+    });
+    let header = fields.next().ok_or(EventDecodeError::MissingField)??;
+    let body = fields.next().ok_or(EventDecodeError::MissingField)??;
+    decode_event(&header, &body)
+}
+
+/// Decode all events logged by the given token program in a transaction's
+/// log messages, in order.
+///
+/// Data logged by other programs, including those invoked by the token
+/// program such as transfer hooks, is skipped.
+pub fn parse_logs<S: AsRef<str>>(
+    program_id: &Pubkey,
+    logs: &[S],
+) -> Result<Vec<TokenEvent>, EventDecodeError> {
+    let program_id = program_id.to_string();
+    let mut invoke_stack = vec![];
+    let mut events = vec![];
+    for log in logs {
+        let log = log.as_ref();
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if invoke_stack.last() == Some(&program_id.as_str()) {
+                events.push(decode_program_data(data)?);
+            }
+        } else if let Some(message) = log.strip_prefix("Program ") {
+            let mut words = message.split(' ');
+            match (words.next(), words.next()) {
+                (Some(invoked_program_id), Some("invoke")) => invoke_stack.push(invoked_program_id),
+                (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                    invoke_stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        ethnum::U256,
+        spl_token_2022::{
+            event::{encode, EVENT_VERSION},
+            instruction::AuthorityType,
+            pod::PodCOption,
+        },
+    };
+
+    fn program_data<E: Event>(event: &E) -> String {
+        let (header, body) = encode(event);
+        format!(
+            "{}{} {}",
+            PROGRAM_DATA_PREFIX,
+            STANDARD.encode(header),
+            STANDARD.encode(body)
+        )
+    }
+
+    /// Check that the fields logged by the program for `event` decode back to
+    /// `expected`, both raw and from the log message
+    fn assert_round_trip<E: Event>(event: &E, expected: TokenEvent) {
+        let (header, body) = encode(event);
+        assert_eq!(decode_event(&header, body), Ok(expected));
+        let data = program_data(event);
+        assert_eq!(
+            decode_program_data(data.strip_prefix(PROGRAM_DATA_PREFIX).unwrap()),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn mint_to_round_trip() {
+        let event = MintToEvent {
+            mint: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            amount: U256::new(10).into(),
+            supply: (U256::from(u64::MAX) + 10).into(),
+        };
+        assert_round_trip(&event, TokenEvent::MintTo(event));
+    }
+
+    #[test]
+    fn burn_round_trip() {
+        let event = BurnEvent {
+            mint: Pubkey::new_unique(),
+            source: Pubkey::new_unique(),
+            amount: U256::new(4).into(),
+            supply: U256::new(6).into(),
+        };
+        assert_round_trip(&event, TokenEvent::Burn(event));
+    }
+
+    #[test]
+    fn transfer_round_trip() {
+        let event = TransferEvent {
+            mint: Pubkey::new_unique(),
+            source: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            amount: U256::new(100).into(),
+            fee: U256::new(1).into(),
+            recovery: false.into(),
+        };
+        assert_round_trip(&event, TokenEvent::Transfer(event));
+    }
+
+    #[test]
+    fn recovery_transfer_round_trip() {
+        let event = TransferEvent {
+            mint: Pubkey::new_unique(),
+            source: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            amount: U256::new(100).into(),
+            fee: U256::ZERO.into(),
+            recovery: true.into(),
+        };
+        assert_round_trip(&event, TokenEvent::Transfer(event));
+    }
+
+    #[test]
+    fn set_authority_round_trip() {
+        let event = SetAuthorityEvent {
+            account: Pubkey::new_unique(),
+            authority_type: AuthorityType::RecoveryAuthority as u8,
+            new_authority: PodCOption::some(Pubkey::new_unique()),
+        };
+        assert_round_trip(&event, TokenEvent::SetAuthority(event));
+    }
+
+    #[test]
+    fn freeze_account_round_trip() {
+        let event = FreezeAccountEvent {
+            mint: Pubkey::new_unique(),
+            account: Pubkey::new_unique(),
+        };
+        assert_round_trip(&event, TokenEvent::FreezeAccount(event));
+    }
+
+    #[test]
+    fn thaw_account_round_trip() {
+        let event = ThawAccountEvent {
+            mint: Pubkey::new_unique(),
+            account: Pubkey::new_unique(),
+        };
+        assert_round_trip(&event, TokenEvent::ThawAccount(event));
+    }
+
+    #[test]
+    fn update_pause_state_round_trip() {
+        let event = UpdatePauseStateEvent {
+            mint: Pubkey::new_unique(),
+            mint_paused: true.into(),
+            burn_paused: false.into(),
+            transfer_paused: true.into(),
+            scheduled_pause_timestamp: 0.into(),
+            scheduled_resume_timestamp: 1_700_000_000.into(),
+        };
+        assert_round_trip(&event, TokenEvent::UpdatePauseState(event));
+    }
+
+    #[test]
+    fn update_multiplier_round_trip() {
+        let event = UpdateMultiplierEvent {
+            mint: Pubkey::new_unique(),
+            multiplier: 1.0.into(),
+            new_multiplier: 2.5.into(),
+            effective_timestamp: 1_700_000_000.into(),
+        };
+        assert_round_trip(&event, TokenEvent::UpdateMultiplier(event));
+    }
+
+    #[test]
+    fn update_rate_round_trip() {
+        let event = UpdateRateEvent {
+            mint: Pubkey::new_unique(),
+            current_rate: 500.into(),
+            pre_update_average_rate: (-100).into(),
+            timestamp: 1_700_000_000.into(),
+        };
+        assert_round_trip(&event, TokenEvent::UpdateRate(event));
+    }
+
+    #[test]
+    fn confidential_deposit_round_trip() {
+        let event = ConfidentialDepositEvent {
+            mint: Pubkey::new_unique(),
+            account: Pubkey::new_unique(),
+            amount: U256::new(42).into(),
+            pending_balance_credit_counter: 3.into(),
+        };
+        assert_round_trip(&event, TokenEvent::ConfidentialDeposit(event));
+    }
+
+    #[test]
+    fn confidential_withdraw_round_trip() {
+        let event = ConfidentialWithdrawEvent {
+            mint: Pubkey::new_unique(),
+            account: Pubkey::new_unique(),
+            amount: U256::new(42).into(),
+        };
+        assert_round_trip(&event, TokenEvent::ConfidentialWithdraw(event));
+    }
+
+    #[test]
+    fn confidential_mint_round_trip() {
+        let event = ConfidentialMintEvent {
+            mint: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            pending_balance_credit_counter: 1.into(),
+        };
+        assert_round_trip(&event, TokenEvent::ConfidentialMint(event));
+    }
+
+    #[test]
+    fn confidential_burn_round_trip() {
+        let event = ConfidentialBurnEvent {
+            mint: Pubkey::new_unique(),
+            source: Pubkey::new_unique(),
+        };
+        assert_round_trip(&event, TokenEvent::ConfidentialBurn(event));
+    }
+
+    #[test]
+    fn harvest_withheld_tokens_round_trip() {
+        let event = HarvestWithheldTokensEvent {
+            mint: Pubkey::new_unique(),
+            account: Pubkey::new_unique(),
+            amount: U256::new(7).into(),
+        };
+        assert_round_trip(&event, TokenEvent::HarvestWithheldTokens(event));
+    }
+
+    #[test]
+    fn withdraw_withheld_tokens_round_trip() {
+        let mint = Pubkey::new_unique();
+        let event = WithdrawWithheldTokensEvent {
+            mint,
+            source: mint,
+            destination: Pubkey::new_unique(),
+            amount: U256::new(7).into(),
+        };
+        assert_round_trip(&event, TokenEvent::WithdrawWithheldTokens(event));
+    }
+
+    #[test]
+    fn sweep_withheld_tokens_round_trip() {
+        let event = SweepWithheldTokensEvent {
+            mint: Pubkey::new_unique(),
+            fee_recipient: Pubkey::new_unique(),
+            amount: U256::new(7).into(),
+        };
+        assert_round_trip(&event, TokenEvent::SweepWithheldTokens(event));
+    }
+
+    #[test]
+    fn vesting_lock_round_trip() {
+        let event = VestingLockEvent {
+            mint: Pubkey::new_unique(),
+            account: Pubkey::new_unique(),
+            locked_total: U256::new(1_000).into(),
+            start_timestamp: 1_700_000_000.into(),
+            cliff_timestamp: 1_710_000_000.into(),
+            end_timestamp: 1_720_000_000.into(),
+        };
+        assert_round_trip(&event, TokenEvent::VestingLock(event));
+    }
+
+    #[test]
+    fn multi_delegate_approve_round_trip() {
+        let event = MultiDelegateApproveEvent {
+            account: Pubkey::new_unique(),
+            delegate: Pubkey::new_unique(),
+            allowance: U256::new(50).into(),
+            expires_at: 0.into(),
+        };
+        assert_round_trip(&event, TokenEvent::MultiDelegateApprove(event));
+    }
+
+    #[test]
+    fn multi_delegate_revoke_round_trip() {
+        let event = MultiDelegateRevokeEvent {
+            account: Pubkey::new_unique(),
+            delegate: Pubkey::new_unique(),
+        };
+        assert_round_trip(&event, TokenEvent::MultiDelegateRevoke(event));
+    }
+
+    #[test]
+    fn decode_event_round_trip() {
+        let transfer = TransferEvent {
+            mint: Pubkey::new_unique(),
+            source: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            amount: U256::new(100).into(),
+            fee: U256::new(1).into(),
+            recovery: false.into(),
+        };
+        let data = program_data(&transfer);
+        assert_eq!(
+            decode_program_data(data.strip_prefix(PROGRAM_DATA_PREFIX).unwrap()),
+            Ok(TokenEvent::Transfer(transfer))
+        );
+
+        let set_authority = SetAuthorityEvent {
+            account: Pubkey::new_unique(),
+            authority_type: AuthorityType::MintTokens as u8,
+            new_authority: PodCOption::none(),
+        };
+        assert_eq!(
+            decode_event(
+                &[EventType::SetAuthority.into(), EVENT_VERSION],
+                bytemuck::bytes_of(&set_authority)
+            ),
+            Ok(TokenEvent::SetAuthority(set_authority))
+        );
+    }
+
+    #[test]
+    fn decode_event_versions() {
+        let freeze = FreezeAccountEvent {
+            mint: Pubkey::new_unique(),
+            account: Pubkey::new_unique(),
+        };
+        let body = bytemuck::bytes_of(&freeze);
+
+        // a newer version with appended fields decodes the known prefix
+        let mut extended_body = body.to_vec();
+        extended_body.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(
+            decode_event(
+                &[EventType::FreezeAccount.into(), EVENT_VERSION + 1],
+                &extended_body
+            ),
+            Ok(TokenEvent::FreezeAccount(freeze))
+        );
+
+        assert_eq!(
+            decode_event(&[EventType::FreezeAccount.into(), 0], body),
+            Err(EventDecodeError::UnsupportedVersion(0))
+        );
+        assert_eq!(
+            decode_event(&[u8::MAX, EVENT_VERSION], body),
+            Err(EventDecodeError::UnknownEventType(u8::MAX))
+        );
+        assert_eq!(
+            decode_event(
+                &[EventType::FreezeAccount.into(), EVENT_VERSION],
+                &body[..body.len() - 1]
+            ),
+            Err(EventDecodeError::InvalidLength)
+        );
+        assert_eq!(
+            decode_event(&[EventType::FreezeAccount.into()], body),
+            Err(EventDecodeError::InvalidHeader)
+        );
+    }
+
+    #[test]
+    fn parse_logs_skips_other_programs() {
+        let token_program_id = spl_token_2022::id();
+        let hook_program_id = Pubkey::new_unique();
+        let mint_to = MintToEvent {
+            mint: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            amount: U256::new(10).into(),
+            supply: U256::new(10).into(),
+        };
+        let burn = BurnEvent {
+            mint: mint_to.mint,
+            source: mint_to.destination,
+            amount: U256::new(4).into(),
+            supply: U256::new(6).into(),
+        };
+        let logs = vec![
+            format!("Program {} invoke [1]", token_program_id),
+            "Program log: Instruction: MintTo".to_string(),
+            program_data(&mint_to),
+            format!("Program {} invoke [2]", hook_program_id),
+            // looks like an event, but logged by the hook
+            program_data(&burn),
+            format!("Program {} success", hook_program_id),
+            format!(
+                "Program {} consumed 5000 of 200000 compute units",
+                token_program_id
+            ),
+            format!("Program {} success", token_program_id),
+            format!("Program {} invoke [1]", token_program_id),
+            program_data(&burn),
+            format!("Program {} success", token_program_id),
+        ];
+        assert_eq!(
+            parse_logs(&token_program_id, &logs),
+            Ok(vec![TokenEvent::MintTo(mint_to), TokenEvent::Burn(burn)])
+        );
+    }
+}
@@ -8,7 +8,8 @@ use {
     futures_util::TryFutureExt,
     solana_program_test::tokio::time,
     solana_sdk::{
-        account::Account as BaseAccount,
+        account::{from_account, Account as BaseAccount},
+        clock::{Clock, Epoch, UnixTimestamp},
         compute_budget::ComputeBudgetInstruction,
         hash::Hash,
         instruction::{AccountMeta, Instruction},
@@ -19,7 +20,7 @@ use {
         pubkey::Pubkey,
         signature::Signature,
         signer::{signers::Signers, Signer, SignerError},
-        system_instruction,
+        system_instruction, sysvar,
         transaction::Transaction,
     },
     spl_associated_token_account_client::{
@@ -43,12 +44,19 @@ use {
                 self, account_info::WithheldTokensInfo, ConfidentialTransferFeeAmount,
                 ConfidentialTransferFeeConfig,
             },
-            cpi_guard, default_account_state, group_member_pointer, group_pointer,
-            interest_bearing_mint, memo_transfer, metadata_pointer, pausable, scaled_ui_amount,
-            transfer_fee, transfer_hook, BaseStateWithExtensions, Extension, ExtensionType,
-            StateWithExtensionsOwned,
+            cpi_guard, default_account_state, delegate_expiry, group_member_pointer, group_pointer,
+            interest_bearing_mint,
+            memo_transfer::{self, MemoRules},
+            metadata_pointer, multi_delegate, non_transferable,
+            pausable::{self, PausableOperation},
+            permanent_delegate::{self, PermanentDelegateOperations},
+            scaled_ui_amount, supply_cap, supply_hook, token_group,
+            transfer_fee::{self, TransferFeeConfig, TransferFeeTier, TransferFeeTiers},
+            transfer_hook, transfer_limit, vesting, BaseStateWithExtensions, Extension,
+            ExtensionType, StateWithExtensionsOwned,
         },
         instruction, offchain,
+        pod::PodWeightedMultisig,
         solana_zk_sdk::{
             encryption::{
                 auth_encryption::AeKey,
@@ -62,7 +70,8 @@ use {
                 state::ProofContextState,
             },
         },
-        state::{Account, AccountState, Mint, Multisig},
+        state::{Account, AccountState, Mint, Multisig, PackedSizeOf},
+        ui_amount,
     },
     spl_token_confidential_transfer_proof_extraction::instruction::{
         zk_proof_type_to_instruction, ProofData, ProofLocation,
@@ -170,6 +179,9 @@ pub enum ExtensionInitializationParams {
     PermanentDelegate {
         delegate: Pubkey,
     },
+    PermanentDelegateScope {
+        scope: PermanentDelegateOperations,
+    },
     TransferHook {
         authority: Option<Pubkey>,
         program_id: Option<Pubkey>,
@@ -197,6 +209,27 @@ pub enum ExtensionInitializationParams {
     PausableConfig {
         authority: Pubkey,
     },
+    PausableOperations,
+    PauseSchedule,
+    TransferFeeTiers,
+    TransferFeeRecipient,
+    SupplyCap {
+        authority: Option<Pubkey>,
+        max_supply: U256,
+    },
+    Vesting {
+        authority: Option<Pubkey>,
+    },
+    SupplyHook {
+        authority: Option<Pubkey>,
+        program_id: Option<Pubkey>,
+    },
+    NonTransferableRecovery {
+        authority: Option<Pubkey>,
+    },
+    TransferLimitPolicy {
+        authority: Option<Pubkey>,
+    },
 }
 impl ExtensionInitializationParams {
     /// Get the extension type associated with the init params
@@ -209,6 +242,7 @@ impl ExtensionInitializationParams {
             Self::InterestBearingConfig { .. } => ExtensionType::InterestBearingConfig,
             Self::NonTransferable => ExtensionType::NonTransferable,
             Self::PermanentDelegate { .. } => ExtensionType::PermanentDelegate,
+            Self::PermanentDelegateScope { .. } => ExtensionType::PermanentDelegateScope,
             Self::TransferHook { .. } => ExtensionType::TransferHook,
             Self::MetadataPointer { .. } => ExtensionType::MetadataPointer,
             Self::ConfidentialTransferFeeConfig { .. } => {
@@ -218,6 +252,15 @@ impl ExtensionInitializationParams {
             Self::GroupMemberPointer { .. } => ExtensionType::GroupMemberPointer,
             Self::ScaledUiAmountConfig { .. } => ExtensionType::ScaledUiAmount,
             Self::PausableConfig { .. } => ExtensionType::Pausable,
+            Self::PausableOperations => ExtensionType::PausableOperations,
+            Self::PauseSchedule => ExtensionType::PauseSchedule,
+            Self::TransferFeeTiers => ExtensionType::TransferFeeTiers,
+            Self::TransferFeeRecipient => ExtensionType::TransferFeeRecipient,
+            Self::SupplyCap { .. } => ExtensionType::SupplyCap,
+            Self::Vesting { .. } => ExtensionType::Vesting,
+            Self::SupplyHook { .. } => ExtensionType::SupplyHook,
+            Self::NonTransferableRecovery { .. } => ExtensionType::NonTransferableRecovery,
+            Self::TransferLimitPolicy { .. } => ExtensionType::TransferLimitPolicy,
         }
     }
     /// Generate an appropriate initialization instruction for the given mint
@@ -280,6 +323,9 @@ impl ExtensionInitializationParams {
             Self::PermanentDelegate { delegate } => {
                 instruction::initialize_permanent_delegate(token_program_id, mint, &delegate)
             }
+            Self::PermanentDelegateScope { scope } => {
+                permanent_delegate::instruction::initialize(token_program_id, mint, scope)
+            }
             Self::TransferHook {
                 authority,
                 program_id,
@@ -339,6 +385,41 @@ impl ExtensionInitializationParams {
             Self::PausableConfig { authority } => {
                 pausable::instruction::initialize(token_program_id, mint, &authority)
             }
+            Self::PausableOperations => {
+                pausable::instruction::initialize_operations(token_program_id, mint)
+            }
+            Self::PauseSchedule => {
+                pausable::instruction::initialize_schedule(token_program_id, mint)
+            }
+            Self::TransferFeeTiers => {
+                transfer_fee::instruction::initialize_transfer_fee_tiers(token_program_id, mint)
+            }
+            Self::TransferFeeRecipient => {
+                transfer_fee::instruction::initialize_transfer_fee_recipient(token_program_id, mint)
+            }
+            Self::SupplyCap {
+                authority,
+                max_supply,
+            } => supply_cap::instruction::initialize(token_program_id, mint, authority, max_supply),
+            Self::Vesting { authority } => {
+                vesting::instruction::initialize(token_program_id, mint, authority)
+            }
+            Self::SupplyHook {
+                authority,
+                program_id,
+            } => {
+                supply_hook::instruction::initialize(token_program_id, mint, authority, program_id)
+            }
+            Self::NonTransferableRecovery { authority } => {
+                non_transferable::instruction::initialize_recovery_authority(
+                    token_program_id,
+                    mint,
+                    authority,
+                )
+            }
+            Self::TransferLimitPolicy { authority } => {
+                transfer_limit::instruction::initialize_policy(token_program_id, mint, authority)
+            }
         }
     }
 }
@@ -730,6 +811,19 @@ where
             .map_err(TokenError::Client)
     }
 
+    /// Process the given token instructions as a single `Batch` instruction
+    pub async fn process_batch<S: Signers>(
+        &self,
+        token_instructions: &[Instruction],
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        self.process_ixs(
+            &[instruction::batch(&self.program_id, token_instructions)?],
+            signing_keypairs,
+        )
+        .await
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn create_mint<'a, S: Signers>(
         &self,
@@ -821,6 +915,37 @@ where
         self.process_ixs(&instructions, &[account]).await
     }
 
+    /// Create weighted multisig
+    pub async fn create_weighted_multisig(
+        &self,
+        account: &dyn Signer,
+        multisig_members: &[&Pubkey],
+        weights: &[u8],
+        threshold: u16,
+    ) -> TokenResult<T::Output> {
+        let instructions = vec![
+            system_instruction::create_account(
+                &self.payer.pubkey(),
+                &account.pubkey(),
+                self.client
+                    .get_minimum_balance_for_rent_exemption(PodWeightedMultisig::SIZE_OF)
+                    .await
+                    .map_err(TokenError::Client)?,
+                PodWeightedMultisig::SIZE_OF as u64,
+                &self.program_id,
+            ),
+            instruction::initialize_weighted_multisig(
+                &self.program_id,
+                &account.pubkey(),
+                multisig_members,
+                weights,
+                threshold,
+            )?,
+        ];
+
+        self.process_ixs(&instructions, &[account]).await
+    }
+
     /// Get the address for the associated token account.
     pub fn get_associated_token_address(&self, owner: &Pubkey) -> Pubkey {
         get_associated_token_address_with_program_id(owner, &self.pubkey, &self.program_id)
@@ -947,6 +1072,104 @@ where
         Ok(account)
     }
 
+    async fn get_clock(&self) -> TokenResult<Clock> {
+        let account = self.get_account(sysvar::clock::id()).await?;
+        from_account::<Clock, _>(&account)
+            .ok_or(TokenError::Program(ProgramError::InvalidAccountData))
+    }
+
+    /// Retrieve the current unix timestamp from the clock sysvar
+    pub async fn get_unix_timestamp(&self) -> TokenResult<UnixTimestamp> {
+        Ok(self.get_clock().await?.unix_timestamp)
+    }
+
+    /// Retrieve the current epoch from the clock sysvar
+    pub async fn get_epoch(&self) -> TokenResult<Epoch> {
+        Ok(self.get_clock().await?.epoch)
+    }
+
+    /// Calculate the fee withheld on a transfer of `amount` in the current
+    /// epoch, including the tiers of the mint, as expected by
+    /// `transfer_with_fee`
+    pub async fn calculate_transfer_fee(&self, amount: U256) -> TokenResult<U256> {
+        let mint = self.get_mint_info().await?;
+        let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() else {
+            return Ok(U256::ZERO);
+        };
+        let epoch = self.get_epoch().await?;
+        transfer_fee_config
+            .calculate_tiered_epoch_fee(
+                mint.get_extension::<TransferFeeTiers>().ok(),
+                epoch,
+                amount,
+            )
+            .ok_or(TokenError::Program(ProgramError::ArithmeticOverflow))
+    }
+
+    /// Calculate the fee withheld on the smallest transfer that leaves
+    /// `post_fee_amount` to the destination in the current epoch, including
+    /// the tiers of the mint
+    pub async fn calculate_inverse_transfer_fee(&self, post_fee_amount: U256) -> TokenResult<U256> {
+        let mint = self.get_mint_info().await?;
+        let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() else {
+            return Ok(U256::ZERO);
+        };
+        let epoch = self.get_epoch().await?;
+        transfer_fee_config
+            .calculate_inverse_tiered_epoch_fee(
+                mint.get_extension::<TransferFeeTiers>().ok(),
+                epoch,
+                post_fee_amount,
+            )
+            .ok_or(TokenError::Program(ProgramError::ArithmeticOverflow))
+    }
+
+    /// Retrieve the transfer fee rate and maximum fee of the current epoch,
+    /// as expected by `confidential_transfer_transfer_with_fee`. Fails if the
+    /// fee of the current epoch is tiered, since confidential transfers only
+    /// support a single rate.
+    pub async fn get_confidential_transfer_fee_parameters(&self) -> TokenResult<(u16, u64)> {
+        let mint = self.get_mint_info().await?;
+        let transfer_fee_config = mint.get_extension::<TransferFeeConfig>()?;
+        let epoch = self.get_epoch().await?;
+        let transfer_fee = transfer_fee_config
+            .get_tiered_epoch_fee(mint.get_extension::<TransferFeeTiers>().ok(), epoch);
+        if transfer_fee.is_tiered() {
+            return Err(TokenError::Program(
+                spl_token_2022::error::TokenError::ConfidentialTransferWithTieredFee.into(),
+            ));
+        }
+        let maximum_fee = u64::try_from(U256::from(transfer_fee.transfer_fee.maximum_fee))
+            .map_err(|_| TokenError::Program(ProgramError::ArithmeticOverflow))?;
+        Ok((
+            u16::from(transfer_fee.transfer_fee.transfer_fee_basis_points),
+            maximum_fee,
+        ))
+    }
+
+    /// Convert a raw amount to its UI representation, using the same
+    /// conversion as the program's `AmountToUiAmount` instruction
+    pub async fn amount_to_ui_amount(&self, amount: U256) -> TokenResult<String> {
+        let mint = self.get_mint_info().await?;
+        let unix_timestamp = self.get_unix_timestamp().await?;
+        ui_amount::amount_to_ui_amount_for_mint(&mint, mint.base.decimals, amount, unix_timestamp)
+            .ok_or(TokenError::Program(ProgramError::InvalidArgument))
+    }
+
+    /// Convert a UI amount to its raw amount, using the same conversion as the
+    /// program's `UiAmountToAmount` instruction
+    pub async fn ui_amount_to_amount(&self, ui_amount: &str) -> TokenResult<U256> {
+        let mint = self.get_mint_info().await?;
+        let unix_timestamp = self.get_unix_timestamp().await?;
+        ui_amount::try_ui_amount_into_amount_for_mint(
+            &mint,
+            mint.base.decimals,
+            ui_amount,
+            unix_timestamp,
+        )
+        .map_err(Into::into)
+    }
+
     /// Retrieve the associated account or create one if not found.
     pub async fn get_or_create_associated_account_info(
         &self,
@@ -1001,16 +1224,27 @@ where
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
+        let fetch_account_data_fn = |address| {
+            self.client
+                .get_account(address)
+                .map_ok(|opt| opt.map(|acc| acc.data))
+        };
+
         let instructions = if let Some(decimals) = self.decimals {
-            [instruction::mint_to_checked(
-                &self.program_id,
-                &self.pubkey,
-                destination,
-                authority,
-                &multisig_signers,
-                amount,
-                decimals,
-            )?]
+            [
+                offchain::create_mint_to_checked_instruction_with_extra_metas(
+                    &self.program_id,
+                    &self.pubkey,
+                    destination,
+                    authority,
+                    &multisig_signers,
+                    amount,
+                    decimals,
+                    fetch_account_data_fn,
+                )
+                .await
+                .map_err(|_| TokenError::AccountNotFound)?,
+            ]
         } else {
             [instruction::mint_to(
                 &self.program_id,
@@ -1237,6 +1471,36 @@ where
         self.process_ixs(&[instruction], signing_keypairs).await
     }
 
+    /// Move a balance of non-transferable tokens to another account, signed
+    /// by the mint's recovery authority
+    pub async fn recover<S: Signers>(
+        &self,
+        source: &Pubkey,
+        destination: &Pubkey,
+        authority: &Pubkey,
+        amount: U256,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+        let decimals = self.decimals.ok_or(TokenError::MissingDecimals)?;
+
+        self.process_ixs(
+            &[non_transferable::instruction::recover(
+                &self.program_id,
+                source,
+                &self.pubkey,
+                destination,
+                authority,
+                &multisig_signers,
+                amount,
+                decimals,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
     /// Burn tokens from account
     pub async fn burn<S: Signers>(
         &self,
@@ -1248,8 +1512,14 @@ where
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
+        let fetch_account_data_fn = |address| {
+            self.client
+                .get_account(address)
+                .map_ok(|opt| opt.map(|acc| acc.data))
+        };
+
         let instructions = if let Some(decimals) = self.decimals {
-            [instruction::burn_checked(
+            [offchain::create_burn_checked_instruction_with_extra_metas(
                 &self.program_id,
                 source,
                 &self.pubkey,
@@ -1257,7 +1527,10 @@ where
                 &multisig_signers,
                 amount,
                 decimals,
-            )?]
+                fetch_account_data_fn,
+            )
+            .await
+            .map_err(|_| TokenError::AccountNotFound)?]
         } else {
             [instruction::burn(
                 &self.program_id,
@@ -1272,18 +1545,45 @@ where
         self.process_ixs(&instructions, signing_keypairs).await
     }
 
-    /// Approve a delegate to spend tokens
+    /// Approve a delegate to spend tokens, optionally only until the given
+    /// unix timestamp
+    #[allow(clippy::too_many_arguments)]
     pub async fn approve<S: Signers>(
         &self,
         source: &Pubkey,
         delegate: &Pubkey,
         authority: &Pubkey,
         amount: U256,
+        expires_at: Option<i64>,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
+        if let Some(expires_at) = expires_at {
+            // make room for the expiry first, a no-op if the account has it
+            let instructions = [
+                instruction::reallocate(
+                    &self.program_id,
+                    source,
+                    &self.payer.pubkey(),
+                    authority,
+                    &multisig_signers,
+                    &[ExtensionType::DelegateExpiry],
+                )?,
+                delegate_expiry::instruction::approve(
+                    &self.program_id,
+                    source,
+                    delegate,
+                    authority,
+                    &multisig_signers,
+                    amount,
+                    expires_at,
+                )?,
+            ];
+            return self.process_ixs(&instructions, signing_keypairs).await;
+        }
+
         let instructions = if let Some(decimals) = self.decimals {
             [instruction::approve_checked(
                 &self.program_id,
@@ -1331,6 +1631,72 @@ where
         .await
     }
 
+    /// Approve an additional delegate with its own allowance in the account's
+    /// delegate table, optionally only until the given unix timestamp
+    #[allow(clippy::too_many_arguments)]
+    pub async fn approve_table_delegate<S: Signers>(
+        &self,
+        source: &Pubkey,
+        delegate: &Pubkey,
+        authority: &Pubkey,
+        amount: U256,
+        expires_at: Option<i64>,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        // make room for the table first, a no-op if the account has it
+        self.process_ixs(
+            &[
+                instruction::reallocate(
+                    &self.program_id,
+                    source,
+                    &self.payer.pubkey(),
+                    authority,
+                    &multisig_signers,
+                    &[ExtensionType::MultiDelegate],
+                )?,
+                multi_delegate::instruction::approve(
+                    &self.program_id,
+                    source,
+                    delegate,
+                    authority,
+                    &multisig_signers,
+                    amount,
+                    expires_at,
+                )?,
+            ],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Remove a delegate from the account's delegate table, signed by the
+    /// account owner or the delegate
+    pub async fn revoke_table_delegate<S: Signers>(
+        &self,
+        source: &Pubkey,
+        delegate: &Pubkey,
+        authority: &Pubkey,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[multi_delegate::instruction::revoke(
+                &self.program_id,
+                source,
+                delegate,
+                authority,
+                &multisig_signers,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
     /// Close an empty account and reclaim its lamports
     pub async fn close_account<S: Signers>(
         &self,
@@ -1598,6 +1964,81 @@ where
         .await
     }
 
+    /// Set tiered transfer fee
+    pub async fn set_tiered_transfer_fee<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        transfer_fee_basis_points: u16,
+        maximum_fee: U256,
+        tiers: &[TransferFeeTier],
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[transfer_fee::instruction::set_tiered_transfer_fee(
+                &self.program_id,
+                &self.pubkey,
+                authority,
+                &multisig_signers,
+                transfer_fee_basis_points,
+                maximum_fee,
+                tiers,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Exempt a token account from transfer fees, or revoke its exemption
+    pub async fn set_transfer_fee_exemption<S: Signers>(
+        &self,
+        account: &Pubkey,
+        authority: &Pubkey,
+        fee_exempt: bool,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[transfer_fee::instruction::set_transfer_fee_exemption(
+                &self.program_id,
+                account,
+                &self.pubkey,
+                authority,
+                &multisig_signers,
+                fee_exempt,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Set or clear the account that withheld tokens may be swept to
+    pub async fn set_fee_recipient<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        fee_recipient: Option<&Pubkey>,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[transfer_fee::instruction::set_fee_recipient(
+                &self.program_id,
+                &self.pubkey,
+                authority,
+                &multisig_signers,
+                fee_recipient,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
     /// Set default account state on mint
     pub async fn set_default_account_state<S: Signers>(
         &self,
@@ -1626,121 +2067,435 @@ where
     /// Harvest withheld tokens to mint
     pub async fn harvest_withheld_tokens_to_mint(
         &self,
-        sources: &[&Pubkey],
+        sources: &[&Pubkey],
+    ) -> TokenResult<T::Output> {
+        self.process_ixs::<[&dyn Signer; 0]>(
+            &[transfer_fee::instruction::harvest_withheld_tokens_to_mint(
+                &self.program_id,
+                &self.pubkey,
+                sources,
+            )?],
+            &[],
+        )
+        .await
+    }
+
+    /// Withdraw withheld tokens from mint
+    pub async fn withdraw_withheld_tokens_from_mint<S: Signers>(
+        &self,
+        destination: &Pubkey,
+        authority: &Pubkey,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[
+                transfer_fee::instruction::withdraw_withheld_tokens_from_mint(
+                    &self.program_id,
+                    &self.pubkey,
+                    destination,
+                    authority,
+                    &multisig_signers,
+                )?,
+            ],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Sweep withheld tokens from mint to the configured fee recipient
+    pub async fn sweep_withheld_tokens_to_fee_recipient(
+        &self,
+        fee_recipient: &Pubkey,
+    ) -> TokenResult<T::Output> {
+        self.process_ixs::<[&dyn Signer; 0]>(
+            &[
+                transfer_fee::instruction::sweep_withheld_tokens_to_fee_recipient(
+                    &self.program_id,
+                    &self.pubkey,
+                    fee_recipient,
+                )?,
+            ],
+            &[],
+        )
+        .await
+    }
+
+    /// Withdraw withheld tokens from accounts
+    pub async fn withdraw_withheld_tokens_from_accounts<S: Signers>(
+        &self,
+        destination: &Pubkey,
+        authority: &Pubkey,
+        sources: &[&Pubkey],
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[
+                transfer_fee::instruction::withdraw_withheld_tokens_from_accounts(
+                    &self.program_id,
+                    &self.pubkey,
+                    destination,
+                    authority,
+                    &multisig_signers,
+                    sources,
+                )?,
+            ],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Reallocate a token account to be large enough for a set of
+    /// `ExtensionType`s
+    pub async fn reallocate<S: Signers>(
+        &self,
+        account: &Pubkey,
+        authority: &Pubkey,
+        extension_types: &[ExtensionType],
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[instruction::reallocate(
+                &self.program_id,
+                account,
+                &self.payer.pubkey(),
+                authority,
+                &multisig_signers,
+                extension_types,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Add extensions to the mint, reallocating it if needed
+    pub async fn reallocate_mint<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        extension_types: &[ExtensionType],
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[instruction::reallocate_mint(
+                &self.program_id,
+                &self.pubkey,
+                &self.payer.pubkey(),
+                authority,
+                &multisig_signers,
+                extension_types,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Remove disabled or empty extensions from an account, refunding the
+    /// freed rent to its owner
+    pub async fn remove_account_extensions<S: Signers>(
+        &self,
+        account: &Pubkey,
+        authority: &Pubkey,
+        extension_types: &[ExtensionType],
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[instruction::remove_account_extensions(
+                &self.program_id,
+                account,
+                &self.pubkey,
+                authority,
+                &multisig_signers,
+                extension_types,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Require memos on transfers into this account
+    pub async fn enable_required_transfer_memos<S: Signers>(
+        &self,
+        account: &Pubkey,
+        authority: &Pubkey,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[memo_transfer::instruction::enable_required_transfer_memos(
+                &self.program_id,
+                account,
+                authority,
+                &multisig_signers,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Stop requiring memos on transfers into this account
+    pub async fn disable_required_transfer_memos<S: Signers>(
+        &self,
+        account: &Pubkey,
+        authority: &Pubkey,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[memo_transfer::instruction::disable_required_transfer_memos(
+                &self.program_id,
+                account,
+                authority,
+                &multisig_signers,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Configure in which directions transfers of a token account need a memo,
+    /// and the rules these memos must follow
+    #[allow(clippy::too_many_arguments)]
+    pub async fn configure_transfer_memos<S: Signers>(
+        &self,
+        account: &Pubkey,
+        authority: &Pubkey,
+        require_incoming_transfer_memos: bool,
+        require_outgoing_transfer_memos: bool,
+        rules: MemoRules,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[memo_transfer::instruction::configure_transfer_memos(
+                &self.program_id,
+                account,
+                authority,
+                &multisig_signers,
+                require_incoming_transfer_memos,
+                require_outgoing_transfer_memos,
+                rules,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Pause the given operations on the mint, leaving the others untouched
+    pub async fn pause<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        operations: &[PausableOperation],
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        let instructions = if PausableOperation::ALL
+            .iter()
+            .all(|operation| operations.contains(operation))
+        {
+            vec![pausable::instruction::pause(
+                &self.program_id,
+                self.get_address(),
+                authority,
+                &multisig_signers,
+            )?]
+        } else {
+            operations
+                .iter()
+                .map(|operation| {
+                    pausable::instruction::set_paused(
+                        &self.program_id,
+                        self.get_address(),
+                        authority,
+                        &multisig_signers,
+                        *operation,
+                        true,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        self.process_ixs(&instructions, signing_keypairs).await
+    }
+
+    /// Resume the given operations on the mint, leaving the others untouched
+    pub async fn resume<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        operations: &[PausableOperation],
+        signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
-        self.process_ixs::<[&dyn Signer; 0]>(
-            &[transfer_fee::instruction::harvest_withheld_tokens_to_mint(
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        let instructions = if PausableOperation::ALL
+            .iter()
+            .all(|operation| operations.contains(operation))
+        {
+            vec![pausable::instruction::resume(
                 &self.program_id,
-                &self.pubkey,
-                sources,
-            )?],
-            &[],
-        )
-        .await
+                self.get_address(),
+                authority,
+                &multisig_signers,
+            )?]
+        } else {
+            operations
+                .iter()
+                .map(|operation| {
+                    pausable::instruction::set_paused(
+                        &self.program_id,
+                        self.get_address(),
+                        authority,
+                        &multisig_signers,
+                        *operation,
+                        false,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        self.process_ixs(&instructions, signing_keypairs).await
     }
 
-    /// Withdraw withheld tokens from mint
-    pub async fn withdraw_withheld_tokens_from_mint<S: Signers>(
+    /// Schedule a pause and / or an automatic resume of all operations on the
+    /// mint at the given unix timestamps
+    pub async fn set_pause_schedule<S: Signers>(
         &self,
-        destination: &Pubkey,
         authority: &Pubkey,
+        pause_timestamp: Option<i64>,
+        resume_timestamp: Option<i64>,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[
-                transfer_fee::instruction::withdraw_withheld_tokens_from_mint(
-                    &self.program_id,
-                    &self.pubkey,
-                    destination,
-                    authority,
-                    &multisig_signers,
-                )?,
-            ],
+            &[pausable::instruction::set_pause_schedule(
+                &self.program_id,
+                self.get_address(),
+                authority,
+                &multisig_signers,
+                pause_timestamp,
+                resume_timestamp,
+            )?],
             signing_keypairs,
         )
         .await
     }
 
-    /// Withdraw withheld tokens from accounts
-    pub async fn withdraw_withheld_tokens_from_accounts<S: Signers>(
+    /// Lower the maximum supply of the mint
+    pub async fn update_max_supply<S: Signers>(
         &self,
-        destination: &Pubkey,
         authority: &Pubkey,
-        sources: &[&Pubkey],
+        max_supply: U256,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[
-                transfer_fee::instruction::withdraw_withheld_tokens_from_accounts(
-                    &self.program_id,
-                    &self.pubkey,
-                    destination,
-                    authority,
-                    &multisig_signers,
-                    sources,
-                )?,
-            ],
+            &[supply_cap::instruction::update_max_supply(
+                &self.program_id,
+                self.get_address(),
+                authority,
+                &multisig_signers,
+                max_supply,
+            )?],
             signing_keypairs,
         )
         .await
     }
 
-    /// Reallocate a token account to be large enough for a set of
-    /// `ExtensionType`s
-    pub async fn reallocate<S: Signers>(
+    /// Narrow the operations the permanent delegate may perform
+    pub async fn narrow_permanent_delegate_scope<S: Signers>(
         &self,
-        account: &Pubkey,
         authority: &Pubkey,
-        extension_types: &[ExtensionType],
+        scope: PermanentDelegateOperations,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[instruction::reallocate(
+            &[permanent_delegate::instruction::narrow_scope(
                 &self.program_id,
-                account,
-                &self.payer.pubkey(),
+                self.get_address(),
                 authority,
                 &multisig_signers,
-                extension_types,
+                scope,
             )?],
             signing_keypairs,
         )
         .await
     }
 
-    /// Require memos on transfers into this account
-    pub async fn enable_required_transfer_memos<S: Signers>(
+    #[allow(clippy::too_many_arguments)]
+    pub async fn set_vesting_schedule<S: Signers>(
         &self,
         account: &Pubkey,
         authority: &Pubkey,
+        owner: &Pubkey,
+        start_timestamp: i64,
+        cliff_timestamp: i64,
+        end_timestamp: i64,
+        amount: U256,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
-        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+        // the vesting authority and the owner both sign directly unless one of
+        // them is a multisig
+        let multisig_signers = if signing_pubkeys
+            .iter()
+            .all(|signer| signer == authority || signer == owner)
+            && signing_pubkeys.contains(authority)
+            && signing_pubkeys.contains(owner)
+        {
+            vec![]
+        } else {
+            signing_pubkeys.iter().collect::<Vec<_>>()
+        };
 
         self.process_ixs(
-            &[memo_transfer::instruction::enable_required_transfer_memos(
+            &[vesting::instruction::lock(
                 &self.program_id,
                 account,
+                self.get_address(),
                 authority,
+                owner,
                 &multisig_signers,
+                start_timestamp,
+                cliff_timestamp,
+                end_timestamp,
+                amount,
             )?],
             signing_keypairs,
         )
         .await
     }
 
-    /// Stop requiring memos on transfers into this account
-    pub async fn disable_required_transfer_memos<S: Signers>(
+    /// Prevent unsafe usage of token account through CPI
+    pub async fn enable_cpi_guard<S: Signers>(
         &self,
         account: &Pubkey,
         authority: &Pubkey,
@@ -1750,7 +2505,7 @@ where
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[memo_transfer::instruction::disable_required_transfer_memos(
+            &[cpi_guard::instruction::enable_cpi_guard(
                 &self.program_id,
                 account,
                 authority,
@@ -1761,9 +2516,10 @@ where
         .await
     }
 
-    /// Pause transferring, minting, and burning on the mint
-    pub async fn pause<S: Signers>(
+    /// Stop preventing unsafe usage of token account through CPI
+    pub async fn disable_cpi_guard<S: Signers>(
         &self,
+        account: &Pubkey,
         authority: &Pubkey,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
@@ -1771,9 +2527,9 @@ where
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[pausable::instruction::pause(
+            &[cpi_guard::instruction::disable_cpi_guard(
                 &self.program_id,
-                self.get_address(),
+                account,
                 authority,
                 &multisig_signers,
             )?],
@@ -1782,66 +2538,93 @@ where
         .await
     }
 
-    /// Resume transferring, minting, and burning on the mint
-    pub async fn resume<S: Signers>(
+    /// Replace the programs allowed to perform guarded operations on a token
+    /// account through CPI
+    pub async fn set_cpi_guard_allowlist<S: Signers>(
         &self,
+        account: &Pubkey,
         authority: &Pubkey,
+        allowed_programs: &[Pubkey],
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
+        // make room for the allowlist first, a no-op if the account has it
         self.process_ixs(
-            &[pausable::instruction::resume(
-                &self.program_id,
-                self.get_address(),
-                authority,
-                &multisig_signers,
-            )?],
+            &[
+                instruction::reallocate(
+                    &self.program_id,
+                    account,
+                    &self.payer.pubkey(),
+                    authority,
+                    &multisig_signers,
+                    &[ExtensionType::CpiGuardAllowlist],
+                )?,
+                cpi_guard::instruction::set_allowlist(
+                    &self.program_id,
+                    account,
+                    authority,
+                    &multisig_signers,
+                    allowed_programs,
+                )?,
+            ],
             signing_keypairs,
         )
         .await
     }
 
-    /// Prevent unsafe usage of token account through CPI
-    pub async fn enable_cpi_guard<S: Signers>(
+    /// Limit the amount of tokens leaving a token account per time window
+    pub async fn configure_transfer_limit<S: Signers>(
         &self,
         account: &Pubkey,
         authority: &Pubkey,
+        limit: U256,
+        window_seconds: i64,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[cpi_guard::instruction::enable_cpi_guard(
+            &[transfer_limit::instruction::configure_transfer_limit(
                 &self.program_id,
                 account,
                 authority,
                 &multisig_signers,
+                limit,
+                window_seconds,
             )?],
             signing_keypairs,
         )
         .await
     }
 
-    /// Stop preventing unsafe usage of token account through CPI
-    pub async fn disable_cpi_guard<S: Signers>(
+    /// Limit the amount of tokens leaving a token account per time window, as
+    /// the mint's transfer limit policy authority
+    pub async fn configure_transfer_limit_by_policy_authority<S: Signers>(
         &self,
         account: &Pubkey,
-        authority: &Pubkey,
+        policy_authority: &Pubkey,
+        limit: U256,
+        window_seconds: i64,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
-        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+        let multisig_signers = self.get_multisig_signers(policy_authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[cpi_guard::instruction::disable_cpi_guard(
-                &self.program_id,
-                account,
-                authority,
-                &multisig_signers,
-            )?],
+            &[
+                transfer_limit::instruction::configure_transfer_limit_by_policy_authority(
+                    &self.program_id,
+                    account,
+                    &self.pubkey,
+                    policy_authority,
+                    &multisig_signers,
+                    limit,
+                    window_seconds,
+                )?,
+            ],
             signing_keypairs,
         )
         .await
@@ -1918,6 +2701,29 @@ where
         .await
     }
 
+    /// Update supply hook program id
+    pub async fn update_supply_hook_program_id<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        new_program_id: Option<Pubkey>,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[supply_hook::instruction::update(
+                &self.program_id,
+                self.get_address(),
+                authority,
+                &multisig_signers,
+                new_program_id,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
     /// Update metadata pointer address
     pub async fn update_metadata_address<S: Signers>(
         &self,
@@ -2093,6 +2899,24 @@ where
         .await
     }
 
+    /// Closes the ElGamal registry account of an owner, sending its lamports
+    /// to a destination account
+    pub async fn confidential_transfer_close_elgamal_registry<S: Signers>(
+        &self,
+        owner: &Pubkey,
+        destination: &Pubkey,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        self.process_ixs(
+            &[spl_elgamal_registry::instruction::close_registry(
+                owner,
+                destination,
+            )],
+            signing_keypairs,
+        )
+        .await
+    }
+
     /// Approves a token account for confidential transfers
     pub async fn confidential_transfer_approve_account<S: Signers>(
         &self,
@@ -3318,25 +4142,33 @@ where
         self.process_ixs(&instructions, signing_keypairs).await
     }
 
-    /// Update a token-metadata field on a mint
+    /// Update a token-metadata field on a mint. If the mint shrinks, the freed
+    /// rent is sent to the destination in `refund`, if provided, with the
+    /// approval of the mint authority in `refund`.
     pub async fn token_metadata_update_field<S: Signers>(
         &self,
         update_authority: &Pubkey,
         field: Field,
         value: String,
+        refund: Option<(&Pubkey, &Pubkey)>,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
-        self.process_ixs(
-            &[spl_token_metadata_interface::instruction::update_field(
-                &self.program_id,
-                &self.pubkey,
-                update_authority,
-                field,
-                value,
-            )],
-            signing_keypairs,
-        )
-        .await
+        let mut instruction = spl_token_metadata_interface::instruction::update_field(
+            &self.program_id,
+            &self.pubkey,
+            update_authority,
+            field,
+            value,
+        );
+        if let Some((refund_destination, mint_authority)) = refund {
+            instruction
+                .accounts
+                .push(AccountMeta::new(*refund_destination, false));
+            instruction
+                .accounts
+                .push(AccountMeta::new_readonly(*mint_authority, true));
+        }
+        self.process_ixs(&[instruction], signing_keypairs).await
     }
 
     async fn get_additional_rent_for_updated_metadata(
@@ -3360,7 +4192,9 @@ where
     }
 
     /// Update a token-metadata field on a mint. Includes a transfer for any
-    /// additional rent-exempt SOL required.
+    /// additional rent-exempt SOL required. If the mint shrinks, the freed
+    /// rent is sent to the destination in `refund`, if provided, with the
+    /// approval of the mint authority in `refund`.
     #[allow(clippy::too_many_arguments)]
     pub async fn token_metadata_update_field_with_rent_transfer<S: Signers>(
         &self,
@@ -3369,6 +4203,7 @@ where
         field: Field,
         value: String,
         transfer_lamports: Option<u64>,
+        refund: Option<(&Pubkey, &Pubkey)>,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let additional_lamports = if let Some(transfer_lamports) = transfer_lamports {
@@ -3385,13 +4220,22 @@ where
                 additional_lamports,
             ));
         }
-        instructions.push(spl_token_metadata_interface::instruction::update_field(
+        let mut instruction = spl_token_metadata_interface::instruction::update_field(
             &self.program_id,
             &self.pubkey,
             update_authority,
             field,
             value,
-        ));
+        );
+        if let Some((refund_destination, mint_authority)) = refund {
+            instruction
+                .accounts
+                .push(AccountMeta::new(*refund_destination, false));
+            instruction
+                .accounts
+                .push(AccountMeta::new_readonly(*mint_authority, true));
+        }
+        instructions.push(instruction);
         self.process_ixs(&instructions, signing_keypairs).await
     }
 
@@ -3414,25 +4258,33 @@ where
         .await
     }
 
-    /// Remove a token-metadata field on a mint
+    /// Remove a token-metadata field on a mint. The freed rent is sent to the
+    /// destination in `refund`, if provided, with the approval of the mint
+    /// authority in `refund`.
     pub async fn token_metadata_remove_key<S: Signers>(
         &self,
         update_authority: &Pubkey,
         key: String,
         idempotent: bool,
+        refund: Option<(&Pubkey, &Pubkey)>,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
-        self.process_ixs(
-            &[spl_token_metadata_interface::instruction::remove_key(
-                &self.program_id,
-                &self.pubkey,
-                update_authority,
-                key,
-                idempotent,
-            )],
-            signing_keypairs,
-        )
-        .await
+        let mut instruction = spl_token_metadata_interface::instruction::remove_key(
+            &self.program_id,
+            &self.pubkey,
+            update_authority,
+            key,
+            idempotent,
+        );
+        if let Some((refund_destination, mint_authority)) = refund {
+            instruction
+                .accounts
+                .push(AccountMeta::new(*refund_destination, false));
+            instruction
+                .accounts
+                .push(AccountMeta::new_readonly(*mint_authority, true));
+        }
+        self.process_ixs(&[instruction], signing_keypairs).await
     }
 
     /// Initialize token-group on a mint
@@ -3602,6 +4454,74 @@ where
         ));
         self.process_ixs(&instructions, signing_keypairs).await
     }
+
+    /// Remove a token-group member from its group. The freed rent is sent to
+    /// the destination in `refund`, if provided, with the approval of the
+    /// member mint authority in `refund`.
+    pub async fn token_group_remove_member<S: Signers>(
+        &self,
+        group_mint: &Pubkey,
+        authority: &Pubkey,
+        refund: Option<(&Pubkey, &Pubkey)>,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        self.process_ixs(
+            &[token_group::instruction::remove_member(
+                &self.program_id,
+                &self.pubkey,
+                group_mint,
+                authority,
+                refund,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Remove a token-group member from its group, first funding the group
+    /// mint for the member counter added on its first removal
+    pub async fn token_group_remove_member_with_rent_transfer<S: Signers>(
+        &self,
+        payer: &Pubkey,
+        group_mint: &Pubkey,
+        authority: &Pubkey,
+        refund: Option<(&Pubkey, &Pubkey)>,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        // the group mint may have other decimals, so skip `unpack_mint_info`
+        let group_account = self.get_account(*group_mint).await?;
+        let group_lamports = group_account.lamports;
+        let group_state = StateWithExtensionsOwned::<Mint>::unpack(group_account.data)?;
+        let mut instructions = vec![];
+        if group_state
+            .get_extension::<token_group::TokenGroupMemberCounter>()
+            .is_err()
+        {
+            let new_account_len =
+                group_state.try_get_new_account_len::<token_group::TokenGroupMemberCounter>()?;
+            let new_rent_exempt_minimum = self
+                .client
+                .get_minimum_balance_for_rent_exemption(new_account_len)
+                .await
+                .map_err(TokenError::Client)?;
+            let additional_lamports = new_rent_exempt_minimum.saturating_sub(group_lamports);
+            if additional_lamports > 0 {
+                instructions.push(system_instruction::transfer(
+                    payer,
+                    group_mint,
+                    additional_lamports,
+                ));
+            }
+        }
+        instructions.push(token_group::instruction::remove_member(
+            &self.program_id,
+            &self.pubkey,
+            group_mint,
+            authority,
+            refund,
+        )?);
+        self.process_ixs(&instructions, signing_keypairs).await
+    }
 }
 
 /// Calculates the maximum chunk size for a zero-knowledge proof record
@@ -0,0 +1,261 @@
+mod program_test;
+use {
+    ethnum::U256,
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{cpi_guard, ExtensionType},
+        instruction,
+    },
+    spl_token_client::token::TokenError as TokenClientError,
+};
+
+#[tokio::test]
+async fn airdrop() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext {
+        decimals,
+        mint_authority,
+        token,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(1_000),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let mut recipients = vec![];
+    for _ in 0..5 {
+        let owner = Pubkey::new_unique();
+        token.create_associated_token_account(&owner).await.unwrap();
+        recipients.push(token.get_associated_token_address(&owner));
+    }
+
+    // mint to and transfer to every recipient in one instruction
+    let mut instructions = vec![];
+    for (i, recipient) in recipients.iter().enumerate() {
+        instructions.push(
+            instruction::mint_to_checked(
+                &spl_token_2022::id(),
+                token.get_address(),
+                recipient,
+                &mint_authority.pubkey(),
+                &[],
+                U256::from(i as u64 + 1),
+                decimals,
+            )
+            .unwrap(),
+        );
+        instructions.push(
+            instruction::transfer_checked(
+                &spl_token_2022::id(),
+                &alice_account,
+                token.get_address(),
+                recipient,
+                &alice.pubkey(),
+                &[],
+                U256::new(150),
+                decimals,
+            )
+            .unwrap(),
+        );
+    }
+    token
+        .process_batch(&instructions, &[&mint_authority, &alice])
+        .await
+        .unwrap();
+
+    for (i, recipient) in recipients.iter().enumerate() {
+        let state = token.get_account_info(recipient).await.unwrap();
+        assert_eq!(U256::from(state.base.amount), U256::from(i as u64 + 151));
+    }
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(U256::from(state.base.amount), U256::new(250));
+
+    // one failing instruction fails the whole batch
+    let error = token
+        .process_batch(&instructions[1..], &[&mint_authority, &alice])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::InsufficientFunds as u32)
+            )
+        )))
+    );
+    let state = token.get_account_info(&recipients[1]).await.unwrap();
+    assert_eq!(U256::from(state.base.amount), U256::new(152));
+}
+
+fn invalid_batch_account_error() -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TokenError::InvalidBatchAccount as u32),
+        ),
+    )))
+}
+
+#[tokio::test]
+async fn memo_required_accounts_rejected() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext {
+        decimals,
+        mint_authority,
+        token,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account_with_extension_space(
+            &bob_account,
+            &bob.pubkey(),
+            vec![ExtensionType::MemoTransfer],
+        )
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(1_000),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .enable_required_transfer_memos(&bob_account, &bob.pubkey(), &[&bob])
+        .await
+        .unwrap();
+
+    // a memo ahead of the batch would be seen by every transfer in it
+    let transfer = instruction::transfer_checked(
+        &spl_token_2022::id(),
+        &alice_account,
+        token.get_address(),
+        &bob_account,
+        &alice.pubkey(),
+        &[],
+        U256::new(10),
+        decimals,
+    )
+    .unwrap();
+    let error = token
+        .process_batch(&[transfer.clone(), transfer], &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(error, invalid_batch_account_error());
+    let state = token.get_account_info(&bob_account).await.unwrap();
+    assert_eq!(U256::from(state.base.amount), U256::ZERO);
+}
+
+#[tokio::test]
+async fn cpi_guarded_accounts_rejected() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext {
+        decimals,
+        mint_authority,
+        token,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account_with_extension_space(
+            &alice_account,
+            &alice.pubkey(),
+            vec![ExtensionType::CpiGuard],
+        )
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(1_000),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let transfer = instruction::transfer_checked(
+        &spl_token_2022::id(),
+        &alice_account,
+        token.get_address(),
+        &bob_account,
+        &alice.pubkey(),
+        &[],
+        U256::new(10),
+        decimals,
+    )
+    .unwrap();
+
+    // enabling the guard earlier in the batch is caught too
+    let enable_cpi_guard = cpi_guard::instruction::enable_cpi_guard(
+        &spl_token_2022::id(),
+        &alice_account,
+        &alice.pubkey(),
+        &[],
+    )
+    .unwrap();
+    let error = token
+        .process_batch(&[enable_cpi_guard, transfer.clone()], &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(error, invalid_batch_account_error());
+
+    token
+        .enable_cpi_guard(&alice_account, &alice.pubkey(), &[&alice])
+        .await
+        .unwrap();
+    let error = token
+        .process_batch(&[transfer], &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(error, invalid_batch_account_error());
+    let state = token.get_account_info(&bob_account).await.unwrap();
+    assert_eq!(U256::from(state.base.amount), U256::ZERO);
+}
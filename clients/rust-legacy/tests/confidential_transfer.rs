@@ -8,6 +8,7 @@ use {
     },
     solana_program_test::tokio,
     solana_sdk::{
+        clock::Clock,
         instruction::InstructionError,
         pubkey::Pubkey,
         signature::Signer,
@@ -26,6 +27,7 @@ use {
                 account_info::{EmptyAccountAccountInfo, TransferAccountInfo, WithdrawAccountInfo},
                 ConfidentialTransferAccount, MAXIMUM_DEPOSIT_TRANSFER_AMOUNT,
             },
+            pausable::PausableOperation,
             BaseStateWithExtensions, ExtensionType,
         },
         solana_zk_sdk::{
@@ -953,6 +955,92 @@ async fn confidential_transfer_deposit() {
     assert_eq!(extension.actual_pending_balance_credit_counter, 2.into());
 }
 
+#[tokio::test]
+async fn confidential_transfer_deposit_from_wide_balance() {
+    let authority = Keypair::new();
+    let auto_approve_new_accounts = true;
+    let auditor_elgamal_keypair = ElGamalKeypair::new_rand();
+    let auditor_elgamal_pubkey = (*auditor_elgamal_keypair.pubkey()).into();
+
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![
+            ExtensionInitializationParams::ConfidentialTransferMint {
+                authority: Some(authority.pubkey()),
+                auto_approve_new_accounts,
+                auditor_elgamal_pubkey: Some(auditor_elgamal_pubkey),
+            },
+        ])
+        .await
+        .unwrap();
+
+    let TokenContext {
+        token,
+        alice,
+        mint_authority,
+        decimals,
+        ..
+    } = context.token_context.unwrap();
+    let alice_meta = ConfidentialTokenAccountMeta::new(&token, &alice, Some(2), false, false).await;
+
+    let balance = U256::from(u64::MAX) * 4;
+    token
+        .mint_to(
+            &alice_meta.token_account,
+            &mint_authority.pubkey(),
+            balance,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // the non-confidential balance does not fit in a `u64`
+    token
+        .confidential_transfer_deposit(
+            &alice_meta.token_account,
+            &alice.pubkey(),
+            U256::new(65537),
+            decimals,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+
+    let state = token
+        .get_account_info(&alice_meta.token_account)
+        .await
+        .unwrap();
+    assert_eq!(state.base.amount, balance - 65537);
+
+    alice_meta
+        .check_balances(
+            &token,
+            ConfidentialTokenAccountBalances {
+                pending_balance_lo: 1,
+                pending_balance_hi: 1,
+                available_balance: 0,
+                decryptable_available_balance: 0,
+            },
+        )
+        .await;
+
+    // confidential amounts are not full-width
+    let err = token
+        .confidential_transfer_deposit(
+            &alice_meta.token_account,
+            &alice.pubkey(),
+            U256::from(u64::MAX) + 1,
+            decimals,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Program(TokenError::MaximumDepositAmountExceeded.into())
+    );
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn withdraw_with_option<S: Signers>(
     token: &Token<ProgramBanksClientProcessTransaction>,
@@ -1648,7 +1736,11 @@ async fn pause_confidential_deposit() {
         .unwrap();
 
     token
-        .pause(&pausable_authority.pubkey(), &[&pausable_authority])
+        .pause(
+            &pausable_authority.pubkey(),
+            &PausableOperation::ALL,
+            &[&pausable_authority],
+        )
         .await
         .unwrap();
 
@@ -1673,6 +1765,193 @@ async fn pause_confidential_deposit() {
     );
 }
 
+#[tokio::test]
+async fn transfer_limit_confidential_deposit() {
+    let authority = Keypair::new();
+    let auto_approve_new_accounts = true;
+    let auditor_elgamal_keypair = ElGamalKeypair::new_rand();
+    let auditor_elgamal_pubkey = (*auditor_elgamal_keypair.pubkey()).into();
+
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![
+            ExtensionInitializationParams::ConfidentialTransferMint {
+                authority: Some(authority.pubkey()),
+                auto_approve_new_accounts,
+                auditor_elgamal_pubkey: Some(auditor_elgamal_pubkey),
+            },
+        ])
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        alice,
+        mint_authority,
+        decimals,
+        ..
+    } = context.token_context.unwrap();
+
+    let alice_meta = ConfidentialTokenAccountMeta::new(&token, &alice, None, false, false).await;
+
+    token
+        .mint_to(
+            &alice_meta.token_account,
+            &mint_authority.pubkey(),
+            U256::new(100),
+            &[mint_authority],
+        )
+        .await
+        .unwrap();
+
+    token
+        .reallocate(
+            &alice_meta.token_account,
+            &alice.pubkey(),
+            &[ExtensionType::TransferLimit],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    token
+        .configure_transfer_limit(
+            &alice_meta.token_account,
+            &alice.pubkey(),
+            U256::new(50),
+            86_400,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+
+    // deposits leave the non-confidential balance, so they count against
+    // the limit
+    token
+        .confidential_transfer_deposit(
+            &alice_meta.token_account,
+            &alice.pubkey(),
+            U256::new(30),
+            decimals,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let error = token
+        .confidential_transfer_deposit(
+            &alice_meta.token_account,
+            &alice.pubkey(),
+            U256::new(21),
+            decimals,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::TransferLimitExceeded as u32)
+            )
+        )))
+    );
+}
+
+#[tokio::test]
+async fn vesting_confidential_deposit() {
+    let authority = Keypair::new();
+    let vesting_authority = Keypair::new();
+    let auto_approve_new_accounts = true;
+    let auditor_elgamal_keypair = ElGamalKeypair::new_rand();
+    let auditor_elgamal_pubkey = (*auditor_elgamal_keypair.pubkey()).into();
+
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![
+            ExtensionInitializationParams::ConfidentialTransferMint {
+                authority: Some(authority.pubkey()),
+                auto_approve_new_accounts,
+                auditor_elgamal_pubkey: Some(auditor_elgamal_pubkey),
+            },
+            ExtensionInitializationParams::Vesting {
+                authority: Some(vesting_authority.pubkey()),
+            },
+        ])
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        alice,
+        mint_authority,
+        decimals,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_meta = ConfidentialTokenAccountMeta::new(&token, &alice, None, false, false).await;
+
+    token
+        .mint_to(
+            &alice_meta.token_account,
+            &mint_authority.pubkey(),
+            U256::new(100),
+            &[mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let clock = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap();
+    token
+        .set_vesting_schedule(
+            &alice_meta.token_account,
+            &vesting_authority.pubkey(),
+            &alice.pubkey(),
+            clock.unix_timestamp,
+            clock.unix_timestamp + 1_000,
+            clock.unix_timestamp + 2_000,
+            U256::new(60),
+            &[&vesting_authority, &alice],
+        )
+        .await
+        .unwrap();
+
+    // only the unlocked balance may be deposited
+    token
+        .confidential_transfer_deposit(
+            &alice_meta.token_account,
+            &alice.pubkey(),
+            U256::new(40),
+            decimals,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let error = token
+        .confidential_transfer_deposit(
+            &alice_meta.token_account,
+            &alice.pubkey(),
+            U256::ONE,
+            decimals,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::InsufficientUnlockedFunds as u32)
+            )
+        )))
+    );
+}
+
 #[tokio::test]
 async fn pause_confidential_withdraw() {
     let authority = Keypair::new();
@@ -1739,7 +2018,11 @@ async fn pause_confidential_withdraw() {
         .unwrap();
 
     token
-        .pause(&pausable_authority.pubkey(), &[&pausable_authority])
+        .pause(
+            &pausable_authority.pubkey(),
+            &PausableOperation::ALL,
+            &[&pausable_authority],
+        )
         .await
         .unwrap();
 
@@ -1816,7 +2099,11 @@ async fn pause_confidential_transfer() {
 
     // pause it
     token
-        .pause(&pausable_authority.pubkey(), &[&pausable_authority])
+        .pause(
+            &pausable_authority.pubkey(),
+            &PausableOperation::ALL,
+            &[&pausable_authority],
+        )
         .await
         .unwrap();
     let error = confidential_transfer_with_option(
@@ -2620,7 +2907,11 @@ async fn pause_confidential_transfer_with_fee() {
     let bob_meta = ConfidentialTokenAccountMeta::new(&token, &bob, None, false, true).await;
 
     token
-        .pause(&pausable_authority.pubkey(), &[&pausable_authority])
+        .pause(
+            &pausable_authority.pubkey(),
+            &PausableOperation::ALL,
+            &[&pausable_authority],
+        )
         .await
         .unwrap();
 
@@ -3304,4 +3595,48 @@ async fn confidential_transfer_configure_token_account_with_registry() {
         extension.elgamal_pubkey,
         (*new_elgamal_keypair.pubkey()).into()
     );
+
+    // close ElGamal registry
+    let destination = Pubkey::new_unique();
+    token
+        .confidential_transfer_close_elgamal_registry(&alice.pubkey(), &destination, &[&alice])
+        .await
+        .unwrap();
+
+    let ctx = context.context.lock().await;
+    assert!(ctx
+        .banks_client
+        .get_account(elgamal_registry_address)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        ctx.banks_client.get_balance(destination).await.unwrap(),
+        rent.minimum_balance(space)
+    );
+
+    // the registry can be re-created after it is closed
+    let proof_data =
+        confidential_transfer::instruction::PubkeyValidityProofData::new(&elgamal_keypair).unwrap();
+    let proof_location = ProofLocation::InstructionOffset(
+        1.try_into().unwrap(),
+        ProofData::InstructionData(&proof_data),
+    );
+    let system_instruction = system_instruction::transfer(
+        &ctx.payer.pubkey(),
+        &elgamal_registry_address,
+        rent.minimum_balance(space),
+    );
+    let create_registry_instructions =
+        spl_elgamal_registry::instruction::create_registry(&alice.pubkey(), proof_location)
+            .unwrap();
+
+    let instructions = [&[system_instruction], &create_registry_instructions[..]].concat();
+    let tx = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&ctx.payer.pubkey()),
+        &[&ctx.payer, &alice],
+        ctx.last_blockhash,
+    );
+    ctx.banks_client.process_transaction(tx).await.unwrap();
 }
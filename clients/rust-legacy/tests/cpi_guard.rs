@@ -8,14 +8,19 @@ use {
         ProgramTest,
     },
     solana_sdk::{
-        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
-        transaction::TransactionError, transport::TransportError,
+        instruction::{AccountMeta, InstructionError},
+        pubkey::Pubkey,
+        signature::Signer,
+        signer::keypair::Keypair,
+        sysvar,
+        transaction::TransactionError,
+        transport::TransportError,
     },
     spl_instruction_padding::instruction::wrap_instruction,
     spl_token_2022::{
         error::TokenError,
         extension::{
-            cpi_guard::{self, CpiGuard},
+            cpi_guard::{self, CpiGuard, CpiGuardAllowlist},
             BaseStateWithExtensions, ExtensionType,
         },
         instruction::{self, AuthorityType},
@@ -286,6 +291,7 @@ async fn test_cpi_guard_transfer() {
                 &alice.pubkey(),
                 &alice.pubkey(),
                 U256::ONE,
+                None,
                 &[&alice],
             )
             .await
@@ -307,6 +313,7 @@ async fn test_cpi_guard_transfer() {
                 &bob.pubkey(),
                 &alice.pubkey(),
                 U256::ONE,
+                None,
                 &[&alice],
             )
             .await
@@ -426,6 +433,7 @@ async fn test_cpi_guard_burn() {
                 &alice.pubkey(),
                 &alice.pubkey(),
                 U256::ONE,
+                None,
                 &[&alice],
             )
             .await
@@ -447,6 +455,7 @@ async fn test_cpi_guard_burn() {
                 &bob.pubkey(),
                 &alice.pubkey(),
                 U256::ONE,
+                None,
                 &[&alice],
             )
             .await
@@ -535,6 +544,7 @@ async fn test_cpi_guard_approve() {
                 &bob.pubkey(),
                 &alice.pubkey(),
                 U256::ONE,
+                None,
                 &[&alice],
             )
             .await
@@ -811,3 +821,107 @@ async fn test_cpi_guard_set_authority() {
         }
     }
 }
+
+#[tokio::test]
+async fn test_cpi_guard_allowlist() {
+    let context = make_context().await;
+    let TokenContext {
+        token, alice, bob, ..
+    } = context.token_context.unwrap();
+
+    let mk_approve = |with_instructions_sysvar| {
+        let mut approve = instruction::approve(
+            &spl_token_2022::id(),
+            &alice.pubkey(),
+            &bob.pubkey(),
+            &alice.pubkey(),
+            &[],
+            U256::ONE,
+        )
+        .unwrap();
+        if with_instructions_sysvar {
+            approve
+                .accounts
+                .push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+        }
+        wrap_instruction(spl_instruction_padding::id(), approve, vec![], 0).unwrap()
+    };
+
+    token
+        .enable_cpi_guard(&alice.pubkey(), &alice.pubkey(), &[&alice])
+        .await
+        .unwrap();
+
+    // allowlist can't be set through cpi
+    let error = token
+        .process_ixs(
+            &[wrap_instruction(
+                spl_instruction_padding::id(),
+                cpi_guard::instruction::set_allowlist(
+                    &spl_token_2022::id(),
+                    &alice.pubkey(),
+                    &alice.pubkey(),
+                    &[],
+                    &[spl_instruction_padding::id()],
+                )
+                .unwrap(),
+                vec![],
+                0,
+            )
+            .unwrap()],
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::CpiGuardSettingsLocked));
+
+    // another program on the allowlist doesn't help
+    token
+        .set_cpi_guard_allowlist(
+            &alice.pubkey(),
+            &alice.pubkey(),
+            &[Pubkey::new_unique()],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let error = token
+        .process_ixs(&[mk_approve(true)], &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::CpiGuardApproveBlocked));
+
+    // allow the padding program
+    token
+        .set_cpi_guard_allowlist(
+            &alice.pubkey(),
+            &alice.pubkey(),
+            &[spl_instruction_padding::id()],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let alice_state = token.get_account_info(&alice.pubkey()).await.unwrap();
+    let extension = alice_state.get_extension::<CpiGuardAllowlist>().unwrap();
+    assert!(extension.contains(&spl_instruction_padding::id()));
+
+    // the invoking program can't be checked without the instructions sysvar
+    token.get_new_latest_blockhash().await.unwrap();
+    let error = token
+        .process_ixs(&[mk_approve(false)], &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::CpiGuardApproveBlocked));
+
+    // approve works through the allowed program
+    token
+        .process_ixs(&[mk_approve(true)], &[&alice])
+        .await
+        .unwrap();
+    let alice_state = token.get_account_info(&alice.pubkey()).await.unwrap();
+    assert_eq!(alice_state.base.delegate, Some(bob.pubkey()).into());
+
+    // the guard itself remains enabled
+    let extension = alice_state.get_extension::<CpiGuard>().unwrap();
+    assert!(bool::from(extension.lock_cpi));
+}
@@ -89,6 +89,7 @@ async fn run_basic(
                 &bob.pubkey(),
                 &alice.pubkey(),
                 delegated_amount,
+                None,
                 &[&alice],
             )
             .await
@@ -99,6 +100,7 @@ async fn run_basic(
                 &bob.pubkey(),
                 &alice.pubkey(),
                 delegated_amount,
+                None,
                 &[&alice],
             )
             .await
@@ -0,0 +1,153 @@
+mod program_test;
+use {
+    ethnum::U256,
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        clock::Clock, instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{delegate_expiry::DelegateExpiry, BaseStateWithExtensions},
+    },
+    spl_token_client::token::TokenError as TokenClientError,
+};
+
+fn client_error(index: u8, token_error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(index, InstructionError::Custom(token_error as u32)),
+    )))
+}
+
+#[tokio::test]
+async fn expired_delegate_is_revoked() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext {
+        mint_authority,
+        token,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(100),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let mut clock = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap();
+
+    // fail, expiry already passed
+    let error = token
+        .approve(
+            &alice_account,
+            &bob.pubkey(),
+            &alice.pubkey(),
+            U256::new(10),
+            Some(clock.unix_timestamp),
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(1, TokenError::InvalidDelegateExpiry));
+
+    // approve until a minute from now, reallocating the account
+    let expires_at = clock.unix_timestamp + 60;
+    token
+        .approve(
+            &alice_account,
+            &bob.pubkey(),
+            &alice.pubkey(),
+            U256::new(10),
+            Some(expires_at),
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    let extension = state.get_extension::<DelegateExpiry>().unwrap();
+    assert_eq!(i64::from(extension.expires_at), expires_at);
+
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &bob.pubkey(),
+            U256::ONE,
+            &[&bob],
+        )
+        .await
+        .unwrap();
+
+    // once expired, the delegate can neither transfer nor burn
+    clock.unix_timestamp = expires_at;
+    context.context.lock().await.set_sysvar(&clock);
+    let error = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &bob.pubkey(),
+            U256::ONE,
+            &[&bob],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(0, TokenError::OwnerMismatch));
+    let error = token
+        .burn(&alice_account, &bob.pubkey(), U256::ONE, &[&bob])
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(0, TokenError::OwnerMismatch));
+
+    // a plain approval clears the expiry
+    token
+        .approve(
+            &alice_account,
+            &bob.pubkey(),
+            &alice.pubkey(),
+            U256::new(10),
+            None,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    let extension = state.get_extension::<DelegateExpiry>().unwrap();
+    assert_eq!(i64::from(extension.expires_at), 0);
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &bob.pubkey(),
+            U256::ONE,
+            &[&bob],
+        )
+        .await
+        .unwrap();
+}
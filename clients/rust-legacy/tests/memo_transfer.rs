@@ -16,7 +16,10 @@ use {
     },
     spl_token_2022::{
         error::TokenError,
-        extension::{memo_transfer::MemoTransfer, BaseStateWithExtensions, ExtensionType},
+        extension::{
+            memo_transfer::{outgoing_memo_required, MemoRules, MemoTransfer, MemoTransferRules},
+            BaseStateWithExtensions, ExtensionType,
+        },
     },
     spl_token_client::token::TokenError as TokenClientError,
     std::sync::Arc,
@@ -268,3 +271,183 @@ async fn require_memo_transfers_with_realloc() {
 
     test_memo_transfers(context.context, token_context, alice_account, bob_account).await;
 }
+
+fn client_error(index: u8, token_error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(index, InstructionError::Custom(token_error as u32)),
+    )))
+}
+
+#[tokio::test]
+async fn require_outgoing_memo_transfers_with_rules() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext {
+        mint_authority,
+        token,
+        alice,
+        bob,
+        ..
+    } = context.token_context.unwrap();
+
+    // create token accounts
+    token
+        .create_auxiliary_token_account_with_extension_space(
+            &alice,
+            &alice.pubkey(),
+            vec![
+                ExtensionType::MemoTransfer,
+                ExtensionType::MemoTransferRules,
+            ],
+        )
+        .await
+        .unwrap();
+    let alice_account = alice.pubkey();
+    token
+        .create_auxiliary_token_account(&bob, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob.pubkey();
+
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(4242),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // rules that can't be satisfied are rejected
+    let invalid_rules = MemoRules {
+        min_len: 11.into(),
+        max_len: 10.into(),
+        ..MemoRules::default()
+    };
+    let err = token
+        .configure_transfer_memos(
+            &alice_account,
+            &alice.pubkey(),
+            false,
+            true,
+            invalid_rules,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, client_error(0, TokenError::InvalidMemoRules));
+
+    // require deposit-tagged memos on transfers out of alice_account
+    let rules = MemoRules::new(6, Some(10), b"dep-").unwrap();
+    token
+        .configure_transfer_memos(
+            &alice_account,
+            &alice.pubkey(),
+            false,
+            true,
+            rules,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let alice_state = token.get_account_info(&alice_account).await.unwrap();
+    let extension = alice_state.get_extension::<MemoTransfer>().unwrap();
+    assert!(!bool::from(extension.require_incoming_transfer_memos));
+    let extension = alice_state.get_extension::<MemoTransferRules>().unwrap();
+    assert!(bool::from(extension.require_outgoing_transfer_memos));
+    assert_eq!(extension.rules, rules);
+
+    // transfers into alice_account don't need a memo
+    token
+        .mint_to(
+            &bob_account,
+            &mint_authority.pubkey(),
+            U256::new(1),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .transfer(
+            &bob_account,
+            &alice_account,
+            &bob.pubkey(),
+            U256::new(1),
+            &[&bob],
+        )
+        .await
+        .unwrap();
+
+    // transfer out without memo
+    let err = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::new(10),
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, client_error(0, TokenError::NoMemo));
+
+    // memos breaking the rules
+    for memo in ["tag-42", "dep-4", "dep-4242424"] {
+        let err = token
+            .with_memo(memo, vec![alice.pubkey()])
+            .transfer(
+                &alice_account,
+                &bob_account,
+                &alice.pubkey(),
+                U256::new(10),
+                &[&alice],
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err, client_error(1, TokenError::MemoRulesViolated));
+    }
+    let bob_state = token.get_account_info(&bob_account).await.unwrap();
+    assert_eq!(bob_state.base.amount, 0);
+
+    // memo following the rules
+    token
+        .with_memo("dep-42", vec![alice.pubkey()])
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::new(10),
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let bob_state = token.get_account_info(&bob_account).await.unwrap();
+    assert_eq!(bob_state.base.amount, 10);
+
+    // an account requiring outgoing memos can't shed the rules
+    let err = token
+        .remove_account_extensions(
+            &alice_account,
+            &alice.pubkey(),
+            &[ExtensionType::MemoTransferRules],
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, client_error(0, TokenError::ExtensionNotRemovable));
+
+    // but incoming memos aren't required, so the flag can go
+    token
+        .remove_account_extensions(
+            &alice_account,
+            &alice.pubkey(),
+            &[ExtensionType::MemoTransfer],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let alice_state = token.get_account_info(&alice_account).await.unwrap();
+    assert!(alice_state.get_extension::<MemoTransfer>().is_err());
+    assert!(outgoing_memo_required(&alice_state));
+}
@@ -0,0 +1,179 @@
+mod program_test;
+use {
+    ethnum::U256,
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            multi_delegate::{MultiDelegate, MAX_DELEGATES},
+            BaseStateWithExtensions,
+        },
+    },
+    spl_token_client::token::TokenError as TokenClientError,
+};
+
+fn client_error(index: u8, token_error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(index, InstructionError::Custom(token_error as u32)),
+    )))
+}
+
+#[tokio::test]
+async fn concurrent_delegates() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext {
+        mint_authority,
+        token,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(100),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // bob is the main delegate, carol and dave go in the table
+    token
+        .approve(
+            &alice_account,
+            &bob.pubkey(),
+            &alice.pubkey(),
+            U256::new(10),
+            None,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let carol = Keypair::new();
+    let dave = Keypair::new();
+    for delegate in [&carol, &dave] {
+        token
+            .approve_table_delegate(
+                &alice_account,
+                &delegate.pubkey(),
+                &alice.pubkey(),
+                U256::new(20),
+                None,
+                &[&alice],
+            )
+            .await
+            .unwrap();
+    }
+
+    // every delegate spends its own allowance
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &bob.pubkey(),
+            U256::new(5),
+            &[&bob],
+        )
+        .await
+        .unwrap();
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &carol.pubkey(),
+            U256::new(15),
+            &[&carol],
+        )
+        .await
+        .unwrap();
+    token
+        .burn(&alice_account, &dave.pubkey(), U256::new(20), &[&dave])
+        .await
+        .unwrap();
+    let error = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &carol.pubkey(),
+            U256::new(6),
+            &[&carol],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(0, TokenError::InsufficientFunds));
+
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(state.base.amount, U256::new(60));
+    assert_eq!(state.base.delegate, Some(bob.pubkey()).into());
+    assert_eq!(state.base.delegated_amount, U256::new(5));
+    let extension = state.get_extension::<MultiDelegate>().unwrap();
+    assert_eq!(extension.find_active(&carol.pubkey(), 0), Some(0));
+    // dave used up the allowance
+    assert_eq!(extension.find_active(&dave.pubkey(), 0), None);
+
+    // carol revokes herself
+    token
+        .revoke_table_delegate(&alice_account, &carol.pubkey(), &carol.pubkey(), &[&carol])
+        .await
+        .unwrap();
+    let error = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &carol.pubkey(),
+            U256::ONE,
+            &[&carol],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(0, TokenError::OwnerMismatch));
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert!(state.get_extension::<MultiDelegate>().unwrap().is_empty());
+
+    // the table is bounded
+    for _ in 0..MAX_DELEGATES {
+        token
+            .approve_table_delegate(
+                &alice_account,
+                &Pubkey::new_unique(),
+                &alice.pubkey(),
+                U256::ONE,
+                None,
+                &[&alice],
+            )
+            .await
+            .unwrap();
+    }
+    let error = token
+        .approve_table_delegate(
+            &alice_account,
+            &Pubkey::new_unique(),
+            &alice.pubkey(),
+            U256::ONE,
+            None,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(1, TokenError::DelegateTableFull));
+}
@@ -331,3 +331,165 @@ async fn transfer_checked_with_fee() {
         )))
     );
 }
+
+#[tokio::test]
+async fn recover() {
+    let test_transfer_amount = U256::new(100);
+    let recovery_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![
+            ExtensionInitializationParams::NonTransferable,
+            ExtensionInitializationParams::NonTransferableRecovery {
+                authority: Some(recovery_authority.pubkey()),
+            },
+        ])
+        .await
+        .unwrap();
+
+    let TokenContext {
+        mint_authority,
+        token,
+        alice,
+        bob,
+        ..
+    } = context.token_context.unwrap();
+
+    token
+        .create_auxiliary_token_account(&alice, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice.pubkey();
+    token
+        .create_auxiliary_token_account(&bob, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob.pubkey();
+
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            test_transfer_amount,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // the owner still cannot transfer
+    let error = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            test_transfer_amount,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::NonTransferable as u32)
+            )
+        )))
+    );
+
+    // nor can the owner recover
+    let error = token
+        .recover(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            test_transfer_amount,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // the recovery authority moves the balance
+    token
+        .recover(
+            &alice_account,
+            &bob_account,
+            &recovery_authority.pubkey(),
+            test_transfer_amount,
+            &[&recovery_authority],
+        )
+        .await
+        .unwrap();
+    let alice_state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(alice_state.base.amount, U256::ZERO);
+    let bob_state = token.get_account_info(&bob_account).await.unwrap();
+    assert_eq!(bob_state.base.amount, test_transfer_amount);
+}
+
+#[tokio::test]
+async fn recover_not_enabled() {
+    let test_transfer_amount = U256::new(100);
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::NonTransferable])
+        .await
+        .unwrap();
+
+    let TokenContext {
+        mint_authority,
+        token,
+        alice,
+        bob,
+        ..
+    } = context.token_context.unwrap();
+
+    token
+        .create_auxiliary_token_account(&alice, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice.pubkey();
+    token
+        .create_auxiliary_token_account(&bob, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob.pubkey();
+
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            test_transfer_amount,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let error = token
+        .recover(
+            &alice_account,
+            &bob_account,
+            &mint_authority.pubkey(),
+            test_transfer_amount,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::RecoveryNotEnabled as u32)
+            )
+        )))
+    );
+}
@@ -4,13 +4,16 @@ use {
     program_test::{TestContext, TokenContext},
     solana_program_test::tokio,
     solana_sdk::{
-        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
-        transaction::TransactionError, transport::TransportError,
+        clock::Clock, instruction::InstructionError, pubkey::Pubkey, signature::Signer,
+        signer::keypair::Keypair, transaction::TransactionError, transport::TransportError,
     },
     spl_token_2022::{
         error::TokenError,
         extension::{
-            pausable::{PausableAccount, PausableConfig},
+            pausable::{
+                PausableAccount, PausableConfig, PausableOperation, PausableOperations,
+                PauseSchedule,
+            },
             BaseStateWithExtensions,
         },
         instruction::AuthorityType,
@@ -34,7 +37,9 @@ async fn success_initialize() {
     let state = token.get_mint_info().await.unwrap();
     let extension = state.get_extension::<PausableConfig>().unwrap();
     assert_eq!(Option::<Pubkey>::from(extension.authority), Some(authority));
-    assert!(!bool::from(extension.paused));
+    for operation in PausableOperation::ALL {
+        assert!(!extension.is_paused(None, operation));
+    }
 
     let account = Keypair::new();
     token
@@ -76,11 +81,15 @@ async fn set_authority() {
         Some(new_authority.pubkey()).try_into().unwrap(),
     );
     token
-        .pause(&new_authority.pubkey(), &[&new_authority])
+        .pause(
+            &new_authority.pubkey(),
+            &PausableOperation::ALL,
+            &[&new_authority],
+        )
         .await
         .unwrap();
     let err = token
-        .pause(&authority.pubkey(), &[&authority])
+        .pause(&authority.pubkey(), &PausableOperation::ALL, &[&authority])
         .await
         .unwrap_err();
     assert_eq!(
@@ -135,7 +144,7 @@ async fn pause_mint() {
     let alice_account = alice_account.pubkey();
 
     token
-        .pause(&authority.pubkey(), &[&authority])
+        .pause(&authority.pubkey(), &PausableOperation::ALL, &[&authority])
         .await
         .unwrap();
 
@@ -216,7 +225,7 @@ async fn pause_burn() {
         .unwrap();
 
     token
-        .pause(&authority.pubkey(), &[&authority])
+        .pause(&authority.pubkey(), &PausableOperation::ALL, &[&authority])
         .await
         .unwrap();
 
@@ -296,7 +305,7 @@ async fn pause_transfer() {
         .unwrap();
 
     token
-        .pause(&authority.pubkey(), &[&authority])
+        .pause(&authority.pubkey(), &PausableOperation::ALL, &[&authority])
         .await
         .unwrap();
 
@@ -365,3 +374,383 @@ async fn pause_transfer() {
         )))
     );
 }
+
+#[tokio::test]
+async fn pause_single_operation() {
+    let authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![
+            ExtensionInitializationParams::PausableConfig {
+                authority: authority.pubkey(),
+            },
+            ExtensionInitializationParams::PausableOperations,
+        ])
+        .await
+        .unwrap();
+    let TokenContext {
+        mint_authority,
+        token,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+
+    token
+        .pause(
+            &authority.pubkey(),
+            &[PausableOperation::Transfer],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<PausableConfig>().unwrap();
+    let operations = state.get_extension::<PausableOperations>().ok();
+    assert!(!extension.is_paused(operations, PausableOperation::Mint));
+    assert!(!extension.is_paused(operations, PausableOperation::Burn));
+    assert!(extension.is_paused(operations, PausableOperation::Transfer));
+
+    // minting and burning still work
+    let amount = U256::new(10);
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            amount,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .burn(&alice_account, &alice.pubkey(), U256::ONE, &[&alice])
+        .await
+        .unwrap();
+
+    let error = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::ONE,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::MintPaused as u32)
+            )
+        )))
+    );
+
+    // pause burning, resume transferring
+    token
+        .pause(
+            &authority.pubkey(),
+            &[PausableOperation::Burn],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    token
+        .resume(
+            &authority.pubkey(),
+            &[PausableOperation::Transfer],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::ONE,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+
+    let error = token
+        .burn(&alice_account, &alice.pubkey(), U256::ONE, &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::MintPaused as u32)
+            )
+        )))
+    );
+
+    // resuming everything clears every flag
+    token
+        .resume(&authority.pubkey(), &PausableOperation::ALL, &[&authority])
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<PausableConfig>().unwrap();
+    let operations = state.get_extension::<PausableOperations>().unwrap();
+    assert_eq!(*operations, PausableOperations::default());
+    for operation in PausableOperation::ALL {
+        assert!(!extension.is_paused(Some(operations), operation));
+    }
+
+    // resuming a single operation of a paused mint keeps the others paused
+    token
+        .pause(&authority.pubkey(), &PausableOperation::ALL, &[&authority])
+        .await
+        .unwrap();
+    token
+        .resume(
+            &authority.pubkey(),
+            &[PausableOperation::Transfer],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::ONE,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let error = token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            amount,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::MintPaused as u32)
+            )
+        )))
+    );
+}
+
+#[tokio::test]
+async fn operations_and_schedule_require_extensions() {
+    let authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::PausableConfig {
+            authority: authority.pubkey(),
+        }])
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    let error = token
+        .pause(
+            &authority.pubkey(),
+            &[PausableOperation::Transfer],
+            &[&authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::ExtensionNotFound as u32)
+            )
+        )))
+    );
+
+    let error = token
+        .set_pause_schedule(&authority.pubkey(), Some(1), None, &[&authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::ExtensionNotFound as u32)
+            )
+        )))
+    );
+}
+
+#[tokio::test]
+async fn scheduled_pause_and_resume() {
+    let authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![
+            ExtensionInitializationParams::PausableConfig {
+                authority: authority.pubkey(),
+            },
+            ExtensionInitializationParams::PauseSchedule,
+        ])
+        .await
+        .unwrap();
+    let TokenContext {
+        mint_authority,
+        token,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(10),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let mut clock = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap();
+    let pause_timestamp = clock.unix_timestamp + 100;
+    let resume_timestamp = clock.unix_timestamp + 200;
+
+    // fail resume before pause
+    let error = token
+        .set_pause_schedule(
+            &authority.pubkey(),
+            Some(resume_timestamp),
+            Some(pause_timestamp),
+            &[&authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::InvalidPauseSchedule as u32)
+            )
+        )))
+    );
+
+    token
+        .set_pause_schedule(
+            &authority.pubkey(),
+            Some(pause_timestamp),
+            Some(resume_timestamp),
+            &[&authority],
+        )
+        .await
+        .unwrap();
+
+    // not paused yet
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::ONE,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+
+    // paused once the pause timestamp is reached
+    clock.unix_timestamp = pause_timestamp;
+    context.context.lock().await.set_sysvar(&clock);
+    let error = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::new(2),
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::MintPaused as u32)
+            )
+        )))
+    );
+
+    // resumed once the resume timestamp is reached
+    clock.unix_timestamp = resume_timestamp;
+    context.context.lock().await.set_sysvar(&clock);
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::new(3),
+            &[&alice],
+        )
+        .await
+        .unwrap();
+
+    // the elapsed schedule does not override a later manual pause
+    token
+        .pause(&authority.pubkey(), &PausableOperation::ALL, &[&authority])
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let schedule = state.get_extension::<PauseSchedule>().unwrap();
+    assert_eq!(*schedule, PauseSchedule::default());
+    let extension = state.get_extension::<PausableConfig>().unwrap();
+    for operation in PausableOperation::ALL {
+        assert!(extension.is_paused(None, operation));
+    }
+}
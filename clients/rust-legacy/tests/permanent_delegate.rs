@@ -9,7 +9,12 @@ use {
     },
     spl_token_2022::{
         error::TokenError,
-        extension::{permanent_delegate::PermanentDelegate, BaseStateWithExtensions},
+        extension::{
+            permanent_delegate::{
+                PermanentDelegate, PermanentDelegateOperations, PermanentDelegateScope,
+            },
+            BaseStateWithExtensions, ExtensionType,
+        },
         instruction,
     },
     spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
@@ -247,6 +252,207 @@ async fn success_burn() {
     assert_eq!(destination.base.amount, 0);
 }
 
+#[tokio::test]
+async fn burn_only_scope() {
+    let delegate = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![
+            ExtensionInitializationParams::PermanentDelegate {
+                delegate: delegate.pubkey(),
+            },
+            ExtensionInitializationParams::PermanentDelegateScope {
+                scope: PermanentDelegateOperations::Burn,
+            },
+        ])
+        .await
+        .unwrap();
+    let token_context = context.token_context.unwrap();
+    let state = token_context.token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<PermanentDelegate>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.delegate),
+        Some(delegate.pubkey())
+    );
+    let extension = state.get_extension::<PermanentDelegateScope>().unwrap();
+    assert_eq!(extension.scope(), Ok(PermanentDelegateOperations::Burn));
+
+    let amount = U256::new(10);
+    let (alice_account, bob_account) = setup_accounts(&token_context, amount).await;
+
+    // fail transfer, out of scope
+    let error = token_context
+        .token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &delegate.pubkey(),
+            amount,
+            &[&delegate],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // burn works
+    token_context
+        .token
+        .burn(&alice_account, &delegate.pubkey(), amount, &[&delegate])
+        .await
+        .unwrap();
+    let source = token_context
+        .token
+        .get_account_info(&alice_account)
+        .await
+        .unwrap();
+    assert_eq!(source.base.amount, 0);
+}
+
+#[tokio::test]
+async fn narrow_scope() {
+    let delegate = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::PermanentDelegate {
+            delegate: delegate.pubkey(),
+        }])
+        .await
+        .unwrap();
+    let token_context = context.token_context.unwrap();
+    let amount = U256::new(10);
+    let (alice_account, bob_account) = setup_accounts(&token_context, amount).await;
+
+    // fail, wrong signature
+    let wrong = Keypair::new();
+    let err = token_context
+        .token
+        .narrow_permanent_delegate_scope(
+            &wrong.pubkey(),
+            PermanentDelegateOperations::Transfer,
+            &[&wrong],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // fail, the mint has no scope extension yet
+    let err = token_context
+        .token
+        .narrow_permanent_delegate_scope(
+            &delegate.pubkey(),
+            PermanentDelegateOperations::Transfer,
+            &[&delegate],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::ExtensionNotFound as u32)
+            )
+        )))
+    );
+
+    // the added scope starts out covering everything
+    token_context
+        .token
+        .reallocate_mint(
+            &token_context.mint_authority.pubkey(),
+            &[ExtensionType::PermanentDelegateScope],
+            &[&token_context.mint_authority],
+        )
+        .await
+        .unwrap();
+    let state = token_context.token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<PermanentDelegateScope>().unwrap();
+    assert_eq!(
+        extension.scope(),
+        Ok(PermanentDelegateOperations::TransferAndBurn)
+    );
+
+    // narrow to transfers only
+    token_context
+        .token
+        .narrow_permanent_delegate_scope(
+            &delegate.pubkey(),
+            PermanentDelegateOperations::Transfer,
+            &[&delegate],
+        )
+        .await
+        .unwrap();
+    let state = token_context.token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<PermanentDelegateScope>().unwrap();
+    assert_eq!(extension.scope(), Ok(PermanentDelegateOperations::Transfer));
+
+    // fail burn, out of scope
+    let err = token_context
+        .token
+        .burn(&alice_account, &delegate.pubkey(), amount, &[&delegate])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // transfer still works
+    token_context
+        .token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &delegate.pubkey(),
+            amount,
+            &[&delegate],
+        )
+        .await
+        .unwrap();
+
+    // fail, can't widen again
+    for scope in [
+        PermanentDelegateOperations::TransferAndBurn,
+        PermanentDelegateOperations::Burn,
+    ] {
+        let err = token_context
+            .token
+            .narrow_permanent_delegate_scope(&delegate.pubkey(), scope, &[&delegate])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err,
+            TokenClientError::Client(Box::new(TransportError::TransactionError(
+                TransactionError::InstructionError(
+                    0,
+                    InstructionError::Custom(TokenError::PermanentDelegateScopeWidened as u32)
+                )
+            )))
+        );
+    }
+}
+
 #[tokio::test]
 async fn fail_without_extension() {
     let delegate = Pubkey::new_unique();
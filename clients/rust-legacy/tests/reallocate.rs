@@ -6,6 +6,7 @@ use {
     solana_sdk::{
         instruction::InstructionError,
         program_option::COption,
+        program_pack::Pack,
         pubkey::Pubkey,
         signature::Signer,
         signer::keypair::Keypair,
@@ -13,7 +14,11 @@ use {
         transaction::{Transaction, TransactionError},
         transport::TransportError,
     },
-    spl_token_2022::{error::TokenError, extension::ExtensionType, state::Account},
+    spl_token_2022::{
+        error::TokenError,
+        extension::{metadata_pointer::MetadataPointer, BaseStateWithExtensions, ExtensionType},
+        state::{Account, Mint},
+    },
     spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
     test_case::test_case,
 };
@@ -284,3 +289,317 @@ async fn reallocate_updates_native_rent_exemption(
         assert!(pre_rent_exempt_reserve < post_rent_exempt_reserve);
     }
 }
+
+#[tokio::test]
+async fn reallocate_mint() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext {
+        token,
+        alice,
+        mint_authority,
+        ..
+    } = context.token_context.unwrap();
+
+    // reallocate fails on extensions that can't be added to a live mint
+    for extension_type in [
+        ExtensionType::TransferFeeConfig,
+        ExtensionType::ImmutableOwner,
+        ExtensionType::Pausable,
+        ExtensionType::PausableOperations,
+        ExtensionType::PauseSchedule,
+    ] {
+        let error = token
+            .reallocate_mint(
+                &mint_authority.pubkey(),
+                &[extension_type],
+                &[&mint_authority],
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error,
+            TokenClientError::Client(Box::new(TransportError::TransactionError(
+                TransactionError::InstructionError(
+                    0,
+                    InstructionError::Custom(TokenError::InvalidState as u32)
+                )
+            )))
+        );
+    }
+
+    // reallocate fails on invalid authority
+    let error = token
+        .reallocate_mint(
+            &alice.pubkey(),
+            &[ExtensionType::MetadataPointer],
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // reallocate succeeds, and dedupes extensions
+    token
+        .reallocate_mint(
+            &mint_authority.pubkey(),
+            &[
+                ExtensionType::MetadataPointer,
+                ExtensionType::GroupPointer,
+                ExtensionType::MetadataPointer,
+            ],
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    let mint = token.get_account(*token.get_address()).await.unwrap();
+    assert_eq!(
+        mint.data.len(),
+        ExtensionType::try_calculate_account_len::<Mint>(&[
+            ExtensionType::MetadataPointer,
+            ExtensionType::GroupPointer,
+        ])
+        .unwrap()
+    );
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<MetadataPointer>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.authority),
+        Some(mint_authority.pubkey())
+    );
+    assert_eq!(Option::<Pubkey>::from(extension.metadata_address), None);
+
+    // the mint authority configures the new extension
+    let metadata_address = Pubkey::new_unique();
+    token
+        .update_metadata_address(
+            &mint_authority.pubkey(),
+            Some(metadata_address),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // extensions already on the mint are left untouched
+    token
+        .reallocate_mint(
+            &mint_authority.pubkey(),
+            &[ExtensionType::MetadataPointer],
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<MetadataPointer>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.metadata_address),
+        Some(metadata_address)
+    );
+}
+
+#[tokio::test]
+async fn reallocate_mint_invalid_combination() {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::InterestBearingConfig {
+            rate_authority: None,
+            rate: 5,
+        }])
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        ..
+    } = context.token_context.unwrap();
+
+    let error = token
+        .reallocate_mint(
+            &mint_authority.pubkey(),
+            &[ExtensionType::ScaledUiAmount],
+            &[&mint_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::InvalidExtensionCombination as u32)
+            )
+        )))
+    );
+}
+
+#[tokio::test]
+async fn remove_account_extensions() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext { token, alice, .. } = context.token_context.unwrap();
+    let context = context.context.clone();
+
+    // fund alice to receive the freed rent
+    {
+        let context = context.lock().await;
+        let tx = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &context.payer.pubkey(),
+                &alice.pubkey(),
+                1_000_000_000,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let extensions = [ExtensionType::MemoTransfer, ExtensionType::CpiGuard];
+    token
+        .reallocate(&alice_account, &alice.pubkey(), &extensions, &[&alice])
+        .await
+        .unwrap();
+    token
+        .enable_required_transfer_memos(&alice_account, &alice.pubkey(), &[&alice])
+        .await
+        .unwrap();
+    token
+        .enable_cpi_guard(&alice_account, &alice.pubkey(), &[&alice])
+        .await
+        .unwrap();
+
+    // fail, extensions still in use
+    let error = token
+        .remove_account_extensions(&alice_account, &alice.pubkey(), &extensions, &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::ExtensionNotRemovable as u32)
+            )
+        )))
+    );
+
+    token
+        .disable_required_transfer_memos(&alice_account, &alice.pubkey(), &[&alice])
+        .await
+        .unwrap();
+
+    // fail, wrong owner
+    let bob = Keypair::new();
+    let error = token
+        .remove_account_extensions(
+            &alice_account,
+            &bob.pubkey(),
+            &[ExtensionType::MemoTransfer],
+            &[&bob],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // success, only the disabled extension is removed
+    let alice_lamports = {
+        let context = context.lock().await;
+        context
+            .banks_client
+            .get_balance(alice.pubkey())
+            .await
+            .unwrap()
+    };
+    token
+        .remove_account_extensions(
+            &alice_account,
+            &alice.pubkey(),
+            &[ExtensionType::MemoTransfer],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let account = token.get_account(alice_account).await.unwrap();
+    assert_eq!(
+        account.data.len(),
+        ExtensionType::try_calculate_account_len::<Account>(&[ExtensionType::CpiGuard]).unwrap()
+    );
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(
+        state.get_extension_types().unwrap(),
+        vec![ExtensionType::CpiGuard]
+    );
+
+    // the freed rent went back to alice
+    {
+        let context = context.lock().await;
+        let rent = context.banks_client.get_rent().await.unwrap();
+        assert_eq!(account.lamports, rent.minimum_balance(account.data.len()));
+        let new_alice_lamports = context
+            .banks_client
+            .get_balance(alice.pubkey())
+            .await
+            .unwrap();
+        assert!(new_alice_lamports > alice_lamports);
+    }
+
+    // fail, extensions that can never be removed
+    let error = token
+        .remove_account_extensions(
+            &alice_account,
+            &alice.pubkey(),
+            &[ExtensionType::ImmutableOwner],
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::ExtensionNotRemovable as u32)
+            )
+        )))
+    );
+
+    // success, removing the last extension shrinks the account to its base
+    token
+        .disable_cpi_guard(&alice_account, &alice.pubkey(), &[&alice])
+        .await
+        .unwrap();
+    token
+        .remove_account_extensions(
+            &alice_account,
+            &alice.pubkey(),
+            &[ExtensionType::CpiGuard],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let account = token.get_account(alice_account).await.unwrap();
+    assert_eq!(account.data.len(), Account::LEN);
+}
@@ -375,4 +375,15 @@ async fn amount_conversions() {
         .process_transaction(transaction)
         .await
         .unwrap();
+
+    // the client uses the same exact conversion, even for the largest amounts
+    let ui_amount = token.amount_to_ui_amount(U256::MAX).await.unwrap();
+    assert_eq!(
+        ui_amount,
+        "578960446186580977117854925043439539266349923328202820197287920039565.648199675"
+    );
+    assert_eq!(
+        token.ui_amount_to_amount(&ui_amount).await.unwrap(),
+        U256::MAX
+    );
 }
@@ -0,0 +1,276 @@
+mod program_test;
+use {
+    ethnum::U256,
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{supply_cap::SupplyCapConfig, BaseStateWithExtensions},
+        instruction::AuthorityType,
+    },
+    spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
+    std::convert::TryInto,
+};
+
+const TEST_MAX_SUPPLY: U256 = U256::new(1_000);
+
+#[tokio::test]
+async fn success_initialize() {
+    let authority = Pubkey::new_unique();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::SupplyCap {
+            authority: Some(authority),
+            max_supply: TEST_MAX_SUPPLY,
+        }])
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.unwrap();
+
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<SupplyCapConfig>().unwrap();
+    assert_eq!(Option::<Pubkey>::from(extension.authority), Some(authority));
+    assert_eq!(U256::from(extension.max_supply), TEST_MAX_SUPPLY);
+}
+
+#[tokio::test]
+async fn mint_up_to_cap() {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::SupplyCap {
+            authority: None,
+            max_supply: TEST_MAX_SUPPLY,
+        }])
+        .await
+        .unwrap();
+    let TokenContext {
+        mint_authority,
+        token,
+        token_unchecked,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            TEST_MAX_SUPPLY - 1,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // fail, exceeds cap
+    let error = token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(2),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::MaxSupplyExceeded as u32)
+            )
+        )))
+    );
+    let error = token_unchecked
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(2),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::MaxSupplyExceeded as u32)
+            )
+        )))
+    );
+
+    // minting exactly up to the cap is fine
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::ONE,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    assert_eq!(U256::from(state.base.supply), TEST_MAX_SUPPLY);
+
+    // burning frees up room under the cap
+    token
+        .burn(&alice_account, &alice.pubkey(), U256::new(10), &[&alice])
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(10),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn update_max_supply() {
+    let authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::SupplyCap {
+            authority: Some(authority.pubkey()),
+            max_supply: TEST_MAX_SUPPLY,
+        }])
+        .await
+        .unwrap();
+    let TokenContext {
+        mint_authority,
+        token,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let amount = U256::new(100);
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            amount,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // fail, wrong signer
+    let error = token
+        .update_max_supply(&alice.pubkey(), amount, &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // fail, cap can't be raised
+    let error = token
+        .update_max_supply(&authority.pubkey(), TEST_MAX_SUPPLY + 1, &[&authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::MaxSupplyIncrease as u32)
+            )
+        )))
+    );
+
+    // fail, cap below current supply
+    let error = token
+        .update_max_supply(&authority.pubkey(), amount - 1, &[&authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::MaxSupplyExceeded as u32)
+            )
+        )))
+    );
+
+    // success, lowered to the current supply
+    token
+        .update_max_supply(&authority.pubkey(), amount, &[&authority])
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<SupplyCapConfig>().unwrap();
+    assert_eq!(U256::from(extension.max_supply), amount);
+    let error = token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::ONE,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::MaxSupplyExceeded as u32)
+            )
+        )))
+    );
+
+    // set authority to none, cap is fixed
+    token
+        .set_authority(
+            token.get_address(),
+            &authority.pubkey(),
+            None,
+            AuthorityType::SupplyCap,
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<SupplyCapConfig>().unwrap();
+    assert_eq!(extension.authority, None.try_into().unwrap());
+    let error = token
+        .update_max_supply(&authority.pubkey(), amount, &[&authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::NoAuthorityExists as u32)
+            )
+        )))
+    );
+}
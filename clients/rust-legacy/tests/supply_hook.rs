@@ -0,0 +1,337 @@
+mod program_test;
+use {
+    ethnum::U256,
+    program_test::TestContext,
+    solana_program_test::{processor, tokio, ProgramTest},
+    solana_sdk::{
+        account::Account,
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        instruction::InstructionError,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        signature::Signer,
+        signer::keypair::Keypair,
+        transaction::TransactionError,
+        transport::TransportError,
+    },
+    spl_tlv_account_resolution::state::ExtraAccountMetaList,
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            supply_hook::{
+                interface::{self, get_extra_account_metas_address, ExecuteInstruction},
+                SupplyHook,
+            },
+            BaseStateWithExtensions,
+        },
+        instruction::AuthorityType,
+    },
+    spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
+    std::{convert::TryInto, sync::Arc},
+};
+
+/// Generate an empty validation account to be used directly for testing
+fn empty_validation_data() -> Vec<u8> {
+    let account_size = ExtraAccountMetaList::size_of(0).unwrap();
+    let mut data = vec![0; account_size];
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &[]).unwrap();
+    data
+}
+
+// supply hook program that accepts every mint and burn, as long as the token
+// program invoked it
+fn process_accepting_supply_hook(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    interface::decode_execute(input)?;
+    let account_info_iter = &mut accounts.iter();
+    let _source_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    if !interface::is_executing(mint_info)? {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+// supply hook program that rejects every mint and burn
+fn process_rejecting_supply_hook(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    interface::decode_execute(input)?;
+    Err(ProgramError::InvalidInstructionData)
+}
+
+async fn setup(
+    mint: Keypair,
+    reject: bool,
+    program_id: &Pubkey,
+    authority: &Pubkey,
+) -> TestContext {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("spl_token_2022", spl_token_2022::id(), None);
+    if reject {
+        program_test.add_program(
+            "supply_hook_reject",
+            *program_id,
+            processor!(process_rejecting_supply_hook),
+        );
+    } else {
+        program_test.add_program(
+            "supply_hook_accept",
+            *program_id,
+            processor!(process_accepting_supply_hook),
+        );
+    }
+    let validation_address = get_extra_account_metas_address(&mint.pubkey(), program_id);
+    program_test.add_account(
+        validation_address,
+        Account {
+            lamports: 1_000_000_000, // a lot, just to be safe
+            data: empty_validation_data(),
+            owner: *program_id,
+            ..Account::default()
+        },
+    );
+
+    let context = program_test.start_with_context().await;
+    let context = Arc::new(tokio::sync::Mutex::new(context));
+    let mut context = TestContext {
+        context,
+        token_context: None,
+    };
+    context
+        .init_token_with_mint_keypair_and_freeze_authority(
+            mint,
+            vec![ExtensionInitializationParams::SupplyHook {
+                authority: Some(*authority),
+                program_id: Some(*program_id),
+            }],
+            None,
+        )
+        .await
+        .unwrap();
+    context
+}
+
+#[tokio::test]
+async fn success_init() {
+    let authority = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let token = setup(Keypair::new(), false, &program_id, &authority)
+        .await
+        .token_context
+        .take()
+        .unwrap()
+        .token;
+
+    let state = token.get_mint_info().await.unwrap();
+    assert!(state.base.is_initialized);
+    let extension = state.get_extension::<SupplyHook>().unwrap();
+    assert_eq!(extension.authority, Some(authority).try_into().unwrap());
+    assert_eq!(extension.program_id, Some(program_id).try_into().unwrap());
+}
+
+#[tokio::test]
+async fn success_mint_and_burn() {
+    let authority = Keypair::new();
+    let program_id = Pubkey::new_unique();
+    let token_context = setup(Keypair::new(), false, &program_id, &authority.pubkey())
+        .await
+        .token_context
+        .take()
+        .unwrap();
+    let token = &token_context.token;
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &token_context.alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+
+    let amount = U256::from(100u64);
+    token
+        .mint_to(
+            &alice_account,
+            &token_context.mint_authority.pubkey(),
+            amount,
+            &[&token_context.mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let burn_amount = U256::from(40u64);
+    token
+        .burn(
+            &alice_account,
+            &token_context.alice.pubkey(),
+            burn_amount,
+            &[&token_context.alice],
+        )
+        .await
+        .unwrap();
+
+    let account = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(account.base.amount, amount - burn_amount);
+    let mint = token.get_mint_info().await.unwrap();
+    assert_eq!(mint.base.supply, amount - burn_amount);
+    let extension = mint.get_extension::<SupplyHook>().unwrap();
+    assert!(!bool::from(extension.executing));
+
+    // fail, the hook can tell it wasn't invoked by the token program
+    let err = token
+        .process_ixs(
+            &[interface::execute(
+                &program_id,
+                token.get_address(),
+                token.get_address(),
+                &alice_account,
+                &token_context.mint_authority.pubkey(),
+                amount,
+            )],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+        )))
+    );
+}
+
+#[tokio::test]
+async fn fail_supply_hook_program() {
+    let authority = Keypair::new();
+    let program_id = Pubkey::new_unique();
+    let token_context = setup(Keypair::new(), true, &program_id, &authority.pubkey())
+        .await
+        .token_context
+        .take()
+        .unwrap();
+    let token = &token_context.token;
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &token_context.alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+
+    let err = token
+        .mint_to(
+            &alice_account,
+            &token_context.mint_authority.pubkey(),
+            U256::from(100u64),
+            &[&token_context.mint_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+        )))
+    );
+
+    // unset the hook to get some tokens in, then restore it for the burn
+    token
+        .update_supply_hook_program_id(&authority.pubkey(), None, &[&authority])
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account,
+            &token_context.mint_authority.pubkey(),
+            U256::from(100u64),
+            &[&token_context.mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .update_supply_hook_program_id(&authority.pubkey(), Some(program_id), &[&authority])
+        .await
+        .unwrap();
+
+    let err = token
+        .burn(
+            &alice_account,
+            &token_context.alice.pubkey(),
+            U256::from(10u64),
+            &[&token_context.alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+        )))
+    );
+}
+
+#[tokio::test]
+async fn update_supply_hook_program_id() {
+    let authority = Keypair::new();
+    let program_id = Pubkey::new_unique();
+    let token = setup(Keypair::new(), false, &program_id, &authority.pubkey())
+        .await
+        .token_context
+        .take()
+        .unwrap()
+        .token;
+    let new_program_id = Pubkey::new_unique();
+
+    // fail, wrong signature
+    let wrong = Keypair::new();
+    let err = token
+        .update_supply_hook_program_id(&wrong.pubkey(), Some(new_program_id), &[&wrong])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // success
+    token
+        .update_supply_hook_program_id(&authority.pubkey(), Some(new_program_id), &[&authority])
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<SupplyHook>().unwrap();
+    assert_eq!(
+        extension.program_id,
+        Some(new_program_id).try_into().unwrap(),
+    );
+
+    // hand the hook over to a new authority
+    let new_authority = Keypair::new();
+    token
+        .set_authority(
+            token.get_address(),
+            &authority.pubkey(),
+            Some(&new_authority.pubkey()),
+            AuthorityType::SupplyHookProgramId,
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<SupplyHook>().unwrap();
+    assert_eq!(
+        extension.authority,
+        Some(new_authority.pubkey()).try_into().unwrap(),
+    );
+}
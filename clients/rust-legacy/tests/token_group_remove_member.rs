@@ -0,0 +1,412 @@
+mod program_test;
+use {
+    program_test::TestContext,
+    solana_program_test::{processor, tokio, ProgramTest},
+    solana_sdk::{
+        instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{token_group::TokenGroupMemberCounter, BaseStateWithExtensions, ExtensionType},
+        processor::Processor,
+        state::Mint,
+    },
+    spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
+    spl_token_group_interface::{
+        error::TokenGroupError,
+        state::{TokenGroup, TokenGroupMember},
+    },
+    std::sync::Arc,
+};
+
+fn setup_program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program(
+        "spl_token_2022",
+        spl_token_2022::id(),
+        processor!(Processor::process),
+    );
+    program_test
+}
+
+type SetupConfig = (Keypair, Keypair); // Mint, Authority
+
+async fn setup(group: SetupConfig, members: Vec<SetupConfig>) -> (TestContext, Vec<TestContext>) {
+    let program_test = setup_program_test();
+
+    let context = program_test.start_with_context().await;
+    let context = Arc::new(tokio::sync::Mutex::new(context));
+    let mut group_context = TestContext {
+        context: context.clone(),
+        token_context: None,
+    };
+
+    let (group_mint, group_authority) = group;
+    let group_address = Some(group_mint.pubkey());
+    group_context
+        .init_token_with_mint_keypair_and_freeze_authority(
+            group_mint,
+            vec![ExtensionInitializationParams::GroupPointer {
+                authority: Some(group_authority.pubkey()),
+                group_address,
+            }],
+            None,
+        )
+        .await
+        .unwrap();
+
+    let payer_pubkey = context.lock().await.payer.pubkey();
+    let group_token_context = group_context.token_context.as_ref().unwrap();
+    group_token_context
+        .token
+        .token_group_initialize_with_rent_transfer(
+            &payer_pubkey,
+            &group_token_context.mint_authority.pubkey(),
+            &group_authority.pubkey(),
+            2,
+            &[&group_token_context.mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let mut member_contexts = vec![];
+    for member in members.into_iter() {
+        let (member_mint, member_authority) = member;
+        let member_address = Some(member_mint.pubkey());
+        let mut member_context = TestContext {
+            context: context.clone(),
+            token_context: None,
+        };
+        member_context
+            .init_token_with_mint_keypair_and_freeze_authority(
+                member_mint,
+                vec![ExtensionInitializationParams::GroupMemberPointer {
+                    authority: Some(member_authority.pubkey()),
+                    member_address,
+                }],
+                None,
+            )
+            .await
+            .unwrap();
+
+        let member_token_context = member_context.token_context.as_ref().unwrap();
+        member_token_context
+            .token
+            .token_group_initialize_member_with_rent_transfer(
+                &payer_pubkey,
+                &member_token_context.mint_authority.pubkey(),
+                group_token_context.token.get_address(),
+                &group_authority.pubkey(),
+                &[&member_token_context.mint_authority, &group_authority],
+            )
+            .await
+            .unwrap();
+        member_contexts.push(member_context);
+    }
+
+    (group_context, member_contexts)
+}
+
+#[tokio::test]
+async fn success_remove_member() {
+    let group_authority = Keypair::new();
+    let group_mint_keypair = Keypair::new();
+
+    let (group_context, mut member_contexts) = setup(
+        (
+            group_mint_keypair.insecure_clone(),
+            group_authority.insecure_clone(),
+        ),
+        vec![
+            (Keypair::new(), Keypair::new()),
+            (Keypair::new(), Keypair::new()),
+        ],
+    )
+    .await;
+    let payer_pubkey = group_context.context.lock().await.payer.pubkey();
+    let group_token_context = group_context.token_context.unwrap();
+    let member1_token_context = member_contexts[0].token_context.take().unwrap();
+    let member2_token_context = member_contexts[1].token_context.take().unwrap();
+
+    let mint_info = member1_token_context.token.get_mint_info().await.unwrap();
+    let member = mint_info.get_extension::<TokenGroupMember>().unwrap();
+    assert_eq!(u64::from(member.member_number), 1);
+    let mint_info = member2_token_context.token.get_mint_info().await.unwrap();
+    let member = mint_info.get_extension::<TokenGroupMember>().unwrap();
+    assert_eq!(u64::from(member.member_number), 2);
+
+    // removed by the group update authority
+    member1_token_context
+        .token
+        .token_group_remove_member_with_rent_transfer(
+            &payer_pubkey,
+            &group_mint_keypair.pubkey(),
+            &group_authority.pubkey(),
+            None,
+            &[&group_authority],
+        )
+        .await
+        .unwrap();
+
+    let mint_info = member1_token_context.token.get_mint_info().await.unwrap();
+    assert_eq!(
+        mint_info.get_extension::<TokenGroupMember>().unwrap_err(),
+        TokenError::ExtensionNotFound.into()
+    );
+    assert_eq!(
+        mint_info.get_extension_types().unwrap(),
+        vec![ExtensionType::GroupMemberPointer]
+    );
+    let account = member1_token_context
+        .token
+        .get_account(*member1_token_context.token.get_address())
+        .await
+        .unwrap();
+    assert_eq!(
+        account.data.len(),
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::GroupMemberPointer])
+            .unwrap()
+    );
+
+    let mint_info = group_token_context.token.get_mint_info().await.unwrap();
+    let group = mint_info.get_extension::<TokenGroup>().unwrap();
+    assert_eq!(u64::from(group.size), 1);
+    assert_eq!(u64::from(group.max_size), 2);
+    let counter = mint_info
+        .get_extension::<TokenGroupMemberCounter>()
+        .unwrap();
+    assert_eq!(u64::from(counter.next_member_number), 3);
+
+    // removed by the member mint authority, the group mint already has the
+    // counter
+    member2_token_context
+        .token
+        .token_group_remove_member_with_rent_transfer(
+            &payer_pubkey,
+            &group_mint_keypair.pubkey(),
+            &member2_token_context.mint_authority.pubkey(),
+            None,
+            &[&member2_token_context.mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let mint_info = group_token_context.token.get_mint_info().await.unwrap();
+    let group = mint_info.get_extension::<TokenGroup>().unwrap();
+    assert_eq!(u64::from(group.size), 0);
+
+    // the freed space in the group can be used by a new member
+    member1_token_context
+        .token
+        .token_group_initialize_member_with_rent_transfer(
+            &payer_pubkey,
+            &member1_token_context.mint_authority.pubkey(),
+            &group_mint_keypair.pubkey(),
+            &group_authority.pubkey(),
+            &[&member1_token_context.mint_authority, &group_authority],
+        )
+        .await
+        .unwrap();
+
+    let mint_info = group_token_context.token.get_mint_info().await.unwrap();
+    let group = mint_info.get_extension::<TokenGroup>().unwrap();
+    assert_eq!(u64::from(group.size), 1);
+
+    // re-added members never reuse a number, even if the size matches it
+    let mint_info = member1_token_context.token.get_mint_info().await.unwrap();
+    let member = mint_info.get_extension::<TokenGroupMember>().unwrap();
+    assert_eq!(u64::from(member.member_number), 3);
+
+    member2_token_context
+        .token
+        .token_group_initialize_member_with_rent_transfer(
+            &payer_pubkey,
+            &member2_token_context.mint_authority.pubkey(),
+            &group_mint_keypair.pubkey(),
+            &group_authority.pubkey(),
+            &[&member2_token_context.mint_authority, &group_authority],
+        )
+        .await
+        .unwrap();
+
+    let mint_info = member2_token_context.token.get_mint_info().await.unwrap();
+    let member = mint_info.get_extension::<TokenGroupMember>().unwrap();
+    assert_eq!(u64::from(member.member_number), 4);
+
+    let mint_info = group_token_context.token.get_mint_info().await.unwrap();
+    let group = mint_info.get_extension::<TokenGroup>().unwrap();
+    assert_eq!(u64::from(group.size), 2);
+    let counter = mint_info
+        .get_extension::<TokenGroupMemberCounter>()
+        .unwrap();
+    assert_eq!(u64::from(counter.next_member_number), 5);
+}
+
+#[tokio::test]
+async fn fail_remove_member() {
+    let group_authority = Keypair::new();
+    let group_mint_keypair = Keypair::new();
+    let other_group_authority = Keypair::new();
+    let other_group_mint_keypair = Keypair::new();
+
+    let (_, mut member_contexts) = setup(
+        (
+            group_mint_keypair.insecure_clone(),
+            group_authority.insecure_clone(),
+        ),
+        vec![(Keypair::new(), Keypair::new())],
+    )
+    .await;
+    let member_token_context = member_contexts[0].token_context.take().unwrap();
+
+    // fail wrong authority
+    let not_authority = Keypair::new();
+    let error = member_token_context
+        .token
+        .token_group_remove_member(
+            &group_mint_keypair.pubkey(),
+            &not_authority.pubkey(),
+            None,
+            &[&not_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenGroupError::IncorrectUpdateAuthority as u32)
+            )
+        )))
+    );
+
+    // fail group and member same mint
+    let error = member_token_context
+        .token
+        .token_group_remove_member(
+            member_token_context.token.get_address(),
+            &member_token_context.mint_authority.pubkey(),
+            None,
+            &[&member_token_context.mint_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenGroupError::MemberAccountIsGroupAccount as u32)
+            )
+        )))
+    );
+
+    // fail member of another group
+    let mut other_group_context = TestContext {
+        context: member_contexts[0].context.clone(),
+        token_context: None,
+    };
+    other_group_context
+        .init_token_with_mint_keypair_and_freeze_authority(
+            other_group_mint_keypair.insecure_clone(),
+            vec![ExtensionInitializationParams::GroupPointer {
+                authority: Some(other_group_authority.pubkey()),
+                group_address: Some(other_group_mint_keypair.pubkey()),
+            }],
+            None,
+        )
+        .await
+        .unwrap();
+    let payer_pubkey = other_group_context.context.lock().await.payer.pubkey();
+    let other_group_token_context = other_group_context.token_context.unwrap();
+    other_group_token_context
+        .token
+        .token_group_initialize_with_rent_transfer(
+            &payer_pubkey,
+            &other_group_token_context.mint_authority.pubkey(),
+            &other_group_authority.pubkey(),
+            2,
+            &[&other_group_token_context.mint_authority],
+        )
+        .await
+        .unwrap();
+    let error = member_token_context
+        .token
+        .token_group_remove_member(
+            &other_group_mint_keypair.pubkey(),
+            &other_group_authority.pubkey(),
+            None,
+            &[&other_group_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::MintMismatch as u32)
+            )
+        )))
+    );
+
+    // fail group authority taking the rent of the member mint
+    let error = member_token_context
+        .token
+        .token_group_remove_member(
+            &group_mint_keypair.pubkey(),
+            &group_authority.pubkey(),
+            Some((&group_authority.pubkey(), &group_authority.pubkey())),
+            &[&group_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // fail double-remove
+    member_token_context
+        .token
+        .token_group_remove_member_with_rent_transfer(
+            &payer_pubkey,
+            &group_mint_keypair.pubkey(),
+            &group_authority.pubkey(),
+            None,
+            &[&group_authority],
+        )
+        .await
+        .unwrap();
+    {
+        let mut context = member_contexts[0].context.lock().await;
+        context.get_new_latest_blockhash().await.unwrap();
+        context.get_new_latest_blockhash().await.unwrap();
+    }
+    let error = member_token_context
+        .token
+        .token_group_remove_member(
+            &group_mint_keypair.pubkey(),
+            &group_authority.pubkey(),
+            None,
+            &[&group_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::ExtensionNotFound as u32)
+            )
+        )))
+    );
+}
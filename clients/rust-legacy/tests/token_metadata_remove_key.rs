@@ -7,10 +7,11 @@ use {
         pubkey::Pubkey,
         signature::Signer,
         signer::keypair::Keypair,
+        system_instruction,
         transaction::{Transaction, TransactionError},
         transport::TransportError,
     },
-    spl_token_2022::{extension::BaseStateWithExtensions, processor::Processor},
+    spl_token_2022::{error::TokenError, extension::BaseStateWithExtensions, processor::Processor},
     spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
     spl_token_metadata_interface::{
         error::TokenMetadataError,
@@ -103,6 +104,7 @@ async fn success_remove() {
             field,
             value,
             None,
+            None,
             &[&update_authority],
         )
         .await
@@ -115,6 +117,7 @@ async fn success_remove() {
             &update_authority.pubkey(),
             key.clone(),
             false, // idempotent
+            None,
             &[&update_authority],
         )
         .await
@@ -133,6 +136,7 @@ async fn success_remove() {
             &update_authority.pubkey(),
             key.clone(),
             true, // idempotent
+            None,
             &[&update_authority],
         )
         .await
@@ -151,6 +155,7 @@ async fn success_remove() {
             &update_authority.pubkey(),
             key.clone(),
             false, // idempotent
+            None,
             &[&update_authority],
         )
         .await
@@ -213,6 +218,7 @@ async fn fail_authority_checks() {
             &payer_pubkey,
             key,
             true, // idempotent
+            None,
             &[] as &[&dyn Signer; 0],
         )
         .await
@@ -254,3 +260,135 @@ async fn fail_authority_checks() {
         TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature,)
     );
 }
+
+#[tokio::test]
+async fn success_remove_with_refund() {
+    let authority = Keypair::new();
+    let mint_keypair = Keypair::new();
+    let mut test_context = setup(mint_keypair, &authority.pubkey()).await;
+    let payer_pubkey = test_context.context.lock().await.payer.pubkey();
+    let token_context = test_context.token_context.take().unwrap();
+
+    let update_authority = Keypair::new();
+    token_context
+        .token
+        .token_metadata_initialize_with_rent_transfer(
+            &payer_pubkey,
+            &update_authority.pubkey(),
+            &token_context.mint_authority.pubkey(),
+            "MySuperCoolToken".to_string(),
+            "MINE".to_string(),
+            "my.super.cool.token".to_string(),
+            &[&token_context.mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let key = "new_field, wow!".to_string();
+    let value = "so impressed with the new field, don't know what to put here".to_string();
+    token_context
+        .token
+        .token_metadata_update_field_with_rent_transfer(
+            &payer_pubkey,
+            &update_authority.pubkey(),
+            Field::Key(key.clone()),
+            value,
+            None,
+            None,
+            &[&update_authority],
+        )
+        .await
+        .unwrap();
+
+    let mint_address = *token_context.token.get_address();
+    let previous_account = token_context.token.get_account(mint_address).await.unwrap();
+
+    // fund the destination so that it stays rent-exempt
+    let destination = Pubkey::new_unique();
+    let rent = {
+        let mut context = test_context.context.lock().await;
+        let rent = context.banks_client.get_rent().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &context.payer.pubkey(),
+                &destination,
+                rent.minimum_balance(0),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+        rent
+    };
+
+    let mint_authority = token_context.mint_authority.pubkey();
+
+    // the update authority cannot approve the refund on its own
+    let error = token_context
+        .token
+        .token_metadata_remove_key(
+            &update_authority.pubkey(),
+            key.clone(),
+            false, // idempotent
+            Some((&destination, &update_authority.pubkey())),
+            &[&update_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // remove the field, sending the freed rent to the destination
+    token_context
+        .token
+        .token_metadata_remove_key(
+            &update_authority.pubkey(),
+            key,
+            false, // idempotent
+            Some((&destination, &mint_authority)),
+            &[&update_authority, &token_context.mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let account = token_context.token.get_account(mint_address).await.unwrap();
+    assert!(account.data.len() < previous_account.data.len());
+    let refund = rent.minimum_balance(previous_account.data.len())
+        - rent.minimum_balance(account.data.len());
+    assert_eq!(account.lamports, rent.minimum_balance(account.data.len()));
+    let destination_account = token_context.token.get_account(destination).await.unwrap();
+    assert_eq!(
+        destination_account.lamports,
+        rent.minimum_balance(0) + refund
+    );
+
+    // nothing is refunded when the account does not shrink
+    token_context
+        .token
+        .token_metadata_remove_key(
+            &update_authority.pubkey(),
+            "missing".to_string(),
+            true, // idempotent
+            Some((&destination, &mint_authority)),
+            &[&update_authority, &token_context.mint_authority],
+        )
+        .await
+        .unwrap();
+    let destination_account = token_context.token.get_account(destination).await.unwrap();
+    assert_eq!(
+        destination_account.lamports,
+        rent.minimum_balance(0) + refund
+    );
+}
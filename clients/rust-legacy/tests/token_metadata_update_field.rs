@@ -101,6 +101,7 @@ async fn success_update(field: Field, value: String) {
                 &update_authority.pubkey(),
                 field.clone(),
                 value.clone(),
+                None,
                 &[&update_authority],
             )
             .await
@@ -122,6 +123,7 @@ async fn success_update(field: Field, value: String) {
             field,
             value,
             None,
+            None,
             &[&update_authority],
         )
         .await
@@ -188,6 +190,7 @@ async fn fail_authority_checks() {
             &wrong_authority.pubkey(),
             Field::Name,
             "new_name".to_string(),
+            None,
             &[&wrong_authority],
         )
         .await
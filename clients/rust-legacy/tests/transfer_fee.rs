@@ -11,9 +11,10 @@ use {
         error::TokenError,
         extension::{
             transfer_fee::{
-                TransferFee, TransferFeeAmount, TransferFeeConfig, MAX_FEE_BASIS_POINTS,
+                TransferFee, TransferFeeAmount, TransferFeeConfig, TransferFeeExemption,
+                TransferFeeRecipient, TransferFeeTier, TransferFeeTiers, MAX_FEE_BASIS_POINTS,
             },
-            BaseStateWithExtensions,
+            BaseStateWithExtensions, ExtensionType,
         },
         instruction,
     },
@@ -79,10 +80,13 @@ struct TokenWithAccounts {
     token: Token<ProgramBanksClientProcessTransaction>,
     token_unchecked: Token<ProgramBanksClientProcessTransaction>,
     transfer_fee_config: TransferFeeConfig,
+    transfer_fee_config_authority: Keypair,
     withdraw_withheld_authority: Keypair,
+    mint_authority: Keypair,
     freeze_authority: Keypair,
     alice: Keypair,
     alice_account: Pubkey,
+    bob: Keypair,
     bob_account: Pubkey,
 }
 
@@ -147,10 +151,13 @@ async fn create_mint_with_accounts(alice_amount: U256) -> TokenWithAccounts {
         token,
         token_unchecked,
         transfer_fee_config,
+        transfer_fee_config_authority,
         withdraw_withheld_authority,
+        mint_authority,
         freeze_authority: freeze_authority.unwrap(),
         alice,
         alice_account,
+        bob,
         bob_account,
     }
 }
@@ -447,6 +454,203 @@ async fn set_fee() {
     );
 }
 
+#[tokio::test]
+async fn set_tiered_fee() {
+    let TransferFeeConfigWithKeypairs {
+        transfer_fee_config_authority,
+        withdraw_withheld_authority,
+        transfer_fee_config: TransferFeeConfig {
+            newer_transfer_fee, ..
+        },
+        ..
+    } = test_transfer_fee_config_with_keypairs();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![
+            ExtensionInitializationParams::TransferFeeConfig {
+                transfer_fee_config_authority: transfer_fee_config_authority.pubkey().into(),
+                withdraw_withheld_authority: withdraw_withheld_authority.pubkey().into(),
+                transfer_fee_basis_points: newer_transfer_fee.transfer_fee_basis_points.into(),
+                maximum_fee: newer_transfer_fee.maximum_fee.into(),
+            },
+            ExtensionInitializationParams::TransferFeeTiers,
+        ])
+        .await
+        .unwrap();
+    let token = context.token_context.unwrap().token;
+
+    let tiers = [
+        TransferFeeTier {
+            minimum_amount: U256::new(1_000).into(),
+            maximum_fee: U256::new(50).into(),
+            transfer_fee_basis_points: 100.into(),
+        },
+        TransferFeeTier {
+            minimum_amount: U256::new(1_000_000).into(),
+            maximum_fee: U256::new(5_000).into(),
+            transfer_fee_basis_points: 10.into(),
+        },
+    ];
+    token
+        .set_tiered_transfer_fee(
+            &transfer_fee_config_authority.pubkey(),
+            TEST_FEE_BASIS_POINTS,
+            TEST_MAXIMUM_FEE,
+            &tiers,
+            &[&transfer_fee_config_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<TransferFeeConfig>().unwrap();
+    let transfer_fee_tiers = state.get_extension::<TransferFeeTiers>().unwrap();
+    let newer_epoch = u64::from(extension.newer_transfer_fee.epoch);
+    let transfer_fee = extension.get_tiered_epoch_fee(Some(transfer_fee_tiers), newer_epoch);
+    assert!(transfer_fee.is_tiered());
+    assert_eq!(transfer_fee.active_tiers().count(), tiers.len());
+    assert_eq!(transfer_fee.tiers[..tiers.len()], tiers);
+    assert_eq!(
+        transfer_fee.calculate_fee(U256::new(999)),
+        Some(U256::new(25))
+    );
+    // each tier only charges the part of the amount above its minimum
+    assert_eq!(
+        transfer_fee.calculate_fee(U256::new(4_000)),
+        Some(U256::new(55))
+    );
+    assert_eq!(
+        transfer_fee.calculate_fee(U256::new(10_000_000)),
+        Some(U256::new(5_075))
+    );
+    assert_eq!(extension.older_transfer_fee, newer_transfer_fee);
+    assert!(!extension
+        .get_tiered_epoch_fee(Some(transfer_fee_tiers), newer_epoch - 1)
+        .is_tiered());
+
+    // setting a flat fee clears the tiers
+    token
+        .set_transfer_fee(
+            &transfer_fee_config_authority.pubkey(),
+            TEST_FEE_BASIS_POINTS,
+            TEST_MAXIMUM_FEE,
+            &[&transfer_fee_config_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<TransferFeeConfig>().unwrap();
+    let transfer_fee_tiers = state.get_extension::<TransferFeeTiers>().unwrap();
+    assert!(!extension
+        .get_tiered_epoch_fee(Some(transfer_fee_tiers), newer_epoch)
+        .is_tiered());
+
+    // fail, tiers out of order
+    let error = token
+        .set_tiered_transfer_fee(
+            &transfer_fee_config_authority.pubkey(),
+            TEST_FEE_BASIS_POINTS,
+            TEST_MAXIMUM_FEE,
+            &[tiers[1], tiers[0]],
+            &[&transfer_fee_config_authority],
+        )
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::InvalidTransferFeeTiers as u32)
+            )
+        )))
+    );
+
+    // fail, tier fee too high
+    let mut tier = tiers[0];
+    tier.transfer_fee_basis_points = (MAX_FEE_BASIS_POINTS + 1).into();
+    let error = token
+        .set_tiered_transfer_fee(
+            &transfer_fee_config_authority.pubkey(),
+            TEST_FEE_BASIS_POINTS,
+            TEST_MAXIMUM_FEE,
+            &[tier],
+            &[&transfer_fee_config_authority],
+        )
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::TransferFeeExceedsMaximum as u32)
+            )
+        )))
+    );
+}
+
+#[tokio::test]
+async fn set_tiered_fee_without_tiers_extension() {
+    let TransferFeeConfigWithKeypairs {
+        transfer_fee_config_authority,
+        withdraw_withheld_authority,
+        transfer_fee_config: TransferFeeConfig {
+            newer_transfer_fee, ..
+        },
+        ..
+    } = test_transfer_fee_config_with_keypairs();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::TransferFeeConfig {
+            transfer_fee_config_authority: transfer_fee_config_authority.pubkey().into(),
+            withdraw_withheld_authority: withdraw_withheld_authority.pubkey().into(),
+            transfer_fee_basis_points: newer_transfer_fee.transfer_fee_basis_points.into(),
+            maximum_fee: newer_transfer_fee.maximum_fee.into(),
+        }])
+        .await
+        .unwrap();
+    let token = context.token_context.unwrap().token;
+
+    let tier = TransferFeeTier {
+        minimum_amount: U256::new(1_000).into(),
+        maximum_fee: U256::new(50).into(),
+        transfer_fee_basis_points: 100.into(),
+    };
+    let error = token
+        .set_tiered_transfer_fee(
+            &transfer_fee_config_authority.pubkey(),
+            TEST_FEE_BASIS_POINTS,
+            TEST_MAXIMUM_FEE,
+            &[tier],
+            &[&transfer_fee_config_authority],
+        )
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::ExtensionNotFound as u32)
+            )
+        )))
+    );
+
+    // a flat fee can still be set
+    token
+        .set_transfer_fee(
+            &transfer_fee_config_authority.pubkey(),
+            TEST_FEE_BASIS_POINTS,
+            TEST_MAXIMUM_FEE,
+            &[&transfer_fee_config_authority],
+        )
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn fail_unsupported_mint() {
     let mut context = TestContext::new().await;
@@ -1150,6 +1354,336 @@ async fn transfer_checked_with_fee() {
     assert_eq!(extension.withheld_amount, fee.into());
 }
 
+#[tokio::test]
+async fn transfer_checked_with_tiered_fee() {
+    let TransferFeeConfigWithKeypairs {
+        transfer_fee_config_authority,
+        withdraw_withheld_authority,
+        ..
+    } = test_transfer_fee_config_with_keypairs();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![
+            ExtensionInitializationParams::TransferFeeConfig {
+                transfer_fee_config_authority: transfer_fee_config_authority.pubkey().into(),
+                withdraw_withheld_authority: withdraw_withheld_authority.pubkey().into(),
+                transfer_fee_basis_points: TEST_FEE_BASIS_POINTS,
+                maximum_fee: TEST_MAXIMUM_FEE,
+            },
+            ExtensionInitializationParams::TransferFeeTiers,
+        ])
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+    let alice_amount = U256::new(100_000);
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            alice_amount,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let tier_minimum_amount = U256::new(1_000);
+    token
+        .set_tiered_transfer_fee(
+            &transfer_fee_config_authority.pubkey(),
+            TEST_FEE_BASIS_POINTS,
+            TEST_MAXIMUM_FEE,
+            &[TransferFeeTier {
+                minimum_amount: tier_minimum_amount.into(),
+                maximum_fee: U256::new(50).into(),
+                transfer_fee_basis_points: 100.into(),
+            }],
+            &[&transfer_fee_config_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<TransferFeeConfig>().unwrap();
+    let newer_epoch = u64::from(extension.newer_transfer_fee.epoch);
+    context
+        .context
+        .lock()
+        .await
+        .warp_to_epoch(newer_epoch)
+        .unwrap();
+
+    // the flat fee of the epoch is refused
+    let transfer_amount = U256::new(4_000);
+    let flat_fee = extension
+        .calculate_epoch_fee(newer_epoch, transfer_amount)
+        .unwrap();
+    let error = token
+        .transfer_with_fee(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            transfer_amount,
+            flat_fee,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::FeeMismatch as u32)
+            )
+        )))
+    );
+
+    // the net amount keeps growing across the tier boundary
+    let mut bob_amount = U256::ZERO;
+    let mut withheld_amount = U256::ZERO;
+    for (transfer_amount, expected_fee) in [
+        (tier_minimum_amount - 1, U256::new(25)),
+        (tier_minimum_amount, U256::new(25)),
+        (tier_minimum_amount + 1, U256::new(26)),
+        (U256::new(4_000), U256::new(55)),
+    ] {
+        let fee = token.calculate_transfer_fee(transfer_amount).await.unwrap();
+        assert_eq!(fee, expected_fee);
+        token
+            .transfer_with_fee(
+                &alice_account,
+                &bob_account,
+                &alice.pubkey(),
+                transfer_amount,
+                fee,
+                &[&alice],
+            )
+            .await
+            .unwrap();
+        bob_amount += transfer_amount - fee;
+        withheld_amount += fee;
+    }
+    let bob_state = token.get_account_info(&bob_account).await.unwrap();
+    assert_eq!(bob_state.base.amount, bob_amount);
+    let extension = bob_state.get_extension::<TransferFeeAmount>().unwrap();
+    assert_eq!(
+        U256::from(u64::from(extension.withheld_amount)),
+        withheld_amount
+    );
+
+    // the smallest transfer reaching a net amount at the start of the tier
+    let post_fee_amount = tier_minimum_amount - 25;
+    assert_eq!(
+        token
+            .calculate_inverse_transfer_fee(post_fee_amount)
+            .await
+            .unwrap(),
+        U256::new(25)
+    );
+}
+
+#[tokio::test]
+async fn fee_exempt_transfer() {
+    let maximum_fee = TEST_MAXIMUM_FEE;
+    let alice_amount = maximum_fee * 100;
+    let TokenWithAccounts {
+        token,
+        transfer_fee_config,
+        transfer_fee_config_authority,
+        withdraw_withheld_authority,
+        alice,
+        alice_account,
+        bob,
+        bob_account,
+        ..
+    } = create_mint_with_accounts(alice_amount).await;
+
+    // fail, no room for the exemption
+    let error = token
+        .set_transfer_fee_exemption(
+            &bob_account,
+            &transfer_fee_config_authority.pubkey(),
+            true,
+            &[&transfer_fee_config_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+        )))
+    );
+
+    // owners make room for the exemption
+    token
+        .reallocate(
+            &bob_account,
+            &bob.pubkey(),
+            &[ExtensionType::TransferFeeExemption],
+            &[&bob],
+        )
+        .await
+        .unwrap();
+    token
+        .reallocate(
+            &alice_account,
+            &alice.pubkey(),
+            &[ExtensionType::TransferFeeExemption],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+
+    // fail, wrong signer
+    let error = token
+        .set_transfer_fee_exemption(
+            &bob_account,
+            &withdraw_withheld_authority.pubkey(),
+            true,
+            &[&withdraw_withheld_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    token
+        .set_transfer_fee_exemption(
+            &bob_account,
+            &transfer_fee_config_authority.pubkey(),
+            true,
+            &[&transfer_fee_config_authority],
+        )
+        .await
+        .unwrap();
+    let bob_state = token.get_account_info(&bob_account).await.unwrap();
+    let extension = bob_state.get_extension::<TransferFeeExemption>().unwrap();
+    assert!(bool::from(extension.fee_exempt));
+
+    // fail, fee provided for an exempt destination
+    let transfer_amount = maximum_fee;
+    let fee = transfer_fee_config
+        .calculate_epoch_fee(0, transfer_amount)
+        .unwrap();
+    let error = token
+        .transfer_with_fee(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            transfer_amount,
+            fee,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::FeeMismatch as u32)
+            )
+        )))
+    );
+
+    // no fee to an exempt destination
+    token
+        .transfer_with_fee(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            transfer_amount,
+            U256::ZERO,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let bob_state = token.get_account_info(&bob_account).await.unwrap();
+    assert_eq!(bob_state.base.amount, transfer_amount);
+    let extension = bob_state.get_extension::<TransferFeeAmount>().unwrap();
+    assert_eq!(extension.withheld_amount, 0.into());
+
+    // exemption is revoked, fee is withheld again
+    token
+        .set_transfer_fee_exemption(
+            &bob_account,
+            &transfer_fee_config_authority.pubkey(),
+            false,
+            &[&transfer_fee_config_authority],
+        )
+        .await
+        .unwrap();
+    let bob_state = token.get_account_info(&bob_account).await.unwrap();
+    let extension = bob_state.get_extension::<TransferFeeExemption>().unwrap();
+    assert!(!bool::from(extension.fee_exempt));
+    token
+        .transfer_with_fee(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            transfer_amount,
+            fee,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let bob_state = token.get_account_info(&bob_account).await.unwrap();
+    assert_eq!(bob_state.base.amount, transfer_amount * 2 - fee);
+    let extension = bob_state.get_extension::<TransferFeeAmount>().unwrap();
+    assert_eq!(extension.withheld_amount, fee.into());
+
+    // no fee from an exempt source
+    token
+        .set_transfer_fee_exemption(
+            &alice_account,
+            &transfer_fee_config_authority.pubkey(),
+            true,
+            &[&transfer_fee_config_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            transfer_amount,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let bob_state = token.get_account_info(&bob_account).await.unwrap();
+    assert_eq!(bob_state.base.amount, transfer_amount * 3 - fee);
+    let extension = bob_state.get_extension::<TransferFeeAmount>().unwrap();
+    assert_eq!(extension.withheld_amount, fee.into());
+}
+
 #[tokio::test]
 async fn no_fees_from_self_transfer() {
     let amount = TEST_MAXIMUM_FEE;
@@ -1523,6 +2057,154 @@ async fn withdraw_withheld_tokens_from_mint() {
     );
 }
 
+#[tokio::test]
+async fn sweep_withheld_tokens_to_fee_recipient() {
+    let amount = TEST_MAXIMUM_FEE;
+    let alice_amount = amount * 100;
+    let TokenWithAccounts {
+        token,
+        transfer_fee_config,
+        withdraw_withheld_authority,
+        mint_authority,
+        alice,
+        alice_account,
+        bob_account,
+        ..
+    } = create_mint_with_accounts(alice_amount).await;
+
+    // fail, no fee recipient extension
+    let error = token
+        .set_fee_recipient(
+            &withdraw_withheld_authority.pubkey(),
+            Some(&bob_account),
+            &[&withdraw_withheld_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::ExtensionNotFound as u32)
+            )
+        )))
+    );
+
+    token
+        .reallocate_mint(
+            &mint_authority.pubkey(),
+            &[ExtensionType::TransferFeeRecipient],
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // fail, no fee recipient configured
+    let error = token
+        .sweep_withheld_tokens_to_fee_recipient(&bob_account)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::InvalidFeeRecipient as u32)
+            )
+        )))
+    );
+
+    // fail, wrong signer
+    let error = token
+        .set_fee_recipient(&alice.pubkey(), Some(&bob_account), &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    token
+        .set_fee_recipient(
+            &withdraw_withheld_authority.pubkey(),
+            Some(&bob_account),
+            &[&withdraw_withheld_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<TransferFeeRecipient>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.fee_recipient),
+        Some(bob_account)
+    );
+
+    // transfer + harvest to mint
+    let fee = transfer_fee_config.calculate_epoch_fee(0, amount).unwrap();
+    let account =
+        create_and_transfer_to_account(&token, &alice_account, &alice, &alice.pubkey(), amount)
+            .await;
+    token
+        .harvest_withheld_tokens_to_mint(&[&account])
+        .await
+        .unwrap();
+
+    // fail, not the fee recipient
+    let error = token
+        .sweep_withheld_tokens_to_fee_recipient(&alice_account)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::InvalidFeeRecipient as u32)
+            )
+        )))
+    );
+
+    // success, no authority needed
+    token
+        .sweep_withheld_tokens_to_fee_recipient(&bob_account)
+        .await
+        .unwrap();
+    let state = token.get_account_info(&bob_account).await.unwrap();
+    assert_eq!(state.base.amount, fee);
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<TransferFeeConfig>().unwrap();
+    assert_eq!(extension.withheld_amount, 0.into());
+
+    // clearing the fee recipient disables sweeping
+    token
+        .set_fee_recipient(
+            &withdraw_withheld_authority.pubkey(),
+            None,
+            &[&withdraw_withheld_authority],
+        )
+        .await
+        .unwrap();
+    let error = token
+        .sweep_withheld_tokens_to_fee_recipient(&bob_account)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::InvalidFeeRecipient as u32)
+            )
+        )))
+    );
+}
+
 #[tokio::test]
 async fn withdraw_withheld_tokens_from_accounts() {
     let amount = TEST_MAXIMUM_FEE;
@@ -0,0 +1,320 @@
+mod program_test;
+use {
+    ethnum::U256,
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        clock::Clock, instruction::InstructionError, pubkey::Pubkey, signature::Signer,
+        signer::keypair::Keypair, transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            transfer_limit::{TransferLimit, TransferLimitPolicy},
+            BaseStateWithExtensions, ExtensionType,
+        },
+        instruction::AuthorityType,
+    },
+    spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
+};
+
+const TEST_LIMIT: U256 = U256::new(100);
+const TEST_WINDOW_SECONDS: i64 = 86_400;
+
+fn client_error(token_error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, InstructionError::Custom(token_error as u32)),
+    )))
+}
+
+#[tokio::test]
+async fn transfer_and_burn_limited() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext {
+        mint_authority,
+        token,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account_with_extension_space(
+            &alice_account,
+            &alice.pubkey(),
+            vec![ExtensionType::TransferLimit],
+        )
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(1_000),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // fail, wrong signer
+    let error = token
+        .configure_transfer_limit(
+            &alice_account,
+            &bob.pubkey(),
+            TEST_LIMIT,
+            TEST_WINDOW_SECONDS,
+            &[&bob],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::OwnerMismatch));
+
+    token
+        .configure_transfer_limit(
+            &alice_account,
+            &alice.pubkey(),
+            TEST_LIMIT,
+            TEST_WINDOW_SECONDS,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    let extension = state.get_extension::<TransferLimit>().unwrap();
+    assert_eq!(U256::from(extension.limit), TEST_LIMIT);
+    assert_eq!(i64::from(extension.window_seconds), TEST_WINDOW_SECONDS);
+
+    // transfers and burns share the limit
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::new(60),
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    token
+        .burn(&alice_account, &alice.pubkey(), U256::new(40), &[&alice])
+        .await
+        .unwrap();
+    let error = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::ONE,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::TransferLimitExceeded));
+    let error = token
+        .burn(&alice_account, &alice.pubkey(), U256::ONE, &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::TransferLimitExceeded));
+
+    // raising the limit is delayed by a full window
+    let mut clock = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap();
+    clock.unix_timestamp += 10;
+    context.context.lock().await.set_sysvar(&clock);
+    token
+        .configure_transfer_limit(
+            &alice_account,
+            &alice.pubkey(),
+            U256::new(500),
+            TEST_WINDOW_SECONDS,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    let extension = state.get_extension::<TransferLimit>().unwrap();
+    assert_eq!(U256::from(extension.limit), TEST_LIMIT);
+    assert_eq!(U256::from(extension.pending_limit), U256::new(500));
+
+    // the window restarts, but the old limit still applies
+    clock.unix_timestamp = i64::from(extension.window_start) + TEST_WINDOW_SECONDS;
+    context.context.lock().await.set_sysvar(&clock);
+    let error = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::new(101),
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::TransferLimitExceeded));
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            TEST_LIMIT,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+
+    // once the pending limit takes effect, more can be transferred
+    clock.unix_timestamp = i64::from(extension.pending_effective_timestamp);
+    context.context.lock().await.set_sysvar(&clock);
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::new(500),
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    let extension = state.get_extension::<TransferLimit>().unwrap();
+    assert_eq!(U256::from(extension.limit), U256::new(500));
+    assert_eq!(i64::from(extension.pending_effective_timestamp), 0);
+}
+
+#[tokio::test]
+async fn policy_authority_configures_limit() {
+    let policy_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::TransferLimitPolicy {
+            authority: Some(policy_authority.pubkey()),
+        }])
+        .await
+        .unwrap();
+    let TokenContext {
+        mint_authority,
+        token,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<TransferLimitPolicy>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.authority),
+        Some(policy_authority.pubkey())
+    );
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account_with_extension_space(
+            &alice_account,
+            &alice.pubkey(),
+            vec![ExtensionType::TransferLimit],
+        )
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(1_000),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // fail, not the policy authority
+    let error = token
+        .configure_transfer_limit_by_policy_authority(
+            &alice_account,
+            &bob.pubkey(),
+            TEST_LIMIT,
+            TEST_WINDOW_SECONDS,
+            &[&bob],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::OwnerMismatch));
+
+    token
+        .configure_transfer_limit_by_policy_authority(
+            &alice_account,
+            &policy_authority.pubkey(),
+            TEST_LIMIT,
+            TEST_WINDOW_SECONDS,
+            &[&policy_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    let extension = state.get_extension::<TransferLimit>().unwrap();
+    assert_eq!(U256::from(extension.limit), TEST_LIMIT);
+    assert_eq!(i64::from(extension.window_seconds), TEST_WINDOW_SECONDS);
+
+    let error = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            TEST_LIMIT + 1,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::TransferLimitExceeded));
+
+    // remove the policy authority, only the owner can configure the limit
+    token
+        .set_authority(
+            token.get_address(),
+            &policy_authority.pubkey(),
+            None,
+            AuthorityType::TransferLimitPolicy,
+            &[&policy_authority],
+        )
+        .await
+        .unwrap();
+    let error = token
+        .configure_transfer_limit_by_policy_authority(
+            &alice_account,
+            &policy_authority.pubkey(),
+            TEST_LIMIT / 2,
+            TEST_WINDOW_SECONDS,
+            &[&policy_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::OwnerMismatch));
+    token
+        .configure_transfer_limit(
+            &alice_account,
+            &alice.pubkey(),
+            TEST_LIMIT / 2,
+            TEST_WINDOW_SECONDS,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+}
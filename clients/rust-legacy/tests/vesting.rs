@@ -0,0 +1,331 @@
+mod program_test;
+use {
+    ethnum::U256,
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        clock::Clock, instruction::InstructionError, pubkey::Pubkey, signature::Signer,
+        signer::keypair::Keypair, transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            vesting::{VestingAccount, VestingConfig},
+            BaseStateWithExtensions,
+        },
+        instruction::AuthorityType,
+    },
+    spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
+};
+
+fn client_error(token_error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, InstructionError::Custom(token_error as u32)),
+    )))
+}
+
+#[tokio::test]
+async fn success_initialize() {
+    let authority = Pubkey::new_unique();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::Vesting {
+            authority: Some(authority),
+        }])
+        .await
+        .unwrap();
+    let TokenContext { token, alice, .. } = context.token_context.unwrap();
+
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<VestingConfig>().unwrap();
+    assert_eq!(Option::<Pubkey>::from(extension.authority), Some(authority));
+
+    // accounts get the extension automatically
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let state = token
+        .get_account_info(&alice_account.pubkey())
+        .await
+        .unwrap();
+    let extension = state.get_extension::<VestingAccount>().unwrap();
+    assert_eq!(U256::from(extension.locked_total), U256::ZERO);
+}
+
+#[tokio::test]
+async fn locked_balance() {
+    let authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::Vesting {
+            authority: Some(authority.pubkey()),
+        }])
+        .await
+        .unwrap();
+    let TokenContext {
+        mint_authority,
+        token,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            U256::new(1_100),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let mut clock = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap();
+    let start = clock.unix_timestamp;
+    let cliff = start + 100;
+    let end = start + 200;
+
+    // fail, wrong signer
+    let error = token
+        .set_vesting_schedule(
+            &alice_account,
+            &alice.pubkey(),
+            &alice.pubkey(),
+            start,
+            cliff,
+            end,
+            U256::new(1_000),
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::OwnerMismatch));
+
+    // fail, cliff after end
+    let error = token
+        .set_vesting_schedule(
+            &alice_account,
+            &authority.pubkey(),
+            &alice.pubkey(),
+            start,
+            end + 1,
+            end,
+            U256::new(1_000),
+            &[&authority, &alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::InvalidVestingSchedule));
+
+    // fail, more than the balance
+    let error = token
+        .set_vesting_schedule(
+            &alice_account,
+            &authority.pubkey(),
+            &alice.pubkey(),
+            start,
+            cliff,
+            end,
+            U256::new(1_101),
+            &[&authority, &alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::InsufficientFunds));
+
+    // fail, the owner doesn't sign
+    let error = token
+        .set_vesting_schedule(
+            &alice_account,
+            &authority.pubkey(),
+            &alice.pubkey(),
+            start,
+            cliff,
+            end,
+            U256::new(1_000),
+            &[&authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+        )))
+    );
+
+    token
+        .set_vesting_schedule(
+            &alice_account,
+            &authority.pubkey(),
+            &alice.pubkey(),
+            start,
+            cliff,
+            end,
+            U256::new(1_000),
+            &[&authority, &alice],
+        )
+        .await
+        .unwrap();
+
+    // fail, the running schedule can't lock more
+    let error = token
+        .set_vesting_schedule(
+            &alice_account,
+            &authority.pubkey(),
+            &alice.pubkey(),
+            start,
+            cliff,
+            end,
+            U256::new(1_001),
+            &[&authority, &alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::VestingScheduleExtended));
+
+    // only the unlocked part can be spent
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::new(60),
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    token
+        .burn(&alice_account, &alice.pubkey(), U256::new(40), &[&alice])
+        .await
+        .unwrap();
+    let error = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::ONE,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::InsufficientUnlockedFunds));
+    let error = token
+        .burn(&alice_account, &alice.pubkey(), U256::ONE, &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::InsufficientUnlockedFunds));
+
+    // half unlocks at the cliff
+    clock.unix_timestamp = start + 150;
+    context.context.lock().await.set_sysvar(&clock);
+    let error = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::new(751),
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::InsufficientUnlockedFunds));
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            U256::new(750),
+            &[&alice],
+        )
+        .await
+        .unwrap();
+
+    // fail, the running schedule can't end later
+    let error = token
+        .set_vesting_schedule(
+            &alice_account,
+            &authority.pubkey(),
+            &alice.pubkey(),
+            start,
+            cliff,
+            end + 1,
+            U256::new(250),
+            &[&authority, &alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::VestingScheduleExtended));
+
+    // it can be narrowed to what is still locked
+    token
+        .set_vesting_schedule(
+            &alice_account,
+            &authority.pubkey(),
+            &alice.pubkey(),
+            start,
+            cliff,
+            end,
+            U256::new(250),
+            &[&authority, &alice],
+        )
+        .await
+        .unwrap();
+
+    // everything unlocks at the end
+    clock.unix_timestamp = end;
+    context.context.lock().await.set_sysvar(&clock);
+    token
+        .burn(&alice_account, &alice.pubkey(), U256::new(250), &[&alice])
+        .await
+        .unwrap();
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(U256::from(state.base.amount), U256::ZERO);
+
+    // remove the authority, no more locks
+    token
+        .set_authority(
+            token.get_address(),
+            &authority.pubkey(),
+            None,
+            AuthorityType::Vesting,
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    let error = token
+        .set_vesting_schedule(
+            &bob_account,
+            &authority.pubkey(),
+            &bob.pubkey(),
+            start,
+            cliff,
+            end,
+            U256::ONE,
+            &[&authority, &bob],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, client_error(TokenError::NoAuthorityExists));
+}
@@ -0,0 +1,134 @@
+mod program_test;
+use {
+    ethnum::U256,
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_pod::bytemuck::pod_from_bytes,
+    spl_token_2022::pod::PodWeightedMultisig,
+    spl_token_client::token::TokenError as TokenClientError,
+};
+
+#[tokio::test]
+async fn transfer_with_weighted_signers() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext {
+        mint_authority,
+        token,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let treasurer = Keypair::new();
+    let clerk1 = Keypair::new();
+    let clerk2 = Keypair::new();
+    let multisig = Keypair::new();
+    token
+        .create_weighted_multisig(
+            &multisig,
+            &[&treasurer.pubkey(), &clerk1.pubkey(), &clerk2.pubkey()],
+            &[2, 1, 1],
+            2,
+        )
+        .await
+        .unwrap();
+    let multisig = multisig.pubkey();
+
+    let multisig_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&multisig_account, &multisig)
+        .await
+        .unwrap();
+    let multisig_account = multisig_account.pubkey();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+    token
+        .mint_to(
+            &multisig_account,
+            &mint_authority.pubkey(),
+            U256::new(100),
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // fail, a single clerk doesn't meet the threshold
+    let error = token
+        .transfer(
+            &multisig_account,
+            &bob_account,
+            &multisig,
+            U256::new(10),
+            &[&clerk1],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+        )))
+    );
+
+    // success, the treasurer alone
+    token
+        .transfer(
+            &multisig_account,
+            &bob_account,
+            &multisig,
+            U256::new(10),
+            &[&treasurer],
+        )
+        .await
+        .unwrap();
+
+    // success, both clerks together
+    token
+        .transfer(
+            &multisig_account,
+            &bob_account,
+            &multisig,
+            U256::new(20),
+            &[&clerk1, &clerk2],
+        )
+        .await
+        .unwrap();
+
+    let bob_state = token.get_account_info(&bob_account).await.unwrap();
+    assert_eq!(bob_state.base.amount, U256::new(30));
+}
+
+#[tokio::test]
+async fn more_than_eleven_signers() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    let members = (0..20).map(|_| Keypair::new()).collect::<Vec<_>>();
+    let member_pubkeys = members
+        .iter()
+        .map(|member| member.pubkey())
+        .collect::<Vec<_>>();
+    let member_pubkey_refs = member_pubkeys.iter().collect::<Vec<_>>();
+    let multisig = Keypair::new();
+    token
+        .create_weighted_multisig(&multisig, &member_pubkey_refs, &[1; 20], 15)
+        .await
+        .unwrap();
+
+    let account = token.get_account(multisig.pubkey()).await.unwrap();
+    assert!(PodWeightedMultisig::is_weighted_multisig(&account.data));
+    let state = pod_from_bytes::<PodWeightedMultisig>(&account.data).unwrap();
+    assert_eq!(u16::from(state.threshold), 15);
+    assert_eq!(state.n, 20);
+    assert_eq!(state.total_weight(), 20);
+    assert_eq!(&state.signers[..20], &member_pubkeys[..]);
+}
@@ -49,6 +49,14 @@ pub enum RegistryInstruction {
         /// for the proof.
         proof_instruction_offset: i8,
     },
+    /// Close an ElGamal public key registry and transfer its lamports to a
+    /// destination account. The registry account data is zeroed so that the
+    /// registry can be re-created later.
+    ///
+    /// 0. `[writable]` The ElGamal registry account
+    /// 1. `[writable]` The destination account for the registry lamports
+    /// 2. `[signer]` The owner of the ElGamal public key registry
+    CloseRegistry,
 }
 
 impl RegistryInstruction {
@@ -73,6 +81,7 @@ impl RegistryInstruction {
                     proof_instruction_offset: proof_instruction_offset as i8,
                 }
             }
+            2 => Self::CloseRegistry,
             _ => return Err(ProgramError::InvalidInstructionData),
         })
     }
@@ -93,6 +102,7 @@ impl RegistryInstruction {
                 buf.push(1);
                 buf.extend_from_slice(&proof_instruction_offset.to_le_bytes());
             }
+            Self::CloseRegistry => buf.push(2),
         };
         buf
     }
@@ -147,6 +157,21 @@ pub fn update_registry(
     Ok(instructions)
 }
 
+/// Create a `RegistryInstruction::CloseRegistry` instruction
+pub fn close_registry(owner_address: &Pubkey, destination_address: &Pubkey) -> Instruction {
+    let elgamal_registry_address = get_elgamal_registry_address(owner_address, &id());
+
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new(elgamal_registry_address, false),
+            AccountMeta::new(*destination_address, false),
+            AccountMeta::new_readonly(*owner_address, true),
+        ],
+        data: RegistryInstruction::CloseRegistry.pack(),
+    }
+}
+
 /// Takes a `ProofLocation`, updates the list of accounts, and returns a
 /// suitable proof location
 fn proof_instruction_offset(
@@ -13,13 +13,13 @@ use {
         program_error::ProgramError,
         pubkey::Pubkey,
         rent::Rent,
-        system_instruction,
+        system_instruction, system_program,
         sysvar::Sysvar,
     },
     solana_zk_sdk::zk_elgamal_proof_program::proof_data::pubkey_validity::{
         PubkeyValidityProofContext, PubkeyValidityProofData,
     },
-    spl_pod::bytemuck::pod_from_bytes_mut,
+    spl_pod::bytemuck::{pod_from_bytes, pod_from_bytes_mut},
     spl_token_confidential_transfer_proof_extraction::instruction::verify_and_extract_context,
 };
 
@@ -101,6 +101,45 @@ pub fn process_update_registry_account(
     Ok(())
 }
 
+/// Processes `CloseRegistry` instruction
+pub fn process_close_registry_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let elgamal_registry_account_info = next_account_info(account_info_iter)?;
+    let destination_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+
+    if elgamal_registry_account_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if elgamal_registry_account_info.key == destination_account_info.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    {
+        let elgamal_registry_account_data = &elgamal_registry_account_info.data.borrow();
+        let elgamal_registry_account =
+            pod_from_bytes::<ElGamalRegistry>(elgamal_registry_account_data)?;
+        validate_owner(owner_info, &elgamal_registry_account.owner)?;
+    }
+
+    let destination_starting_lamports = destination_account_info.lamports();
+    **destination_account_info.lamports.borrow_mut() = destination_starting_lamports
+        .checked_add(elgamal_registry_account_info.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **elgamal_registry_account_info.lamports.borrow_mut() = 0;
+
+    // zero and shrink the registry data, and hand the account back to the
+    // system program so that the registry address can be re-created
+    elgamal_registry_account_info.data.borrow_mut().fill(0);
+    elgamal_registry_account_info.realloc(0, false)?;
+    elgamal_registry_account_info.assign(&system_program::id());
+
+    Ok(())
+}
+
 /// Instruction processor
 pub fn process_instruction(
     program_id: &Pubkey,
@@ -121,6 +160,10 @@ pub fn process_instruction(
             msg!("ElGamalRegistryInstruction::UpdateRegistry");
             process_update_registry_account(program_id, accounts, proof_instruction_offset as i64)
         }
+        RegistryInstruction::CloseRegistry => {
+            msg!("ElGamalRegistryInstruction::CloseRegistry");
+            process_close_registry_account(program_id, accounts)
+        }
     }
 }
 
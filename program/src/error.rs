@@ -266,9 +266,72 @@ pub enum TokenError {
     /// Invalid scale for scaled ui amount
     #[error("Invalid scale for scaled ui amount")]
     InvalidScale,
-    /// Transferring, minting, and burning is paused on this mint
-    #[error("Transferring, minting, and burning is paused on this mint")]
+    /// Transferring, minting, or burning is paused on this mint
+    #[error("Transferring, minting, or burning is paused on this mint")]
     MintPaused,
+    /// Scheduled resume must come after the scheduled pause
+    #[error("Scheduled resume must come after the scheduled pause")]
+    InvalidPauseSchedule,
+    /// Transfer fee tiers must have increasing non-zero minimum amounts
+    #[error("Transfer fee tiers must have increasing non-zero minimum amounts")]
+    InvalidTransferFeeTiers,
+
+    // 70
+    /// Tiered transfer fees are not supported for confidential transfers
+    #[error("Tiered transfer fees are not supported for confidential transfers")]
+    ConfidentialTransferWithTieredFee,
+    /// No fee recipient is configured, or the account is not the fee recipient
+    #[error("No fee recipient is configured, or the account is not the fee recipient")]
+    InvalidFeeRecipient,
+    /// Minting would exceed the maximum supply of the mint
+    #[error("Minting would exceed the maximum supply of the mint")]
+    MaxSupplyExceeded,
+    /// The maximum supply of a mint can only be lowered
+    #[error("The maximum supply of a mint can only be lowered")]
+    MaxSupplyIncrease,
+    /// Transfer would exceed the transfer limit of the account
+    #[error("Transfer would exceed the transfer limit of the account")]
+    TransferLimitExceeded,
+    /// Transfer limit window must not be negative
+    #[error("Transfer limit window must not be negative")]
+    InvalidTransferLimit,
+    /// Vesting schedule timestamps must be ordered start, cliff, end
+    #[error("Vesting schedule timestamps must be ordered start, cliff, end")]
+    InvalidVestingSchedule,
+    /// Insufficient unlocked funds, part of the balance is still vesting
+    #[error("Insufficient unlocked funds, part of the balance is still vesting")]
+    InsufficientUnlockedFunds,
+    /// Extension is required by the mint or still in use by the account
+    #[error("Extension is required by the mint or still in use by the account")]
+    ExtensionNotRemovable,
+    /// The permanent delegate scope can only be narrowed
+    #[error("The permanent delegate scope can only be narrowed")]
+    PermanentDelegateScopeWidened,
+
+    // 80
+    /// Delegate expiry must be in the future
+    #[error("Delegate expiry must be in the future")]
+    InvalidDelegateExpiry,
+    /// The account's delegate table is full
+    #[error("The account's delegate table is full")]
+    DelegateTableFull,
+    /// Memo doesn't follow the rules of the account
+    #[error("Memo doesn't follow the rules of the account")]
+    MemoRulesViolated,
+    /// Memo rules can't be satisfied by any memo
+    #[error("Memo rules can't be satisfied by any memo")]
+    InvalidMemoRules,
+    /// Recovery is not enabled for this mint
+    #[error("Recovery is not enabled for this mint")]
+    RecoveryNotEnabled,
+    /// Account can't be used in a batch, since its checks need the
+    /// surrounding instructions
+    #[error("Account can't be used in a batch")]
+    InvalidBatchAccount,
+    /// A new vesting schedule can't lock more or for longer than the current
+    /// one
+    #[error("A new vesting schedule can't lock more or for longer than the current one")]
+    VestingScheduleExtended,
 }
 impl From<TokenError> for ProgramError {
     fn from(e: TokenError) -> Self {
@@ -463,7 +526,64 @@ impl PrintProgramError for TokenError {
                 msg!("Invalid scale for scaled ui amount")
             }
             TokenError::MintPaused => {
-                msg!("Transferring, minting, and burning is paused on this mint")
+                msg!("Transferring, minting, or burning is paused on this mint")
+            }
+            TokenError::InvalidPauseSchedule => {
+                msg!("Scheduled resume must come after the scheduled pause")
+            }
+            TokenError::InvalidTransferFeeTiers => {
+                msg!("Transfer fee tiers must have increasing non-zero minimum amounts")
+            }
+            TokenError::ConfidentialTransferWithTieredFee => {
+                msg!("Tiered transfer fees are not supported for confidential transfers")
+            }
+            TokenError::InvalidFeeRecipient => {
+                msg!("No fee recipient is configured, or the account is not the fee recipient")
+            }
+            TokenError::MaxSupplyExceeded => {
+                msg!("Minting would exceed the maximum supply of the mint")
+            }
+            TokenError::MaxSupplyIncrease => {
+                msg!("The maximum supply of a mint can only be lowered")
+            }
+            TokenError::TransferLimitExceeded => {
+                msg!("Transfer would exceed the transfer limit of the account")
+            }
+            TokenError::InvalidTransferLimit => {
+                msg!("Transfer limit window must not be negative")
+            }
+            TokenError::InvalidVestingSchedule => {
+                msg!("Vesting schedule timestamps must be ordered start, cliff, end")
+            }
+            TokenError::InsufficientUnlockedFunds => {
+                msg!("Insufficient unlocked funds, part of the balance is still vesting")
+            }
+            TokenError::ExtensionNotRemovable => {
+                msg!("Extension is required by the mint or still in use by the account")
+            }
+            TokenError::PermanentDelegateScopeWidened => {
+                msg!("The permanent delegate scope can only be narrowed")
+            }
+            TokenError::InvalidDelegateExpiry => {
+                msg!("Delegate expiry must be in the future")
+            }
+            TokenError::DelegateTableFull => {
+                msg!("The account's delegate table is full")
+            }
+            TokenError::MemoRulesViolated => {
+                msg!("Memo doesn't follow the rules of the account")
+            }
+            TokenError::InvalidMemoRules => {
+                msg!("Memo rules can't be satisfied by any memo")
+            }
+            TokenError::RecoveryNotEnabled => {
+                msg!("Recovery is not enabled for this mint")
+            }
+            TokenError::InvalidBatchAccount => {
+                msg!("Account can't be used in a batch")
+            }
+            TokenError::VestingScheduleExtended => {
+                msg!("A new vesting schedule can't lock more or for longer than the current one")
             }
         }
     }
@@ -0,0 +1,451 @@
+//! Structured events emitted on state changes, for consumption by indexers
+//!
+//! Every event is logged through `sol_log_data` as two fields: a header of
+//! `[event_type, version]`, followed by the pod-encoded event body. Later
+//! versions of an event may only append fields to its body, so a decoder can
+//! always read the prefix it knows about.
+
+use {
+    crate::{
+        extension::{interest_bearing_mint::BasisPoints, scaled_ui_amount::PodF64},
+        pod::PodCOption,
+    },
+    bytemuck::{Pod, Zeroable},
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{log::sol_log_data, pubkey::Pubkey},
+    spl_pod::primitives::{PodBool, PodI64, PodU256, PodU64},
+};
+
+/// Current layout version of the event bodies
+pub const EVENT_VERSION: u8 = 1;
+
+/// Length of the header logged ahead of every event body
+pub const EVENT_HEADER_LEN: usize = 2;
+
+/// Types of events emitted by the program
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum EventType {
+    /// Tokens were minted to an account, see [`MintToEvent`]
+    MintTo,
+    /// Tokens were burned from an account, see [`BurnEvent`]
+    Burn,
+    /// Tokens were moved between accounts, see [`TransferEvent`]
+    Transfer,
+    /// An authority was changed, see [`SetAuthorityEvent`]
+    SetAuthority,
+    /// An account was frozen, see [`FreezeAccountEvent`]
+    FreezeAccount,
+    /// An account was thawed, see [`ThawAccountEvent`]
+    ThawAccount,
+    /// The pause state of a mint changed, see [`UpdatePauseStateEvent`]
+    UpdatePauseState,
+    /// The UI amount multiplier of a mint changed, see
+    /// [`UpdateMultiplierEvent`]
+    UpdateMultiplier,
+    /// The interest rate of a mint changed, see [`UpdateRateEvent`]
+    UpdateRate,
+    /// Tokens were deposited into a confidential pending balance, see
+    /// [`ConfidentialDepositEvent`]
+    ConfidentialDeposit,
+    /// Tokens were withdrawn from a confidential available balance, see
+    /// [`ConfidentialWithdrawEvent`]
+    ConfidentialWithdraw,
+    /// Tokens were minted confidentially, see [`ConfidentialMintEvent`]
+    ConfidentialMint,
+    /// Tokens were burned confidentially, see [`ConfidentialBurnEvent`]
+    ConfidentialBurn,
+    /// Withheld transfer fees were harvested to the mint, see
+    /// [`HarvestWithheldTokensEvent`]
+    HarvestWithheldTokens,
+    /// Withheld transfer fees were withdrawn, see
+    /// [`WithdrawWithheldTokensEvent`]
+    WithdrawWithheldTokens,
+    /// Withheld transfer fees were swept to the fee recipient, see
+    /// [`SweepWithheldTokensEvent`]
+    SweepWithheldTokens,
+    /// Tokens in an account were locked under a vesting schedule, see
+    /// [`VestingLockEvent`]
+    VestingLock,
+    /// A delegate was approved in an account's delegate table, see
+    /// [`MultiDelegateApproveEvent`]
+    MultiDelegateApprove,
+    /// A delegate was removed from an account's delegate table, see
+    /// [`MultiDelegateRevokeEvent`]
+    MultiDelegateRevoke,
+}
+
+/// Pod-encoded event body, tied to its event type
+pub trait Event: Pod {
+    /// Type of the event, logged in its header
+    const TYPE: EventType;
+}
+
+/// Encode the event into the header and body fields that are logged for it
+pub fn encode<E: Event>(event: &E) -> ([u8; EVENT_HEADER_LEN], &[u8]) {
+    ([E::TYPE.into(), EVENT_VERSION], bytemuck::bytes_of(event))
+}
+
+/// Log the event with its header
+pub(crate) fn emit<E: Event>(event: &E) {
+    let (header, body) = encode(event);
+    sol_log_data(&[&header, body]);
+}
+
+/// Tokens were minted to an account
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct MintToEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// The account credited with the new tokens
+    pub destination: Pubkey,
+    /// Amount of tokens minted
+    pub amount: PodU256,
+    /// Supply of the mint after minting
+    pub supply: PodU256,
+}
+impl Event for MintToEvent {
+    const TYPE: EventType = EventType::MintTo;
+}
+
+/// Tokens were burned from an account
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct BurnEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// The account debited with the burned tokens
+    pub source: Pubkey,
+    /// Amount of tokens burned
+    pub amount: PodU256,
+    /// Supply of the mint after burning
+    pub supply: PodU256,
+}
+impl Event for BurnEvent {
+    const TYPE: EventType = EventType::Burn;
+}
+
+/// Tokens were moved between accounts
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct TransferEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// The account debited with `amount`
+    pub source: Pubkey,
+    /// The account credited with `amount - fee`
+    pub destination: Pubkey,
+    /// The owner, delegate or recovery authority that signed the transfer
+    pub authority: Pubkey,
+    /// Amount of tokens debited from the source
+    pub amount: PodU256,
+    /// Transfer fee withheld in the destination
+    pub fee: PodU256,
+    /// Whether the transfer was a recovery of non-transferable tokens
+    pub recovery: PodBool,
+}
+impl Event for TransferEvent {
+    const TYPE: EventType = EventType::Transfer;
+}
+
+/// An authority on a mint or account was changed
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SetAuthorityEvent {
+    /// The mint or account holding the authority
+    pub account: Pubkey,
+    /// The `AuthorityType` that was changed
+    pub authority_type: u8,
+    /// The new authority, if any
+    pub new_authority: PodCOption<Pubkey>,
+}
+impl Event for SetAuthorityEvent {
+    const TYPE: EventType = EventType::SetAuthority;
+}
+
+/// An account was frozen
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct FreezeAccountEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// The frozen account
+    pub account: Pubkey,
+}
+impl Event for FreezeAccountEvent {
+    const TYPE: EventType = EventType::FreezeAccount;
+}
+
+/// An account was thawed
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct ThawAccountEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// The thawed account
+    pub account: Pubkey,
+}
+impl Event for ThawAccountEvent {
+    const TYPE: EventType = EventType::ThawAccount;
+}
+
+/// The pause state of a mint changed, either directly or through its
+/// schedule
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct UpdatePauseStateEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// Whether minting tokens is paused
+    pub mint_paused: PodBool,
+    /// Whether burning tokens is paused
+    pub burn_paused: PodBool,
+    /// Whether transferring tokens is paused
+    pub transfer_paused: PodBool,
+    /// Unix timestamp at which all operations become paused, or 0
+    pub scheduled_pause_timestamp: PodI64,
+    /// Unix timestamp at which all operations are resumed, or 0
+    pub scheduled_resume_timestamp: PodI64,
+}
+impl Event for UpdatePauseStateEvent {
+    const TYPE: EventType = EventType::UpdatePauseState;
+}
+
+/// The UI amount multiplier of a mint changed
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct UpdateMultiplierEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// Multiplier in effect until `effective_timestamp`
+    pub multiplier: PodF64,
+    /// Multiplier in effect from `effective_timestamp`
+    pub new_multiplier: PodF64,
+    /// Unix timestamp at which `new_multiplier` comes into effect
+    pub effective_timestamp: PodI64,
+}
+impl Event for UpdateMultiplierEvent {
+    const TYPE: EventType = EventType::UpdateMultiplier;
+}
+
+/// The interest rate of a mint changed
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct UpdateRateEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// Rate in effect from `timestamp`
+    pub current_rate: BasisPoints,
+    /// Average rate from initialization until `timestamp`
+    pub pre_update_average_rate: BasisPoints,
+    /// Unix timestamp of the update
+    pub timestamp: PodI64,
+}
+impl Event for UpdateRateEvent {
+    const TYPE: EventType = EventType::UpdateRate;
+}
+
+/// Tokens were moved from the non-confidential balance of an account to its
+/// confidential pending balance
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct ConfidentialDepositEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// The account
+    pub account: Pubkey,
+    /// Amount of tokens deposited
+    pub amount: PodU256,
+    /// Pending balance credit counter of the account after the deposit
+    pub pending_balance_credit_counter: PodU64,
+}
+impl Event for ConfidentialDepositEvent {
+    const TYPE: EventType = EventType::ConfidentialDeposit;
+}
+
+/// Tokens were moved from the confidential available balance of an account to
+/// its non-confidential balance
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct ConfidentialWithdrawEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// The account
+    pub account: Pubkey,
+    /// Amount of tokens withdrawn
+    pub amount: PodU256,
+}
+impl Event for ConfidentialWithdrawEvent {
+    const TYPE: EventType = EventType::ConfidentialWithdraw;
+}
+
+/// Tokens were minted to the confidential pending balance of an account. The
+/// amount is encrypted, so it isn't part of the event.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct ConfidentialMintEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// The account credited with the new tokens
+    pub destination: Pubkey,
+    /// Pending balance credit counter of the account after minting
+    pub pending_balance_credit_counter: PodU64,
+}
+impl Event for ConfidentialMintEvent {
+    const TYPE: EventType = EventType::ConfidentialMint;
+}
+
+/// Tokens were burned from the confidential available balance of an account.
+/// The amount is encrypted, so it isn't part of the event.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct ConfidentialBurnEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// The account debited with the burned tokens
+    pub source: Pubkey,
+}
+impl Event for ConfidentialBurnEvent {
+    const TYPE: EventType = EventType::ConfidentialBurn;
+}
+
+/// Transfer fees withheld in an account were harvested to the mint
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct HarvestWithheldTokensEvent {
+    /// The mint, now withholding the fees
+    pub mint: Pubkey,
+    /// The account the fees were harvested from
+    pub account: Pubkey,
+    /// Amount of withheld tokens harvested
+    pub amount: PodU256,
+}
+impl Event for HarvestWithheldTokensEvent {
+    const TYPE: EventType = EventType::HarvestWithheldTokens;
+}
+
+/// Transfer fees withheld in the mint or an account were withdrawn by the
+/// withdraw withheld authority
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct WithdrawWithheldTokensEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// The mint or account the fees were withheld in
+    pub source: Pubkey,
+    /// The account credited with the fees
+    pub destination: Pubkey,
+    /// Amount of withheld tokens withdrawn
+    pub amount: PodU256,
+}
+impl Event for WithdrawWithheldTokensEvent {
+    const TYPE: EventType = EventType::WithdrawWithheldTokens;
+}
+
+/// Transfer fees withheld in the mint were swept to its fee recipient
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SweepWithheldTokensEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// The fee recipient credited with the fees
+    pub fee_recipient: Pubkey,
+    /// Amount of withheld tokens swept
+    pub amount: PodU256,
+}
+impl Event for SweepWithheldTokensEvent {
+    const TYPE: EventType = EventType::SweepWithheldTokens;
+}
+
+/// Tokens in an account were locked under a vesting schedule, replacing any
+/// previous schedule
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct VestingLockEvent {
+    /// The mint
+    pub mint: Pubkey,
+    /// The account
+    pub account: Pubkey,
+    /// Amount of tokens locked at the start of the schedule
+    pub locked_total: PodU256,
+    /// Unix timestamp at which the schedule starts
+    pub start_timestamp: PodI64,
+    /// Unix timestamp before which no tokens unlock
+    pub cliff_timestamp: PodI64,
+    /// Unix timestamp at which all tokens are unlocked
+    pub end_timestamp: PodI64,
+}
+impl Event for VestingLockEvent {
+    const TYPE: EventType = EventType::VestingLock;
+}
+
+/// A delegate was approved in an account's delegate table
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct MultiDelegateApproveEvent {
+    /// The account
+    pub account: Pubkey,
+    /// The approved delegate
+    pub delegate: Pubkey,
+    /// Amount of tokens the delegate may transfer or burn
+    pub allowance: PodU256,
+    /// Unix timestamp at which the approval expires, or 0
+    pub expires_at: PodI64,
+}
+impl Event for MultiDelegateApproveEvent {
+    const TYPE: EventType = EventType::MultiDelegateApprove;
+}
+
+/// A delegate was removed from an account's delegate table
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct MultiDelegateRevokeEvent {
+    /// The account
+    pub account: Pubkey,
+    /// The revoked delegate
+    pub delegate: Pubkey,
+}
+impl Event for MultiDelegateRevokeEvent {
+    const TYPE: EventType = EventType::MultiDelegateRevoke;
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::mem::size_of};
+
+    #[test]
+    fn event_layouts() {
+        // bodies may only grow, so these sizes must never shrink
+        assert_eq!(size_of::<MintToEvent>(), 128);
+        assert_eq!(size_of::<BurnEvent>(), 128);
+        assert_eq!(size_of::<TransferEvent>(), 193);
+        assert_eq!(size_of::<SetAuthorityEvent>(), 69);
+        assert_eq!(size_of::<FreezeAccountEvent>(), 64);
+        assert_eq!(size_of::<ThawAccountEvent>(), 64);
+        assert_eq!(size_of::<UpdatePauseStateEvent>(), 51);
+        assert_eq!(size_of::<UpdateMultiplierEvent>(), 56);
+        assert_eq!(size_of::<UpdateRateEvent>(), 44);
+        assert_eq!(size_of::<ConfidentialDepositEvent>(), 104);
+        assert_eq!(size_of::<ConfidentialWithdrawEvent>(), 96);
+        assert_eq!(size_of::<ConfidentialMintEvent>(), 72);
+        assert_eq!(size_of::<ConfidentialBurnEvent>(), 64);
+        assert_eq!(size_of::<HarvestWithheldTokensEvent>(), 96);
+        assert_eq!(size_of::<WithdrawWithheldTokensEvent>(), 128);
+        assert_eq!(size_of::<SweepWithheldTokensEvent>(), 96);
+        assert_eq!(size_of::<VestingLockEvent>(), 120);
+        assert_eq!(size_of::<MultiDelegateApproveEvent>(), 104);
+        assert_eq!(size_of::<MultiDelegateRevokeEvent>(), 64);
+    }
+
+    #[test]
+    fn event_type_round_trip() {
+        for event_type in 0..=u8::from(EventType::MultiDelegateRevoke) {
+            assert_eq!(
+                u8::from(EventType::try_from(event_type).unwrap()),
+                event_type
+            );
+        }
+        assert!(EventType::try_from(u8::from(EventType::MultiDelegateRevoke) + 1).is_err());
+    }
+}
@@ -1,5 +1,8 @@
 #[cfg(feature = "zk-ops")]
-use spl_token_confidential_transfer_ciphertext_arithmetic as ciphertext_arithmetic;
+use {
+    crate::event::{self, ConfidentialBurnEvent, ConfidentialMintEvent},
+    spl_token_confidential_transfer_ciphertext_arithmetic as ciphertext_arithmetic,
+};
 use {
     crate::{
         check_auditor_ciphertext, check_program_account,
@@ -15,7 +18,7 @@ use {
                 ConfidentialMintBurn,
             },
             confidential_transfer::{ConfidentialTransferAccount, ConfidentialTransferMint},
-            pausable::PausableConfig,
+            pausable::{self, PausableOperation},
             BaseStateWithExtensions, BaseStateWithExtensionsMut, PodStateWithExtensionsMut,
         },
         instruction::{decode_instruction_data, decode_instruction_type},
@@ -162,10 +165,8 @@ fn process_confidential_mint(
     let auditor_elgamal_pubkey = mint
         .get_extension::<ConfidentialTransferMint>()?
         .auditor_elgamal_pubkey;
-    if let Ok(extension) = mint.get_extension::<PausableConfig>() {
-        if extension.paused.into() {
-            return Err(TokenError::MintPaused.into());
-        }
+    if pausable::is_paused(&mint, PausableOperation::Mint)? {
+        return Err(TokenError::MintPaused.into());
     }
     let mint_burn_extension = mint.get_extension_mut::<ConfidentialMintBurn>()?;
 
@@ -270,6 +271,13 @@ fn process_confidential_mint(
     .ok_or(TokenError::CiphertextArithmeticFailed)?;
     mint_burn_extension.decryptable_supply = data.new_decryptable_supply;
 
+    event::emit(&ConfidentialMintEvent {
+        mint: *mint_info.key,
+        destination: *token_account_info.key,
+        pending_balance_credit_counter: confidential_transfer_account
+            .pending_balance_credit_counter,
+    });
+
     Ok(())
 }
 
@@ -291,10 +299,8 @@ fn process_confidential_burn(
     let auditor_elgamal_pubkey = mint
         .get_extension::<ConfidentialTransferMint>()?
         .auditor_elgamal_pubkey;
-    if let Ok(extension) = mint.get_extension::<PausableConfig>() {
-        if extension.paused.into() {
-            return Err(TokenError::MintPaused.into());
-        }
+    if pausable::is_paused(&mint, PausableOperation::Burn)? {
+        return Err(TokenError::MintPaused.into());
     }
     let mint_burn_extension = mint.get_extension_mut::<ConfidentialMintBurn>()?;
 
@@ -404,6 +410,11 @@ fn process_confidential_burn(
     )
     .ok_or(TokenError::CiphertextArithmeticFailed)?;
 
+    event::emit(&ConfidentialBurnEvent {
+        mint: *mint_info.key,
+        source: *token_account_info.key,
+    });
+
     Ok(())
 }
 
@@ -181,10 +181,16 @@ pub enum ConfidentialTransferInstruction {
     /// to roll the deposit into their available balance at a time of their
     /// choosing.
     ///
+    /// Confidential amounts are not full-width: a deposit is limited to
+    /// `MAXIMUM_DEPOSIT_TRANSFER_AMOUNT` tokens and confidential balances to
+    /// 64 bits, even though the non-confidential balance may hold any `U256`
+    /// amount.
+    ///
     /// Fails if the source or destination accounts are frozen.
     /// Fails if the associated mint is extended as `NonTransferable`.
     /// Fails if the associated mint is extended as `ConfidentialMintBurn`.
-    /// Fails if the associated mint is paused with the `Pausable` extension.
+    /// Fails if transfers on the associated mint are paused with the `Pausable`
+    /// extension.
     ///
     /// Accounts expected by this instruction:
     ///
@@ -221,7 +227,8 @@ pub enum ConfidentialTransferInstruction {
     /// Fails if the source or destination accounts are frozen.
     /// Fails if the associated mint is extended as `NonTransferable`.
     /// Fails if the associated mint is extended as `ConfidentialMintBurn`.
-    /// Fails if the associated mint is paused with the `Pausable` extension.
+    /// Fails if transfers on the associated mint are paused with the `Pausable`
+    /// extension.
     ///
     /// Accounts expected by this instruction:
     ///
@@ -441,6 +448,9 @@ pub enum ConfidentialTransferInstruction {
     /// `TransferWithFee`. Namely, the instruction fails if the
     /// associated mint is extended as `NonTransferable`.
     ///
+    /// Transfers to or from an account exempt from transfer fees carry no
+    /// fee, and must use `Transfer` instead.
+    ///
     ///   * Transfer without fee
     ///   1. `[writable]` The source SPL Token account.
     ///   2. `[]` The token mint.
@@ -973,6 +983,8 @@ pub fn empty_account(
 }
 
 /// Create a `Deposit` instruction
+///
+/// Fails if `amount` does not fit in the 64-bit instruction data
 #[allow(clippy::too_many_arguments)]
 pub fn deposit(
     token_program_id: &Pubkey,
@@ -1000,7 +1012,9 @@ pub fn deposit(
         TokenInstruction::ConfidentialTransferExtension,
         ConfidentialTransferInstruction::Deposit,
         &DepositInstructionData {
-            amount: amount.into(),
+            amount: u64::try_from(amount)
+                .map_err(|_| TokenError::MaximumDepositAmountExceeded)?
+                .into(),
             decimals,
         },
     ))
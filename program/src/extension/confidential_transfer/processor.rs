@@ -1,8 +1,12 @@
 // Remove feature once zk ops syscalls are enabled on all networks
 #[cfg(feature = "zk-ops")]
 use {
+    crate::event::{self, ConfidentialDepositEvent, ConfidentialWithdrawEvent},
     crate::extension::confidential_mint_burn::ConfidentialMintBurn,
     crate::extension::non_transferable::NonTransferableAccount,
+    crate::extension::transfer_limit::TransferLimit,
+    crate::extension::vesting::VestingAccount,
+    ethnum::U256,
     spl_token_confidential_transfer_ciphertext_arithmetic as ciphertext_arithmetic,
 };
 use {
@@ -15,10 +19,10 @@ use {
                 ConfidentialTransferFeeAmount, ConfidentialTransferFeeConfig,
                 EncryptedWithheldAmount,
             },
-            memo_transfer::{check_previous_sibling_instruction_is_memo, memo_required},
-            pausable::PausableConfig,
+            memo_transfer::{check_incoming_transfer_memo, check_outgoing_transfer_memo},
+            pausable::{self, PausableOperation},
             set_account_type,
-            transfer_fee::TransferFeeConfig,
+            transfer_fee::{TransferFeeConfig, TransferFeeTiers},
             transfer_hook, BaseStateWithExtensions, BaseStateWithExtensionsMut,
             PodStateWithExtensions, PodStateWithExtensionsMut,
         },
@@ -398,10 +402,8 @@ fn process_deposit(
     let mint_data = &mint_info.data.borrow_mut();
     let mint = PodStateWithExtensions::<PodMint>::unpack(mint_data)?;
 
-    if let Ok(extension) = mint.get_extension::<PausableConfig>() {
-        if extension.paused.into() {
-            return Err(TokenError::MintPaused.into());
-        }
+    if pausable::is_paused(&mint, PausableOperation::Transfer)? {
+        return Err(TokenError::MintPaused.into());
     }
 
     if expected_decimals != mint.base.decimals {
@@ -441,8 +443,26 @@ fn process_deposit(
     // Wrapped SOL deposits are not supported because lamports cannot be vanished.
     assert!(!token_account.base.is_native());
 
-    token_account.base.amount = u64::from(token_account.base.amount)
-        .checked_sub(amount)
+    // Only unlocked tokens may leave the non-confidential balance
+    if let Ok(vesting) = token_account.get_extension::<VestingAccount>() {
+        if vesting.unlocked_balance(
+            U256::from(token_account.base.amount),
+            Clock::get()?.unix_timestamp,
+        ) < U256::from(amount)
+        {
+            return Err(TokenError::InsufficientUnlockedFunds.into());
+        }
+    }
+
+    // Tokens leaving the non-confidential balance count against its limit
+    if let Ok(transfer_limit) = token_account.get_extension_mut::<TransferLimit>() {
+        transfer_limit.record_outflow(U256::from(amount), Clock::get()?.unix_timestamp)?;
+    }
+
+    // Confidential balances are 64-bit, but the non-confidential balance may
+    // hold any `U256` amount
+    token_account.base.amount = U256::from(token_account.base.amount)
+        .checked_sub(U256::from(amount))
         .ok_or(TokenError::Overflow)?
         .into();
 
@@ -472,6 +492,14 @@ fn process_deposit(
 
     confidential_transfer_account.increment_pending_balance_credit_counter()?;
 
+    event::emit(&ConfidentialDepositEvent {
+        mint: *mint_info.key,
+        account: *token_account_info.key,
+        amount: U256::from(amount).into(),
+        pending_balance_credit_counter: confidential_transfer_account
+            .pending_balance_credit_counter,
+    });
+
     Ok(())
 }
 
@@ -525,10 +553,8 @@ fn process_withdraw(
         return Err(TokenError::IllegalMintBurnConversion.into());
     }
 
-    if let Ok(extension) = mint.get_extension::<PausableConfig>() {
-        if extension.paused.into() {
-            return Err(TokenError::MintPaused.into());
-        }
+    if pausable::is_paused(&mint, PausableOperation::Transfer)? {
+        return Err(TokenError::MintPaused.into());
     }
 
     check_program_account(token_account_info.owner)?;
@@ -589,11 +615,17 @@ fn process_withdraw(
     }
 
     confidential_transfer_account.decryptable_available_balance = new_decryptable_available_balance;
-    token_account.base.amount = u64::from(token_account.base.amount)
-        .checked_add(amount)
+    token_account.base.amount = U256::from(token_account.base.amount)
+        .checked_add(U256::from(amount))
         .ok_or(TokenError::Overflow)?
         .into();
 
+    event::emit(&ConfidentialWithdrawEvent {
+        mint: *mint_info.key,
+        account: *token_account_info.key,
+        amount: U256::from(amount).into(),
+    });
+
     Ok(())
 }
 
@@ -612,8 +644,6 @@ fn process_transfer(
     fee_ciphertext_validity_proof_instruction_offset: Option<i64>,
     range_proof_instruction_offset: i64,
 ) -> ProgramResult {
-    use ethnum::U256;
-
     let account_info_iter = &mut accounts.iter();
     let source_account_info = next_account_info(account_info_iter)?;
     let mint_info = next_account_info(account_info_iter)?;
@@ -623,10 +653,8 @@ fn process_transfer(
     let mint_data = mint_info.data.borrow_mut();
     let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)?;
 
-    if let Ok(extension) = mint.get_extension::<PausableConfig>() {
-        if extension.paused.into() {
-            return Err(TokenError::MintPaused.into());
-        }
+    if pausable::is_paused(&mint, PausableOperation::Transfer)? {
+        return Err(TokenError::MintPaused.into());
     }
 
     let confidential_transfer_mint = mint.get_extension::<ConfidentialTransferMint>()?;
@@ -641,7 +669,12 @@ fn process_transfer(
     //   - If the mint is extended for fees and the instruction is not a
     //     self-transfer, then
     //   transfer fee is required.
-    let authority_info = if mint.get_extension::<TransferFeeConfig>().is_err() {
+    //   - If either account is exempt from transfer fees, then transfer fee is
+    //     not required.
+    let fee_required = mint.get_extension::<TransferFeeConfig>().is_ok()
+        && !Processor::is_account_fee_exempt(source_account_info)?
+        && !Processor::is_account_fee_exempt(destination_account_info)?;
+    let authority_info = if !fee_required {
         // Transfer fee is not required. Decode the zero-knowledge proof as
         // `TransferContext`.
         //
@@ -698,7 +731,15 @@ fn process_transfer(
     } else {
         // Transfer fee is required.
         let transfer_fee_config = mint.get_extension::<TransferFeeConfig>()?;
-        let fee_parameters = transfer_fee_config.get_epoch_fee(Clock::get()?.epoch);
+        let epoch = Clock::get()?.epoch;
+        // The fee proofs only cover a single rate and cap
+        if transfer_fee_config
+            .get_tiered_epoch_fee(mint.get_extension::<TransferFeeTiers>().ok(), epoch)
+            .is_tiered()
+        {
+            return Err(TokenError::ConfidentialTransferWithTieredFee.into());
+        }
+        let fee_parameters = transfer_fee_config.get_epoch_fee(epoch);
 
         let fee_sigma_proof_insruction_offset =
             fee_sigma_proof_instruction_offset.ok_or(ProgramError::InvalidInstructionData)?;
@@ -852,6 +893,8 @@ fn process_source_for_transfer(
         signers,
     )?;
 
+    check_outgoing_transfer_memo(&token_account)?;
+
     if token_account.base.is_frozen() {
         return Err(TokenError::AccountFrozen.into());
     }
@@ -918,9 +961,7 @@ fn process_destination_for_transfer(
         return Err(TokenError::MintMismatch.into());
     }
 
-    if memo_required(&destination_token_account) {
-        check_previous_sibling_instruction_is_memo()?;
-    }
+    check_incoming_transfer_memo(&destination_token_account)?;
 
     let destination_confidential_transfer_account =
         destination_token_account.get_extension_mut::<ConfidentialTransferAccount>()?;
@@ -988,6 +1029,8 @@ fn process_source_for_transfer_with_fee(
         signers,
     )?;
 
+    check_outgoing_transfer_memo(&token_account)?;
+
     if token_account.base.is_frozen() {
         return Err(TokenError::AccountFrozen.into());
     }
@@ -1057,9 +1100,7 @@ fn process_destination_for_transfer_with_fee(
         return Err(TokenError::MintMismatch.into());
     }
 
-    if memo_required(&destination_token_account) {
-        check_previous_sibling_instruction_is_memo()?;
-    }
+    check_incoming_transfer_memo(&destination_token_account)?;
 
     let destination_confidential_transfer_account =
         destination_token_account.get_extension_mut::<ConfidentialTransferAccount>()?;
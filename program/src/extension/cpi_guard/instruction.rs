@@ -3,14 +3,17 @@ use serde::{Deserialize, Serialize};
 use {
     crate::{
         check_program_account,
+        extension::cpi_guard::MAX_ALLOWED_PROGRAMS,
         instruction::{encode_instruction, TokenInstruction},
     },
+    bytemuck::{Pod, Zeroable},
     num_enum::{IntoPrimitive, TryFromPrimitive},
     solana_program::{
         instruction::{AccountMeta, Instruction},
         program_error::ProgramError,
         pubkey::Pubkey,
     },
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
 };
 
 /// CPI Guard extension instructions
@@ -28,6 +31,9 @@ pub enum CpiGuardInstruction {
     ///
     /// In addition, CPI Guard cannot be enabled or disabled via CPI.
     ///
+    /// Programs on the account's allowlist may still perform these operations
+    /// via CPI, see `SetAllowlist`.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   0. `[writable]` The account to update.
@@ -53,6 +59,39 @@ pub enum CpiGuardInstruction {
     ///   1. `[]`  The account's multisignature owner.
     ///   2. `..2+M` `[signer]` M signer accounts.
     Disable,
+    /// Replace the programs allowed to perform the operations locked by CPI
+    /// Guard through CPI. An allowed program must invoke the token program
+    /// directly from the transaction, and pass the instructions sysvar along
+    /// with the other accounts of the guarded instruction.
+    ///
+    /// Implicitly initializes the extension in the case where it is not
+    /// present, so the account must have room for it, see `Reallocate`.
+    ///
+    /// The allowlist cannot be set via CPI.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The account to update.
+    ///   1. `[signer]` The account's owner.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The account to update.
+    ///   1. `[]`  The account's multisignature owner.
+    ///   2. `..2+M` `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::cpi_guard::instruction::SetAllowlistInstructionData`
+    SetAllowlist,
+}
+
+/// Data expected by `CpiGuardInstruction::SetAllowlist`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct SetAllowlistInstructionData {
+    /// The allowed programs, with unused slots set to none
+    pub programs: [OptionalNonZeroPubkey; MAX_ALLOWED_PROGRAMS],
 }
 
 /// Create an `Enable` instruction
@@ -102,3 +141,36 @@ pub fn disable_cpi_guard(
         &(),
     ))
 }
+
+/// Create a `SetAllowlist` instruction, failing if there are more than
+/// `MAX_ALLOWED_PROGRAMS` programs
+pub fn set_allowlist(
+    token_program_id: &Pubkey,
+    account: &Pubkey,
+    owner: &Pubkey,
+    signers: &[&Pubkey],
+    allowed_programs: &[Pubkey],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    if allowed_programs.len() > MAX_ALLOWED_PROGRAMS {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut programs = [OptionalNonZeroPubkey::default(); MAX_ALLOWED_PROGRAMS];
+    for (slot, program) in programs.iter_mut().zip(allowed_programs) {
+        *slot = Some(*program).try_into()?;
+    }
+    let mut accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(*owner, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::CpiGuardExtension,
+        CpiGuardInstruction::SetAllowlist,
+        &SetAllowlistInstructionData { programs },
+    ))
+}
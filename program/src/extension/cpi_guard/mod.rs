@@ -2,12 +2,20 @@
 use serde::{Deserialize, Serialize};
 use {
     crate::{
-        extension::{BaseStateWithExtensions, Extension, ExtensionType, StateWithExtensionsMut},
+        extension::{
+            BaseState, BaseStateWithExtensions, Extension, ExtensionType, StateWithExtensionsMut,
+        },
         state::Account,
     },
     bytemuck::{Pod, Zeroable},
-    solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT},
-    spl_pod::primitives::PodBool,
+    solana_program::{
+        account_info::AccountInfo,
+        instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        sysvar::instructions::{self, get_instruction_relative},
+    },
+    spl_pod::{optional_keys::OptionalNonZeroPubkey, primitives::PodBool},
 };
 
 /// CPI Guard extension instructions
@@ -41,3 +49,85 @@ pub fn cpi_guard_enabled(account_state: &StateWithExtensionsMut<Account>) -> boo
 pub fn in_cpi() -> bool {
     get_stack_height() > TRANSACTION_LEVEL_STACK_HEIGHT
 }
+
+/// Maximum number of programs on the CPI Guard allowlist of an account
+pub const MAX_ALLOWED_PROGRAMS: usize = 4;
+
+/// Programs that may still perform guarded operations on an Account through
+/// CPI while CPI Guard is enabled
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct CpiGuardAllowlist {
+    /// Allowed programs, with unused slots set to none
+    pub programs: [OptionalNonZeroPubkey; MAX_ALLOWED_PROGRAMS],
+}
+impl CpiGuardAllowlist {
+    /// Check if the program is on the allowlist
+    pub fn contains(&self, program_id: &Pubkey) -> bool {
+        self.programs
+            .iter()
+            .any(|program| Option::<Pubkey>::from(*program).as_ref() == Some(program_id))
+    }
+
+    /// Check if no program is on the allowlist
+    pub fn is_empty(&self) -> bool {
+        self.programs
+            .iter()
+            .all(|program| Option::<Pubkey>::from(*program).is_none())
+    }
+}
+impl Extension for CpiGuardAllowlist {
+    const TYPE: ExtensionType = ExtensionType::CpiGuardAllowlist;
+}
+
+/// Determine if CPI Guard blocks a guarded operation on this account, which
+/// is the case when the guard is enabled, we are in CPI, and the invoking
+/// program is not on the account's allowlist.
+///
+/// The invoking program is read from the instructions sysvar, which must be
+/// among `account_infos`. Since the sysvar only knows the top-level
+/// instructions, only programs invoking the token program directly from the
+/// transaction can be allowed.
+pub fn cpi_guard_blocks<S: BaseState, BSE: BaseStateWithExtensions<S>>(
+    account_state: &BSE,
+    account_infos: &[AccountInfo],
+) -> Result<bool, ProgramError> {
+    match account_state.get_extension::<CpiGuard>() {
+        Ok(extension) if bool::from(extension.lock_cpi) && in_cpi() => {}
+        _ => return Ok(false),
+    }
+    if let Ok(allowlist) = account_state.get_extension::<CpiGuardAllowlist>() {
+        if get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT + 1 {
+            if let Some(instructions_info) = account_infos
+                .iter()
+                .find(|info| instructions::check_id(info.key))
+            {
+                let invoking_instruction = get_instruction_relative(0, instructions_info)?;
+                if allowlist.contains(&invoking_instruction.program_id) {
+                    return Ok(false);
+                }
+            }
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allowlist_contains() {
+        let allowed = Pubkey::new_unique();
+        let mut allowlist = CpiGuardAllowlist::default();
+        assert!(allowlist.is_empty());
+        assert!(!allowlist.contains(&allowed));
+
+        allowlist.programs[2] = Some(allowed).try_into().unwrap();
+        assert!(!allowlist.is_empty());
+        assert!(allowlist.contains(&allowed));
+        assert!(!allowlist.contains(&Pubkey::new_unique()));
+    }
+}
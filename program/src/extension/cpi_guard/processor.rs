@@ -3,10 +3,14 @@ use {
         check_program_account,
         error::TokenError,
         extension::{
-            cpi_guard::{in_cpi, instruction::CpiGuardInstruction, CpiGuard},
+            cpi_guard::{
+                in_cpi,
+                instruction::{CpiGuardInstruction, SetAllowlistInstructionData},
+                CpiGuard, CpiGuardAllowlist,
+            },
             BaseStateWithExtensionsMut, PodStateWithExtensionsMut,
         },
-        instruction::decode_instruction_type,
+        instruction::{decode_instruction_data, decode_instruction_type},
         pod::PodAccount,
         processor::Processor,
     },
@@ -54,6 +58,42 @@ fn process_toggle_cpi_guard(
     Ok(())
 }
 
+/// Replace the programs on the CPI Guard allowlist, initializing the extension
+/// if not already present.
+fn process_set_allowlist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &SetAllowlistInstructionData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let token_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut account_data = token_account_info.data.borrow_mut();
+    let mut account = PodStateWithExtensionsMut::<PodAccount>::unpack(&mut account_data)?;
+
+    Processor::validate_owner(
+        program_id,
+        &account.base.owner,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    if in_cpi() {
+        return Err(TokenError::CpiGuardSettingsLocked.into());
+    }
+
+    let extension = if let Ok(extension) = account.get_extension_mut::<CpiGuardAllowlist>() {
+        extension
+    } else {
+        account.init_extension::<CpiGuardAllowlist>(true)?
+    };
+    extension.programs = data.programs;
+    Ok(())
+}
+
 pub(crate) fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -70,5 +110,10 @@ pub(crate) fn process_instruction(
             msg!("CpiGuardInstruction::Disable");
             process_toggle_cpi_guard(program_id, accounts, false /* disable */)
         }
+        CpiGuardInstruction::SetAllowlist => {
+            msg!("CpiGuardInstruction::SetAllowlist");
+            let data = decode_instruction_data::<SetAllowlistInstructionData>(input)?;
+            process_set_allowlist(program_id, accounts, data)
+        }
     }
 }
@@ -0,0 +1,92 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        check_program_account,
+        instruction::{encode_instruction, TokenInstruction},
+    },
+    bytemuck::{Pod, Zeroable},
+    ethnum::U256,
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    spl_pod::primitives::{PodI64, PodU256},
+};
+
+/// Delegate expiry extension instructions
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum DelegateExpiryInstruction {
+    /// Approves a delegate like `Approve`, but only until the given time.
+    /// From then on, transfers and burns treat the delegation as revoked.
+    ///
+    /// A later `Approve`, `ApproveChecked` or `Revoke` clears the expiry.
+    ///
+    /// Implicitly initializes the extension in the case where it is not
+    /// present, so the account must have room for it, see `Reallocate`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The delegate.
+    ///   2. `[signer]` The source account owner.
+    ///
+    ///   * Multisignature owner
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The delegate.
+    ///   2. `[]` The source account's multisignature owner.
+    ///   3. `..3+M` `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::delegate_expiry::instruction::ApproveInstructionData`
+    Approve,
+}
+
+/// Data expected by `DelegateExpiryInstruction::Approve`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct ApproveInstructionData {
+    /// The amount of tokens the delegate is approved for
+    pub amount: PodU256,
+    /// Time at which the delegation expires, must be in the future
+    pub expires_at: PodI64,
+}
+
+/// Create an `Approve` instruction
+pub fn approve(
+    token_program_id: &Pubkey,
+    source: &Pubkey,
+    delegate: &Pubkey,
+    owner: &Pubkey,
+    signers: &[&Pubkey],
+    amount: U256,
+    expires_at: i64,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*source, false),
+        AccountMeta::new_readonly(*delegate, false),
+        AccountMeta::new_readonly(*owner, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::DelegateExpiryExtension,
+        DelegateExpiryInstruction::Approve,
+        &ApproveInstructionData {
+            amount: amount.into(),
+            expires_at: expires_at.into(),
+        },
+    ))
+}
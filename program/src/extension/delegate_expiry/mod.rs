@@ -0,0 +1,68 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::extension::{BaseState, BaseStateWithExtensions, Extension, ExtensionType},
+    bytemuck::{Pod, Zeroable},
+    solana_program::{clock::Clock, program_error::ProgramError, sysvar::Sysvar},
+    spl_pod::primitives::PodI64,
+};
+
+/// Delegate expiry extension instructions
+pub mod instruction;
+
+/// Delegate expiry extension processor
+pub mod processor;
+
+/// `UnixTimestamp` expressed with an alignment-independent type
+pub type UnixTimestamp = PodI64;
+
+/// Time after which the account's delegate is treated as revoked
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct DelegateExpiry {
+    /// Time at which the delegation expires, or 0 if it never does
+    pub expires_at: UnixTimestamp,
+}
+impl DelegateExpiry {
+    /// Check if the delegation has expired at the given time
+    pub fn is_expired(&self, unix_timestamp: i64) -> bool {
+        let expires_at = i64::from(self.expires_at);
+        expires_at != 0 && unix_timestamp >= expires_at
+    }
+}
+impl Extension for DelegateExpiry {
+    const TYPE: ExtensionType = ExtensionType::DelegateExpiry;
+}
+
+/// Check if the delegation of the account has expired, only reading the clock
+/// if an expiry is set
+pub fn delegate_expired<S: BaseState, BSE: BaseStateWithExtensions<S>>(
+    state: &BSE,
+) -> Result<bool, ProgramError> {
+    match state.get_extension::<DelegateExpiry>() {
+        Ok(extension) if i64::from(extension.expires_at) != 0 => {
+            Ok(extension.is_expired(Clock::get()?.unix_timestamp))
+        }
+        _ => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expiry() {
+        let extension = DelegateExpiry::default();
+        assert!(!extension.is_expired(i64::MAX));
+
+        let extension = DelegateExpiry {
+            expires_at: 100.into(),
+        };
+        assert!(!extension.is_expired(99));
+        assert!(extension.is_expired(100));
+        assert!(extension.is_expired(101));
+    }
+}
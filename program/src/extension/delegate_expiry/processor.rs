@@ -0,0 +1,74 @@
+use {
+    crate::{
+        check_program_account,
+        error::TokenError,
+        extension::{
+            delegate_expiry::{
+                instruction::{ApproveInstructionData, DelegateExpiryInstruction},
+                DelegateExpiry,
+            },
+            BaseStateWithExtensionsMut, PodStateWithExtensionsMut,
+        },
+        instruction::{decode_instruction_data, decode_instruction_type},
+        pod::PodAccount,
+        processor::Processor,
+    },
+    ethnum::U256,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        clock::Clock,
+        entrypoint::ProgramResult,
+        msg,
+        pubkey::Pubkey,
+        sysvar::Sysvar,
+    },
+};
+
+/// Approve a delegate until the given time, initializing the extension if not
+/// already present.
+fn process_approve(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: U256,
+    expires_at: i64,
+) -> ProgramResult {
+    if expires_at <= Clock::get()?.unix_timestamp {
+        return Err(TokenError::InvalidDelegateExpiry.into());
+    }
+
+    // all the checks of a plain approval apply, which also clears any
+    // previous expiry
+    Processor::process_approve(program_id, accounts, amount, None)?;
+
+    let token_account_info = next_account_info(&mut accounts.iter())?;
+    let mut account_data = token_account_info.data.borrow_mut();
+    let mut account = PodStateWithExtensionsMut::<PodAccount>::unpack(&mut account_data)?;
+    let extension = if let Ok(extension) = account.get_extension_mut::<DelegateExpiry>() {
+        extension
+    } else {
+        account.init_extension::<DelegateExpiry>(true)?
+    };
+    extension.expires_at = expires_at.into();
+    Ok(())
+}
+
+pub(crate) fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    check_program_account(program_id)?;
+
+    match decode_instruction_type(input)? {
+        DelegateExpiryInstruction::Approve => {
+            msg!("DelegateExpiryInstruction::Approve");
+            let ApproveInstructionData { amount, expires_at } = decode_instruction_data(input)?;
+            process_approve(
+                program_id,
+                accounts,
+                U256::from(*amount),
+                i64::from(*expires_at),
+            )
+        }
+    }
+}
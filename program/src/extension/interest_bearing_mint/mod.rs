@@ -1,10 +1,10 @@
-use ethnum::{AsU256, U256};
+use ethnum::U256;
 #[cfg(feature = "serde-traits")]
 use serde::{Deserialize, Serialize};
 use {
     crate::{
         extension::{Extension, ExtensionType},
-        trim_ui_amount_string,
+        ui_amount::UiAmountScale,
     },
     bytemuck::{Pod, Zeroable},
     solana_program::program_error::ProgramError,
@@ -23,8 +23,8 @@ pub mod processor;
 
 /// Annual interest rate, expressed as basis points
 pub type BasisPoints = PodI16;
-const ONE_IN_BASIS_POINTS: f64 = 10_000.;
-const SECONDS_PER_YEAR: f64 = 60. * 60. * 24. * 365.24;
+const ONE_IN_BASIS_POINTS: i128 = 10_000;
+const SECONDS_PER_YEAR: i128 = 60 * 60 * 24 * 36_524 / 100;
 
 /// `UnixTimestamp` expressed with an alignment-independent type
 pub type UnixTimestamp = PodI64;
@@ -58,30 +58,20 @@ impl InterestBearingConfig {
         i64::from(self.last_update_timestamp).checked_sub(self.initialization_timestamp.into())
     }
 
-    fn pre_update_exp(&self) -> Option<f64> {
-        let numerator = (i16::from(self.pre_update_average_rate) as i128)
-            .checked_mul(self.pre_update_timespan()? as i128)? as f64;
-        let exponent = numerator / SECONDS_PER_YEAR / ONE_IN_BASIS_POINTS;
-        Some(exponent.exp())
-    }
-
     fn post_update_timespan(&self, unix_timestamp: i64) -> Option<i64> {
         unix_timestamp.checked_sub(self.last_update_timestamp.into())
     }
 
-    fn post_update_exp(&self, unix_timestamp: i64) -> Option<f64> {
-        let numerator = (i16::from(self.current_rate) as i128)
-            .checked_mul(self.post_update_timespan(unix_timestamp)? as i128)?
-            as f64;
-        let exponent = numerator / SECONDS_PER_YEAR / ONE_IN_BASIS_POINTS;
-        Some(exponent.exp())
-    }
-
-    fn total_scale(&self, decimals: u8, unix_timestamp: i64) -> Option<f64> {
-        Some(
-            self.pre_update_exp()? * self.post_update_exp(unix_timestamp)?
-                / 10_f64.powi(decimals as i32),
-        )
+    /// Total interest accrued, as `e^(numerator / denominator)`, where the
+    /// numerator is `r_1 * t_1 + r_2 * t_2` in basis-point-seconds
+    fn total_scale(&self, unix_timestamp: i64) -> Option<UiAmountScale> {
+        let pre_update_numerator = (i16::from(self.pre_update_average_rate) as i128)
+            .checked_mul(self.pre_update_timespan()? as i128)?;
+        let post_update_numerator = (i16::from(self.current_rate) as i128)
+            .checked_mul(self.post_update_timespan(unix_timestamp)? as i128)?;
+        let numerator = pre_update_numerator.checked_add(post_update_numerator)?;
+        let denominator = SECONDS_PER_YEAR.checked_mul(ONE_IN_BASIS_POINTS)? as u128;
+        UiAmountScale::exp(numerator, denominator)
     }
 
     /// Convert a raw amount to its UI representation using the given decimals
@@ -92,10 +82,8 @@ impl InterestBearingConfig {
         decimals: u8,
         unix_timestamp: i64,
     ) -> Option<String> {
-        let scaled_amount_with_interest =
-            amount.as_f64() * self.total_scale(decimals, unix_timestamp)?;
-        let ui_amount = format!("{scaled_amount_with_interest:.*}", decimals as usize);
-        Some(trim_ui_amount_string(ui_amount, decimals))
+        let scale = self.total_scale(unix_timestamp)?;
+        Some(scale.amount_to_ui_amount(amount, decimals))
     }
 
     /// Try to convert a UI representation of a token amount to its raw amount
@@ -106,20 +94,9 @@ impl InterestBearingConfig {
         decimals: u8,
         unix_timestamp: i64,
     ) -> Result<U256, ProgramError> {
-        let scaled_amount = ui_amount
-            .parse::<f64>()
-            .map_err(|_| ProgramError::InvalidArgument)?;
-        let amount = scaled_amount
-            / self
-                .total_scale(decimals, unix_timestamp)
-                .ok_or(ProgramError::InvalidArgument)?;
-        if amount > (U256::MAX.as_f64()) || amount < (U256::MIN.as_f64()) || amount.is_nan() {
-            Err(ProgramError::InvalidArgument)
-        } else {
-            // this is important, if you round earlier, you'll get wrong "inf"
-            // answers
-            Ok(amount.round().as_u256())
-        }
+        self.total_scale(unix_timestamp)
+            .ok_or(ProgramError::InvalidArgument)?
+            .try_ui_amount_into_amount(ui_amount, decimals)
     }
 
     /// The new average rate is the time-weighted average of the current rate
@@ -166,7 +143,7 @@ mod tests {
 
     #[test]
     fn seconds_per_year() {
-        assert_eq!(SECONDS_PER_YEAR, 31_556_736.);
+        assert_eq!(SECONDS_PER_YEAR, 31_556_736);
         assert_eq!(INT_SECONDS_PER_YEAR, 31_556_736);
     }
 
@@ -181,21 +158,21 @@ mod tests {
             last_update_timestamp: INT_SECONDS_PER_YEAR.into(),
             current_rate: 500.into(),
         };
-        // 1 year at 5% gives a total of exp(0.05) = 1.0512710963760241
+        // 1 year at 5% gives a total of exp(0.05) = 1.05127109637602403969...
         let ui_amount = config
             .amount_to_ui_amount(ONE, 18, INT_SECONDS_PER_YEAR)
             .unwrap();
-        assert_eq!(ui_amount, "1.051271096376024117");
+        assert_eq!(ui_amount, "1.05127109637602404");
         // with 1 decimal place
         let ui_amount = config
             .amount_to_ui_amount(ONE, 19, INT_SECONDS_PER_YEAR)
             .unwrap();
-        assert_eq!(ui_amount, "0.1051271096376024117");
+        assert_eq!(ui_amount, "0.105127109637602404");
         // with 10 decimal places
         let ui_amount = config
             .amount_to_ui_amount(ONE, 28, INT_SECONDS_PER_YEAR)
             .unwrap();
-        assert_eq!(ui_amount, "0.0000000001051271096376024175"); // different digits at the end!
+        assert_eq!(ui_amount, "0.000000000105127109637602404"); // same digits at the end
 
         // huge amount with 10 decimal places
         let ui_amount = config
@@ -211,11 +188,12 @@ mod tests {
             last_update_timestamp: INT_SECONDS_PER_YEAR.into(),
             current_rate: PodI16::from(-500),
         };
-        // 1 year at -5% gives a total of exp(-0.05) = 0.951229424500714
+        // 1 year at -5% gives a total of exp(-0.05) = 0.95122942450071400909...
+        // shrinking amounts get one more digit to stay unambiguous
         let ui_amount = config
             .amount_to_ui_amount(ONE, 18, INT_SECONDS_PER_YEAR)
             .unwrap();
-        assert_eq!(ui_amount, "0.951229424500713905");
+        assert_eq!(ui_amount, "0.9512294245007140091");
 
         // net out
         let config = InterestBearingConfig {
@@ -242,12 +220,38 @@ mod tests {
         let ui_amount = config
             .amount_to_ui_amount(U256::MAX, 0, INT_SECONDS_PER_YEAR * 2)
             .unwrap();
-        assert_eq!(ui_amount, "20386805083448098816");
+        assert_eq!(
+            ui_amount,
+            "127970049568302056075323631740315284726156326119361682119823414685536460816751"
+        );
         let ui_amount = config
             .amount_to_ui_amount(U256::MAX, 0, INT_SECONDS_PER_YEAR * 10_000)
             .unwrap();
-        // there's an underflow risk, but it works!
-        assert_eq!(ui_amount, "258917064265813826192025834755112557504850551118283225815045099303279643822914042296793377611277551888244755303462190670431480816358154467489350925148558569427069926786360814068189956495940285398273555561779717914539956777398245259214848");
+        assert_eq!(ui_amount, "1625248753424183034338764247567441521888867897817156302532691469934978078452600549755912674467259861637244389405404071075756233238025705144467764646819290258983998705935712020559294709656645912228892471712899289727106143413614140720398280061814396397120693933792569518502316546798157800259079827");
+
+        // exponents that are too large can't be computed
+        let config = InterestBearingConfig {
+            rate_authority: OptionalNonZeroPubkey::default(),
+            initialization_timestamp: 0.into(),
+            pre_update_average_rate: PodI16::from(i16::MAX),
+            last_update_timestamp: 0.into(),
+            current_rate: PodI16::from(i16::MAX),
+        };
+        assert_eq!(config.amount_to_ui_amount(U256::ONE, 0, i64::MAX), None);
+        // while tiny scales go down to zero
+        let config = InterestBearingConfig {
+            rate_authority: OptionalNonZeroPubkey::default(),
+            initialization_timestamp: 0.into(),
+            pre_update_average_rate: PodI16::from(i16::MIN),
+            last_update_timestamp: 0.into(),
+            current_rate: PodI16::from(i16::MIN),
+        };
+        let ui_amount = config.amount_to_ui_amount(U256::MAX, 0, i64::MAX).unwrap();
+        assert_eq!(ui_amount, "0");
+        assert_eq!(
+            Err(ProgramError::InvalidArgument),
+            config.try_ui_amount_into_amount("1", 0, i64::MAX)
+        );
     }
 
     #[test]
@@ -260,7 +264,7 @@ mod tests {
             last_update_timestamp: INT_SECONDS_PER_YEAR.into(),
             current_rate: 500.into(),
         };
-        // 1 year at 5% gives a total of exp(0.05) = 1.0512710963760241
+        // 1 year at 5% gives a total of exp(0.05) = 1.05127109637602403969...
         let amount = config
             .try_ui_amount_into_amount("1.0512710963760241", 0, INT_SECONDS_PER_YEAR)
             .unwrap();
@@ -290,7 +294,7 @@ mod tests {
             last_update_timestamp: INT_SECONDS_PER_YEAR.into(),
             current_rate: PodI16::from(-500),
         };
-        // 1 year at -5% gives a total of exp(-0.05) = 0.951229424500714
+        // 1 year at -5% gives a total of exp(-0.05) = 0.95122942450071400909...
         let amount = config
             .try_ui_amount_into_amount("0.951229424500714", 0, INT_SECONDS_PER_YEAR)
             .unwrap();
@@ -319,28 +323,44 @@ mod tests {
             current_rate: PodI16::from(500),
         };
         let amount = config
-            .try_ui_amount_into_amount("20386805083448100000", 0, INT_SECONDS_PER_YEAR * 2)
+            .try_ui_amount_into_amount(
+                "127970049568302056075323631740315284726156326119361682119823414685536460816751",
+                0,
+                INT_SECONDS_PER_YEAR * 2,
+            )
             .unwrap();
         assert_eq!(amount, U256::MAX);
         let amount = config
-            .try_ui_amount_into_amount("258917064265813830000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000", 0, INT_SECONDS_PER_YEAR * 10_000)
+            .try_ui_amount_into_amount("1625248753424183034338764247567441521888867897817156302532691469934978078452600549755912674467259861637244389405404071075756233238025705144467764646819290258983998705935712020559294709656645912228892471712899289727106143413614140720398280061814396397120693933792569518502316546798157800259079827", 0, INT_SECONDS_PER_YEAR * 10_000)
             .unwrap();
         assert_eq!(amount, U256::MAX);
         // scientific notation "e"
         let amount = config
-            .try_ui_amount_into_amount("2.5891706426581383e236", 0, INT_SECONDS_PER_YEAR * 10_000)
+            .try_ui_amount_into_amount(
+                "1.27970049568302056075323631740315284726156326119361682119823414685536460816751e77",
+                0,
+                INT_SECONDS_PER_YEAR * 2,
+            )
             .unwrap();
         assert_eq!(amount, U256::MAX);
         // scientific notation "E"
         let amount = config
-            .try_ui_amount_into_amount("2.5891706426581383E236", 0, INT_SECONDS_PER_YEAR * 10_000)
+            .try_ui_amount_into_amount(
+                "1.27970049568302056075323631740315284726156326119361682119823414685536460816751E77",
+                0,
+                INT_SECONDS_PER_YEAR * 2,
+            )
             .unwrap();
         assert_eq!(amount, U256::MAX);
 
-        // overflow u64 fail
+        // overflow U256 fail
         assert_eq!(
             Err(ProgramError::InvalidArgument),
-            config.try_ui_amount_into_amount("20386805083448200001", 0, INT_SECONDS_PER_YEAR)
+            config.try_ui_amount_into_amount(
+                "127970049568302056075323631740315284726156326119361682119823414685536460816760",
+                0,
+                INT_SECONDS_PER_YEAR * 2
+            )
         );
 
         for fail_ui_amount in ["-0.0000000000000000000001", "inf", "-inf", "NaN"] {
@@ -452,10 +472,14 @@ mod tests {
         fn amount_to_ui_amount(
             current_rate in i16::MIN..i16::MAX,
             pre_update_average_rate in i16::MIN..i16::MAX,
-            (initialization_timestamp, last_update_timestamp, current_timestamp) in low_middle_high(),
-            amount in 0..=u64::MAX, // TODO: change to U256::MAX
+            initialization_timestamp in 0..i64::MAX / 2,
+            pre_update_timespan in 0..=INT_SECONDS_PER_YEAR * 100,
+            post_update_timespan in 0..=INT_SECONDS_PER_YEAR * 100,
+            (high, low) in (any::<u128>(), any::<u128>()),
             decimals in 0u8..20u8,
         ) {
+            let last_update_timestamp = initialization_timestamp + pre_update_timespan;
+            let current_timestamp = last_update_timestamp + post_update_timespan;
             let config = InterestBearingConfig {
                 rate_authority: OptionalNonZeroPubkey::default(),
                 initialization_timestamp: initialization_timestamp.into(),
@@ -463,8 +487,13 @@ mod tests {
                 last_update_timestamp: last_update_timestamp.into(),
                 current_rate: current_rate.into(),
             };
-            let ui_amount = config.amount_to_ui_amount(U256::from(amount), decimals, current_timestamp);
+            let amount = U256::from_words(high, low);
+            let ui_amount = config.amount_to_ui_amount(amount, decimals, current_timestamp);
             assert!(ui_amount.is_some());
+            let round_trip = config
+                .try_ui_amount_into_amount(&ui_amount.unwrap(), decimals, current_timestamp)
+                .unwrap();
+            assert_eq!(round_trip, amount);
         }
     }
 }
@@ -2,6 +2,7 @@ use {
     crate::{
         check_program_account,
         error::TokenError,
+        event::{self, UpdateRateEvent},
         extension::{
             interest_bearing_mint::{
                 instruction::{InitializeInstructionData, InterestBearingMintInstruction},
@@ -80,6 +81,13 @@ fn process_update_rate(
     // There is no validation on the rate, since ridiculous values are *technically*
     // possible!
     extension.current_rate = *new_rate;
+
+    event::emit(&UpdateRateEvent {
+        mint: *mint_account_info.key,
+        current_rate: extension.current_rate,
+        pre_update_average_rate: extension.pre_update_average_rate,
+        timestamp: extension.last_update_timestamp,
+    });
     Ok(())
 }
 
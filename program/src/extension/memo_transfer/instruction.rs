@@ -3,14 +3,17 @@ use serde::{Deserialize, Serialize};
 use {
     crate::{
         check_program_account,
+        extension::memo_transfer::MemoRules,
         instruction::{encode_instruction, TokenInstruction},
     },
+    bytemuck::{Pod, Zeroable},
     num_enum::{IntoPrimitive, TryFromPrimitive},
     solana_program::{
         instruction::{AccountMeta, Instruction},
         program_error::ProgramError,
         pubkey::Pubkey,
     },
+    spl_pod::primitives::PodBool,
 };
 
 /// Required Memo Transfers extension instructions
@@ -47,6 +50,44 @@ pub enum RequiredMemoTransfersInstruction {
     ///   1. `[]`  The account's multisignature owner.
     ///   2. `..2+M` `[signer]` M signer accounts.
     Disable,
+    /// Configure in which directions transfers need a memo, and the rules
+    /// these memos must follow: a minimum and maximum length, and a prefix
+    /// such as a deposit tag. Memos are checked for transfers into the Account
+    /// if incoming memos are required, and for transfers out of the Account if
+    /// outgoing memos are required.
+    ///
+    /// Implicitly initializes the `MemoTransfer` extension in the case where
+    /// it is not present. The outgoing requirement and the rules are stored in
+    /// the `MemoTransferRules` extension, which is implicitly initialized if
+    /// outgoing memos or non-default rules are required.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The account to update.
+    ///   1. `[signer]` The account's owner.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The account to update.
+    ///   1. `[]`  The account's multisignature owner.
+    ///   2. `..2+M` `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::memo_transfer::instruction::ConfigureInstructionData`
+    Configure,
+}
+
+/// Data expected by `RequiredMemoTransfersInstruction::Configure`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct ConfigureInstructionData {
+    /// Require transfers into the account to be accompanied by a memo
+    pub require_incoming_transfer_memos: PodBool,
+    /// Require transfers out of the account to be accompanied by a memo
+    pub require_outgoing_transfer_memos: PodBool,
+    /// Rules that the required memos must follow
+    pub rules: MemoRules,
 }
 
 /// Create an `Enable` instruction
@@ -96,3 +137,34 @@ pub fn disable_required_transfer_memos(
         &(),
     ))
 }
+
+/// Create a `Configure` instruction
+pub fn configure_transfer_memos(
+    token_program_id: &Pubkey,
+    account: &Pubkey,
+    owner: &Pubkey,
+    signers: &[&Pubkey],
+    require_incoming_transfer_memos: bool,
+    require_outgoing_transfer_memos: bool,
+    rules: MemoRules,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(*owner, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::MemoTransferExtension,
+        RequiredMemoTransfersInstruction::Configure,
+        &ConfigureInstructionData {
+            require_incoming_transfer_memos: require_incoming_transfer_memos.into(),
+            require_outgoing_transfer_memos: require_outgoing_transfer_memos.into(),
+            rules,
+        },
+    ))
+}
@@ -7,9 +7,10 @@ use {
     },
     bytemuck::{Pod, Zeroable},
     solana_program::{
-        instruction::get_processed_sibling_instruction, program_error::ProgramError, pubkey::Pubkey,
+        entrypoint::ProgramResult, instruction::get_processed_sibling_instruction, msg,
+        program_error::ProgramError, pubkey::Pubkey,
     },
-    spl_pod::primitives::PodBool,
+    spl_pod::primitives::{PodBool, PodU16},
 };
 
 /// Memo Transfer extension instructions
@@ -18,6 +19,9 @@ pub mod instruction;
 /// Memo Transfer extension processor
 pub mod processor;
 
+/// Maximum length of the prefix required in memos
+pub const MAX_MEMO_PREFIX_LEN: usize = 32;
+
 /// Memo Transfer extension for Accounts
 #[repr(C)]
 #[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
@@ -31,6 +35,90 @@ impl Extension for MemoTransfer {
     const TYPE: ExtensionType = ExtensionType::MemoTransfer;
 }
 
+/// Memo Transfer Rules extension for Accounts, holding the outgoing memo
+/// requirement and the rules that required memos must follow
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct MemoTransferRules {
+    /// Require transfers out of this account to be accompanied by a memo
+    pub require_outgoing_transfer_memos: PodBool,
+    /// Rules that the required memos must follow
+    pub rules: MemoRules,
+}
+impl Extension for MemoTransferRules {
+    const TYPE: ExtensionType = ExtensionType::MemoTransferRules;
+}
+
+/// Content rules for the memos required by an account
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct MemoRules {
+    /// Minimum length of the memo, in bytes
+    pub min_len: PodU16,
+    /// Maximum length of the memo in bytes, or 0 for no maximum
+    pub max_len: PodU16,
+    /// Number of used bytes in `prefix`
+    pub prefix_len: u8,
+    /// Bytes the memo must start with, such as a deposit tag
+    pub prefix: [u8; MAX_MEMO_PREFIX_LEN],
+}
+impl MemoRules {
+    /// Create new rules, failing if they can't be satisfied by any memo
+    pub fn new(min_len: u16, max_len: Option<u16>, prefix: &[u8]) -> Result<Self, ProgramError> {
+        let prefix_len = u8::try_from(prefix.len()).map_err(|_| TokenError::InvalidMemoRules)?;
+        let mut rules = Self {
+            min_len: min_len.into(),
+            max_len: max_len.unwrap_or_default().into(),
+            prefix_len,
+            prefix: [0; MAX_MEMO_PREFIX_LEN],
+        };
+        rules.validate()?;
+        rules.prefix[..prefix.len()].copy_from_slice(prefix);
+        Ok(rules)
+    }
+
+    /// Check that the rules can be satisfied by some memo
+    pub fn validate(&self) -> ProgramResult {
+        if usize::from(self.prefix_len) > MAX_MEMO_PREFIX_LEN {
+            return Err(TokenError::InvalidMemoRules.into());
+        }
+        let max_len = u16::from(self.max_len);
+        if max_len != 0
+            && (u16::from(self.min_len) > max_len || u16::from(self.prefix_len) > max_len)
+        {
+            return Err(TokenError::InvalidMemoRules.into());
+        }
+        Ok(())
+    }
+
+    /// The prefix that memos must start with, possibly empty
+    pub fn prefix(&self) -> &[u8] {
+        &self.prefix[..usize::from(self.prefix_len).min(MAX_MEMO_PREFIX_LEN)]
+    }
+
+    /// Check the memo against the rules
+    pub fn check(&self, memo: &[u8]) -> ProgramResult {
+        let max_len = u16::from(self.max_len);
+        if memo.len() < usize::from(u16::from(self.min_len)) {
+            msg!("Memo is shorter than {} bytes", u16::from(self.min_len));
+            return Err(TokenError::MemoRulesViolated.into());
+        }
+        if max_len != 0 && memo.len() > usize::from(max_len) {
+            msg!("Memo is longer than {} bytes", max_len);
+            return Err(TokenError::MemoRulesViolated.into());
+        }
+        if !memo.starts_with(self.prefix()) {
+            msg!("Memo doesn't start with the required prefix");
+            return Err(TokenError::MemoRulesViolated.into());
+        }
+        Ok(())
+    }
+}
+
 /// Determine if a memo is required for transfers into this account
 pub fn memo_required<BSE: BaseStateWithExtensions<S>, S: BaseState>(account_state: &BSE) -> bool {
     if let Ok(extension) = account_state.get_extension::<MemoTransfer>() {
@@ -39,17 +127,108 @@ pub fn memo_required<BSE: BaseStateWithExtensions<S>, S: BaseState>(account_stat
     false
 }
 
-/// Check if the previous sibling instruction is a memo
-pub fn check_previous_sibling_instruction_is_memo() -> Result<(), ProgramError> {
+/// Determine if a memo is required for transfers out of this account
+pub fn outgoing_memo_required<BSE: BaseStateWithExtensions<S>, S: BaseState>(
+    account_state: &BSE,
+) -> bool {
+    if let Ok(extension) = account_state.get_extension::<MemoTransferRules>() {
+        return extension.require_outgoing_transfer_memos.into();
+    }
+    false
+}
+
+/// Get the data of the previous sibling instruction, failing if it is not a
+/// memo
+fn get_previous_sibling_memo() -> Result<Vec<u8>, ProgramError> {
     let is_memo_program = |program_id: &Pubkey| -> bool {
         program_id == &spl_memo::id() || program_id == &spl_memo::v1::id()
     };
     let previous_instruction = get_processed_sibling_instruction(0);
     match previous_instruction {
-        Some(instruction) if is_memo_program(&instruction.program_id) => {}
-        _ => {
-            return Err(TokenError::NoMemo.into());
+        Some(instruction) if is_memo_program(&instruction.program_id) => Ok(instruction.data),
+        _ => Err(TokenError::NoMemo.into()),
+    }
+}
+
+/// Check if the previous sibling instruction is a memo
+pub fn check_previous_sibling_instruction_is_memo() -> Result<(), ProgramError> {
+    get_previous_sibling_memo()?;
+    Ok(())
+}
+
+/// Get the rules that memos required by this account must follow, which
+/// accept any memo if the account has no rules
+pub fn get_memo_rules<BSE: BaseStateWithExtensions<S>, S: BaseState>(
+    account_state: &BSE,
+) -> MemoRules {
+    account_state
+        .get_extension::<MemoTransferRules>()
+        .map(|extension| extension.rules)
+        .unwrap_or_default()
+}
+
+/// Check that a transfer into this account is accompanied by a memo following
+/// the account's rules, if the account requires one
+pub fn check_incoming_transfer_memo<BSE: BaseStateWithExtensions<S>, S: BaseState>(
+    account_state: &BSE,
+) -> ProgramResult {
+    if memo_required(account_state) {
+        get_memo_rules(account_state).check(&get_previous_sibling_memo()?)?;
+    }
+    Ok(())
+}
+
+/// Check that a transfer out of this account is accompanied by a memo
+/// following the account's rules, if the account requires one
+pub fn check_outgoing_transfer_memo<BSE: BaseStateWithExtensions<S>, S: BaseState>(
+    account_state: &BSE,
+) -> ProgramResult {
+    if let Ok(extension) = account_state.get_extension::<MemoTransferRules>() {
+        if bool::from(extension.require_outgoing_transfer_memos) {
+            extension.rules.check(&get_previous_sibling_memo()?)?;
         }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memo_rules() {
+        let rules = MemoRules::default();
+        assert_eq!(rules.check(b""), Ok(()));
+        assert_eq!(rules.check(b"anything"), Ok(()));
+
+        let rules = MemoRules::new(6, Some(10), b"dep-").unwrap();
+        assert_eq!(rules.prefix(), b"dep-");
+        assert_eq!(rules.check(b"dep-42"), Ok(()));
+        assert_eq!(rules.check(b"dep-424242"), Ok(()));
+        assert_eq!(
+            rules.check(b"dep-4"),
+            Err(TokenError::MemoRulesViolated.into())
+        );
+        assert_eq!(
+            rules.check(b"dep-4242424"),
+            Err(TokenError::MemoRulesViolated.into())
+        );
+        assert_eq!(
+            rules.check(b"tag-42"),
+            Err(TokenError::MemoRulesViolated.into())
+        );
+
+        assert_eq!(
+            MemoRules::new(11, Some(10), b""),
+            Err(TokenError::InvalidMemoRules.into())
+        );
+        assert_eq!(
+            MemoRules::new(0, Some(2), b"dep-"),
+            Err(TokenError::InvalidMemoRules.into())
+        );
+        assert_eq!(
+            MemoRules::new(0, None, &[0; MAX_MEMO_PREFIX_LEN + 1]),
+            Err(TokenError::InvalidMemoRules.into())
+        );
+    }
+}
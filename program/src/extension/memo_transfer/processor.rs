@@ -2,10 +2,13 @@ use {
     crate::{
         check_program_account,
         extension::{
-            memo_transfer::{instruction::RequiredMemoTransfersInstruction, MemoTransfer},
+            memo_transfer::{
+                instruction::{ConfigureInstructionData, RequiredMemoTransfersInstruction},
+                MemoRules, MemoTransfer, MemoTransferRules,
+            },
             BaseStateWithExtensionsMut, PodStateWithExtensionsMut,
         },
-        instruction::decode_instruction_type,
+        instruction::{decode_instruction_data, decode_instruction_type},
         pod::PodAccount,
         processor::Processor,
     },
@@ -49,6 +52,52 @@ fn process_toggle_required_memo_transfers(
     Ok(())
 }
 
+/// Configure the directions and rules of required memos, initializing the
+/// `MemoTransfer` extension if not already present. The `MemoTransferRules`
+/// extension is only initialized if outgoing memos or rules are required.
+fn process_configure(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &ConfigureInstructionData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let token_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut account_data = token_account_info.data.borrow_mut();
+    let mut account = PodStateWithExtensionsMut::<PodAccount>::unpack(&mut account_data)?;
+
+    Processor::validate_owner(
+        program_id,
+        &account.base.owner,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    data.rules.validate()?;
+
+    let extension = if let Ok(extension) = account.get_extension_mut::<MemoTransfer>() {
+        extension
+    } else {
+        account.init_extension::<MemoTransfer>(true)?
+    };
+    extension.require_incoming_transfer_memos = data.require_incoming_transfer_memos;
+
+    let extension = if let Ok(extension) = account.get_extension_mut::<MemoTransferRules>() {
+        extension
+    } else if bool::from(data.require_outgoing_transfer_memos) || data.rules != MemoRules::default()
+    {
+        account.init_extension::<MemoTransferRules>(true)?
+    } else {
+        return Ok(());
+    };
+    extension.require_outgoing_transfer_memos = data.require_outgoing_transfer_memos;
+    extension.rules = data.rules;
+    Ok(())
+}
+
 pub(crate) fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -65,5 +114,10 @@ pub(crate) fn process_instruction(
             msg!("RequiredMemoTransfersInstruction::Disable");
             process_toggle_required_memo_transfers(program_id, accounts, false /* disable */)
         }
+        RequiredMemoTransfersInstruction::Configure => {
+            msg!("RequiredMemoTransfersInstruction::Configure");
+            let data = decode_instruction_data::<ConfigureInstructionData>(input)?;
+            process_configure(program_id, accounts, data)
+        }
     }
 }
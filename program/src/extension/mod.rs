@@ -11,31 +11,45 @@ use {
             confidential_transfer_fee::{
                 ConfidentialTransferFeeAmount, ConfidentialTransferFeeConfig,
             },
-            cpi_guard::CpiGuard,
+            cpi_guard::{CpiGuard, CpiGuardAllowlist},
             default_account_state::DefaultAccountState,
+            delegate_expiry::DelegateExpiry,
             group_member_pointer::GroupMemberPointer,
             group_pointer::GroupPointer,
             immutable_owner::ImmutableOwner,
             interest_bearing_mint::InterestBearingConfig,
-            memo_transfer::MemoTransfer,
+            memo_transfer::{MemoTransfer, MemoTransferRules},
             metadata_pointer::MetadataPointer,
             mint_close_authority::MintCloseAuthority,
-            non_transferable::{NonTransferable, NonTransferableAccount},
-            pausable::{PausableAccount, PausableConfig},
-            permanent_delegate::PermanentDelegate,
+            multi_delegate::MultiDelegate,
+            non_transferable::{NonTransferable, NonTransferableAccount, NonTransferableRecovery},
+            pausable::{PausableAccount, PausableConfig, PausableOperations, PauseSchedule},
+            permanent_delegate::{PermanentDelegate, PermanentDelegateScope},
             scaled_ui_amount::ScaledUiAmountConfig,
-            transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+            supply_cap::SupplyCapConfig,
+            supply_hook::SupplyHook,
+            token_group::TokenGroupMemberCounter,
+            transfer_fee::{
+                TransferFeeAmount, TransferFeeConfig, TransferFeeExemption, TransferFeeRecipient,
+                TransferFeeTiers,
+            },
             transfer_hook::{TransferHook, TransferHookAccount},
+            transfer_limit::{TransferLimit, TransferLimitPolicy},
+            vesting::{VestingAccount, VestingConfig},
         },
-        pod::{PodAccount, PodMint},
+        pod::{PodAccount, PodCOption, PodMint, PodWeightedMultisig},
+        processor::Processor,
         state::{Account, Mint, Multisig, PackedSizeOf},
     },
     bytemuck::{Pod, Zeroable},
     num_enum::{IntoPrimitive, TryFromPrimitive},
     solana_program::{
-        account_info::AccountInfo,
+        account_info::{next_account_info, AccountInfo},
         program_error::ProgramError,
         program_pack::{IsInitialized, Pack},
+        pubkey::Pubkey,
+        rent::Rent,
+        sysvar::Sysvar,
     },
     spl_pod::{
         bytemuck::{pod_from_bytes, pod_from_bytes_mut, pod_get_packed_len},
@@ -58,6 +72,8 @@ pub mod confidential_transfer_fee;
 pub mod cpi_guard;
 /// Default Account State extension
 pub mod default_account_state;
+/// Delegate Expiry extension
+pub mod delegate_expiry;
 /// Group Member Pointer extension
 pub mod group_member_pointer;
 /// Group Pointer extension
@@ -72,6 +88,8 @@ pub mod memo_transfer;
 pub mod metadata_pointer;
 /// Mint Close Authority extension
 pub mod mint_close_authority;
+/// Multiple Delegates extension
+pub mod multi_delegate;
 /// Non Transferable extension
 pub mod non_transferable;
 /// Pausable extension
@@ -82,6 +100,10 @@ pub mod permanent_delegate;
 pub mod reallocate;
 /// Scaled UI Amount extension
 pub mod scaled_ui_amount;
+/// Supply Cap extension
+pub mod supply_cap;
+/// Supply Hook extension
+pub mod supply_hook;
 /// Token-group extension
 pub mod token_group;
 /// Token-metadata extension
@@ -90,6 +112,10 @@ pub mod token_metadata;
 pub mod transfer_fee;
 /// Transfer Hook extension
 pub mod transfer_hook;
+/// Transfer Limit extension
+pub mod transfer_limit;
+/// Vesting extension
+pub mod vesting;
 
 /// Confidential mint-burn extension
 pub mod confidential_mint_burn;
@@ -124,9 +150,9 @@ fn get_tlv_indices(type_start: usize) -> TlvIndices {
 }
 
 /// Helper function to tack on the size of an extension bytes if an account with
-/// extensions is exactly the size of a multisig
+/// extensions is exactly the size of a multisig or a weighted multisig
 const fn adjust_len_for_multisig(account_len: usize) -> usize {
-    if account_len == Multisig::LEN {
+    if account_len == Multisig::LEN || account_len == PodWeightedMultisig::SIZE_OF {
         account_len.saturating_add(size_of::<ExtensionType>())
     } else {
         account_len
@@ -299,7 +325,8 @@ fn check_account_type<S: BaseState>(account_type: AccountType) -> Result<(), Pro
 /// With this approach, we only start writing the TLV data after `Account::LEN`,
 /// which means we always know that the account type is going to be right after
 /// that. We do a special case checking for a Multisig length, because those
-/// aren't extensible under any circumstances.
+/// aren't extensible under any circumstances. Weighted multisigs store
+/// `AccountType::WeightedMultisig` at the same position instead.
 const BASE_ACCOUNT_LENGTH: usize = Account::LEN;
 /// Helper that tacks on the `AccountType` length, which gives the minimum for
 /// any account with extensions
@@ -685,6 +712,31 @@ pub trait BaseStateWithExtensionsMut<S: BaseState>: BaseStateWithExtensions<S> {
         Ok(&mut tlv_data[value_start..new_value_end])
     }
 
+    /// Remove the TLV entry for the given extension.
+    ///
+    /// The rest of the buffer is compacted and the freed bytes at the end are
+    /// zeroed out.
+    ///
+    /// Returns an error if the extension is not present.
+    fn remove_extension<V: Extension>(&mut self) -> Result<(), ProgramError> {
+        let tlv_data = self.get_tlv_data_mut();
+        let TlvIndices {
+            type_start,
+            length_start,
+            value_start,
+        } = get_extension_indices::<V>(tlv_data, false)?;
+        let tlv_len = get_tlv_data_info(tlv_data).map(|x| x.used_len)?;
+
+        let length = pod_from_bytes::<Length>(&tlv_data[length_start..value_start])?;
+        let value_end = value_start.saturating_add(usize::from(*length));
+        tlv_data.copy_within(value_end..tlv_len, type_start);
+
+        let new_tlv_len = tlv_len.saturating_sub(value_end.saturating_sub(type_start));
+        tlv_data[new_tlv_len..tlv_len].fill(0);
+
+        Ok(())
+    }
+
     /// Allocate the given number of bytes for the given variable-length
     /// extension and write its contents into the TLV buffer.
     ///
@@ -777,6 +829,9 @@ pub trait BaseStateWithExtensionsMut<S: BaseState>: BaseStateWithExtensions<S> {
             ExtensionType::PausableAccount => {
                 self.init_extension::<PausableAccount>(true).map(|_| ())
             }
+            ExtensionType::VestingAccount => {
+                self.init_extension::<VestingAccount>(true).map(|_| ())
+            }
             #[cfg(test)]
             ExtensionType::AccountPaddingTest => {
                 self.init_extension::<AccountPaddingTest>(true).map(|_| ())
@@ -1034,7 +1089,7 @@ pub fn set_account_type<S: BaseState>(input: &mut [u8]) -> Result<(), ProgramErr
 /// Different kinds of accounts. Note that `Mint`, `Account`, and `Multisig`
 /// types are determined exclusively by the size of the account, and are not
 /// included in the account data. `AccountType` is only included if extensions
-/// have been initialized.
+/// have been initialized, or in weighted multisigs.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive, IntoPrimitive)]
 pub enum AccountType {
@@ -1044,6 +1099,8 @@ pub enum AccountType {
     Mint,
     /// Token holding account with additional extensions
     Account,
+    /// Weighted multisignature account
+    WeightedMultisig,
 }
 impl Default for AccountType {
     fn default() -> Self {
@@ -1124,6 +1181,44 @@ pub enum ExtensionType {
     Pausable,
     /// Indicates that the account belongs to a pausable mint
     PausableAccount,
+    /// Mint has a hard cap on its supply
+    SupplyCap,
+    /// Account limits the amount of tokens leaving it per time window
+    TransferLimit,
+    /// Tokens in accounts of this mint can be locked under a vesting schedule
+    Vesting,
+    /// Indicates that the account belongs to a mint with vesting
+    VestingAccount,
+    /// Account delegation expires at a given time
+    DelegateExpiry,
+    /// Account has a table of delegates with their own allowances
+    MultiDelegate,
+    /// Mint requires a CPI to a program implementing the "transfer hook"
+    /// interface on mints and burns
+    SupplyHook,
+    /// Account allows some programs to bypass CPI Guard
+    CpiGuardAllowlist,
+    /// Non-transferable mint has an authority that can recover balances
+    NonTransferableRecovery,
+    /// Minting, burning and transferring can be paused independently on a
+    /// pausable mint
+    PausableOperations,
+    /// Pausable mint is paused and resumed at scheduled timestamps
+    PauseSchedule,
+    /// Transfer fee charges larger transfers at different rates
+    TransferFeeTiers,
+    /// Account is exempt from transfer fees
+    TransferFeeExemption,
+    /// Withheld transfer fees may be swept to a configured account
+    TransferFeeRecipient,
+    /// Permanent delegate is limited to transferring or burning tokens
+    PermanentDelegateScope,
+    /// Require outbound transfers to have memo, and memos to follow rules
+    MemoTransferRules,
+    /// Mint contains the counter of member numbers handed out by its group
+    TokenGroupMemberCounter,
+    /// Mint contains the authority that can configure transfer limits
+    TransferLimitPolicy,
 
     /// Test variable-length mint extension
     #[cfg(test)]
@@ -1209,6 +1304,28 @@ impl ExtensionType {
             ExtensionType::ScaledUiAmount => pod_get_packed_len::<ScaledUiAmountConfig>(),
             ExtensionType::Pausable => pod_get_packed_len::<PausableConfig>(),
             ExtensionType::PausableAccount => pod_get_packed_len::<PausableAccount>(),
+            ExtensionType::SupplyCap => pod_get_packed_len::<SupplyCapConfig>(),
+            ExtensionType::TransferLimit => pod_get_packed_len::<TransferLimit>(),
+            ExtensionType::Vesting => pod_get_packed_len::<VestingConfig>(),
+            ExtensionType::VestingAccount => pod_get_packed_len::<VestingAccount>(),
+            ExtensionType::DelegateExpiry => pod_get_packed_len::<DelegateExpiry>(),
+            ExtensionType::MultiDelegate => pod_get_packed_len::<MultiDelegate>(),
+            ExtensionType::SupplyHook => pod_get_packed_len::<SupplyHook>(),
+            ExtensionType::CpiGuardAllowlist => pod_get_packed_len::<CpiGuardAllowlist>(),
+            ExtensionType::NonTransferableRecovery => {
+                pod_get_packed_len::<NonTransferableRecovery>()
+            }
+            ExtensionType::PausableOperations => pod_get_packed_len::<PausableOperations>(),
+            ExtensionType::PauseSchedule => pod_get_packed_len::<PauseSchedule>(),
+            ExtensionType::TransferFeeTiers => pod_get_packed_len::<TransferFeeTiers>(),
+            ExtensionType::TransferFeeExemption => pod_get_packed_len::<TransferFeeExemption>(),
+            ExtensionType::TransferFeeRecipient => pod_get_packed_len::<TransferFeeRecipient>(),
+            ExtensionType::PermanentDelegateScope => pod_get_packed_len::<PermanentDelegateScope>(),
+            ExtensionType::MemoTransferRules => pod_get_packed_len::<MemoTransferRules>(),
+            ExtensionType::TokenGroupMemberCounter => {
+                pod_get_packed_len::<TokenGroupMemberCounter>()
+            }
+            ExtensionType::TransferLimitPolicy => pod_get_packed_len::<TransferLimitPolicy>(),
             #[cfg(test)]
             ExtensionType::AccountPaddingTest => pod_get_packed_len::<AccountPaddingTest>(),
             #[cfg(test)]
@@ -1275,7 +1392,18 @@ impl ExtensionType {
             | ExtensionType::ConfidentialMintBurn
             | ExtensionType::TokenGroupMember
             | ExtensionType::ScaledUiAmount
-            | ExtensionType::Pausable => AccountType::Mint,
+            | ExtensionType::Pausable
+            | ExtensionType::SupplyCap
+            | ExtensionType::Vesting
+            | ExtensionType::SupplyHook
+            | ExtensionType::NonTransferableRecovery
+            | ExtensionType::PausableOperations
+            | ExtensionType::PauseSchedule
+            | ExtensionType::TransferFeeTiers
+            | ExtensionType::TransferFeeRecipient
+            | ExtensionType::PermanentDelegateScope
+            | ExtensionType::TokenGroupMemberCounter
+            | ExtensionType::TransferLimitPolicy => AccountType::Mint,
             ExtensionType::ImmutableOwner
             | ExtensionType::TransferFeeAmount
             | ExtensionType::ConfidentialTransferAccount
@@ -1284,7 +1412,14 @@ impl ExtensionType {
             | ExtensionType::TransferHookAccount
             | ExtensionType::CpiGuard
             | ExtensionType::ConfidentialTransferFeeAmount
-            | ExtensionType::PausableAccount => AccountType::Account,
+            | ExtensionType::PausableAccount
+            | ExtensionType::TransferLimit
+            | ExtensionType::VestingAccount
+            | ExtensionType::DelegateExpiry
+            | ExtensionType::MultiDelegate
+            | ExtensionType::CpiGuardAllowlist
+            | ExtensionType::TransferFeeExemption
+            | ExtensionType::MemoTransferRules => AccountType::Account,
             #[cfg(test)]
             ExtensionType::VariableLenMintTest => AccountType::Mint,
             #[cfg(test)]
@@ -1313,6 +1448,9 @@ impl ExtensionType {
                 ExtensionType::Pausable => {
                     account_extension_types.push(ExtensionType::PausableAccount);
                 }
+                ExtensionType::Vesting => {
+                    account_extension_types.push(ExtensionType::VestingAccount);
+                }
                 #[cfg(test)]
                 ExtensionType::MintPaddingTest => {
                     account_extension_types.push(ExtensionType::AccountPaddingTest);
@@ -1333,6 +1471,16 @@ impl ExtensionType {
         let mut confidential_mint_burn = false;
         let mut interest_bearing = false;
         let mut scaled_ui_amount = false;
+        let mut supply_cap = false;
+        let mut non_transferable = false;
+        let mut non_transferable_recovery = false;
+        let mut pausable = false;
+        let mut pausable_operations = false;
+        let mut pause_schedule = false;
+        let mut transfer_fee_tiers = false;
+        let mut transfer_fee_recipient = false;
+        let mut permanent_delegate = false;
+        let mut permanent_delegate_scope = false;
 
         for extension_type in mint_extension_types {
             match extension_type {
@@ -1344,6 +1492,16 @@ impl ExtensionType {
                 ExtensionType::ConfidentialMintBurn => confidential_mint_burn = true,
                 ExtensionType::InterestBearingConfig => interest_bearing = true,
                 ExtensionType::ScaledUiAmount => scaled_ui_amount = true,
+                ExtensionType::SupplyCap => supply_cap = true,
+                ExtensionType::NonTransferable => non_transferable = true,
+                ExtensionType::NonTransferableRecovery => non_transferable_recovery = true,
+                ExtensionType::Pausable => pausable = true,
+                ExtensionType::PausableOperations => pausable_operations = true,
+                ExtensionType::PauseSchedule => pause_schedule = true,
+                ExtensionType::TransferFeeTiers => transfer_fee_tiers = true,
+                ExtensionType::TransferFeeRecipient => transfer_fee_recipient = true,
+                ExtensionType::PermanentDelegate => permanent_delegate = true,
+                ExtensionType::PermanentDelegateScope => permanent_delegate_scope = true,
                 _ => (),
             }
         }
@@ -1365,6 +1523,30 @@ impl ExtensionType {
             return Err(TokenError::InvalidExtensionCombination);
         }
 
+        // confidential mints are encrypted, so the cap could not be enforced
+        if supply_cap && confidential_mint_burn {
+            return Err(TokenError::InvalidExtensionCombination);
+        }
+
+        // recovery only makes sense for tokens that can't otherwise move
+        if non_transferable_recovery && !non_transferable {
+            return Err(TokenError::InvalidExtensionCombination);
+        }
+
+        // the operation flags and schedule are controlled by the pause
+        // authority
+        if (pausable_operations || pause_schedule) && !pausable {
+            return Err(TokenError::InvalidExtensionCombination);
+        }
+
+        if (transfer_fee_tiers || transfer_fee_recipient) && !transfer_fee_config {
+            return Err(TokenError::InvalidExtensionCombination);
+        }
+
+        if permanent_delegate_scope && !permanent_delegate {
+            return Err(TokenError::InvalidExtensionCombination);
+        }
+
         Ok(())
     }
 }
@@ -1480,6 +1662,28 @@ pub(crate) fn alloc_and_serialize<S: BaseState + Pod, V: Default + Extension + P
     Ok(())
 }
 
+/// Removes a fixed-length extension from a TLV space
+///
+/// This function removes the TLV entry and then reduces the size of the
+/// account to fit the remaining extensions.
+pub(crate) fn remove_and_dealloc<S: BaseState + Pod, V: Extension>(
+    account_info: &AccountInfo,
+) -> Result<(), ProgramError> {
+    let previous_account_len = account_info.try_data_len()?;
+    let new_account_len = {
+        let mut buffer = account_info.try_borrow_mut_data()?;
+        let mut state = PodStateWithExtensionsMut::<S>::unpack(&mut buffer)?;
+        state.remove_extension::<V>()?;
+        state.try_get_account_len()?
+    };
+
+    if new_account_len < previous_account_len {
+        account_info.realloc(new_account_len, false)?;
+    }
+
+    Ok(())
+}
+
 /// Packs a variable-length extension into a TLV space
 ///
 /// This function reallocates the account as needed to accommodate for the
@@ -1549,6 +1753,83 @@ pub(crate) fn alloc_and_serialize_variable_len_extension<
     Ok(())
 }
 
+/// Transfers the rent freed by shrinking an account to a destination account
+///
+/// Only the difference between the rent-exempt minimums of the previous and
+/// the current account length is refunded, and never more than the lamports
+/// held above the current rent-exempt minimum.
+pub(crate) fn refund_freed_rent(
+    account_info: &AccountInfo,
+    destination_info: &AccountInfo,
+    previous_account_len: usize,
+) -> Result<(), ProgramError> {
+    let new_account_len = account_info.try_data_len()?;
+    if new_account_len >= previous_account_len {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?;
+    let new_rent_exempt_reserve = rent.minimum_balance(new_account_len);
+    let freed_rent = rent
+        .minimum_balance(previous_account_len)
+        .saturating_sub(new_rent_exempt_reserve);
+    let refund_amount = account_info
+        .lamports()
+        .saturating_sub(new_rent_exempt_reserve)
+        .min(freed_rent);
+
+    let account_starting_lamports = account_info.lamports();
+    **account_info.lamports.borrow_mut() = account_starting_lamports
+        .checked_sub(refund_amount)
+        .ok_or(TokenError::Overflow)?;
+
+    let destination_starting_lamports = destination_info.lamports();
+    **destination_info.lamports.borrow_mut() = destination_starting_lamports
+        .checked_add(refund_amount)
+        .ok_or(TokenError::Overflow)?;
+
+    Ok(())
+}
+
+/// Transfers the rent freed by shrinking a mint to a destination approved by
+/// the mint authority
+///
+/// The destination, the mint authority and any multisig signers are read from
+/// the remaining accounts. Without a destination, the freed rent stays on the
+/// mint and can later be recovered with `WithdrawExcessLamports`.
+pub(crate) fn refund_freed_mint_rent(
+    program_id: &Pubkey,
+    mint_info: &AccountInfo,
+    remaining_account_infos: &[AccountInfo],
+    previous_account_len: usize,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut remaining_account_infos.iter();
+    let Ok(destination_info) = next_account_info(account_info_iter) else {
+        return Ok(());
+    };
+    let mint_authority_info = next_account_info(account_info_iter)?;
+
+    {
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)?;
+        match &mint.base.mint_authority {
+            PodCOption {
+                option: PodCOption::<Pubkey>::SOME,
+                value: mint_authority,
+            } => Processor::validate_owner(
+                program_id,
+                mint_authority,
+                mint_authority_info,
+                mint_authority_info.data_len(),
+                account_info_iter.as_slice(),
+            )?,
+            _ => return Err(TokenError::AuthorityTypeNotSupported.into()),
+        }
+    }
+
+    refund_freed_rent(mint_info, destination_info, previous_account_len)
+}
+
 #[cfg(test)]
 mod test {
     use {
@@ -2726,6 +3007,62 @@ mod test {
         );
     }
 
+    #[test]
+    fn remove_extension() {
+        let variable_len = VariableLenMintTest {
+            data: vec![1, 2, 3, 4],
+        };
+        let account_size =
+            ExtensionType::try_calculate_account_len::<PodMint>(&[ExtensionType::MetadataPointer])
+                .unwrap()
+                + add_type_and_length_to_len(variable_len.get_packed_len().unwrap());
+        let mut buffer = vec![0; account_size];
+        let mut state =
+            PodStateWithExtensionsMut::<PodMint>::unpack_uninitialized(&mut buffer).unwrap();
+
+        // alloc both types
+        let max_pubkey =
+            OptionalNonZeroPubkey::try_from(Some(Pubkey::new_from_array([255; 32]))).unwrap();
+        let extension = state.init_extension::<MetadataPointer>(false).unwrap();
+        extension.authority = max_pubkey;
+        extension.metadata_address = max_pubkey;
+        state
+            .init_variable_len_extension(&variable_len, false)
+            .unwrap();
+
+        // remove the first entry, the second one is moved to the front
+        state.remove_extension::<MetadataPointer>().unwrap();
+        assert_eq!(
+            state.get_extension_types().unwrap(),
+            vec![ExtensionType::VariableLenMintTest]
+        );
+        let extension = state
+            .get_variable_len_extension::<VariableLenMintTest>()
+            .unwrap();
+        assert_eq!(extension, variable_len);
+        assert_eq!(
+            state.try_get_account_len().unwrap(),
+            BASE_ACCOUNT_AND_TYPE_LENGTH
+                + add_type_and_length_to_len(variable_len.get_packed_len().unwrap())
+        );
+
+        // removing again fails
+        assert_eq!(
+            state.remove_extension::<MetadataPointer>().unwrap_err(),
+            TokenError::ExtensionNotFound.into()
+        );
+
+        // remove the last entry
+        state.remove_extension::<VariableLenMintTest>().unwrap();
+        assert_eq!(state.get_extension_types().unwrap(), vec![]);
+        assert_eq!(state.try_get_account_len().unwrap(), PodMint::SIZE_OF);
+        let tlv_start = BASE_ACCOUNT_AND_TYPE_LENGTH;
+        assert_eq!(
+            &buffer[tlv_start..],
+            vec![0; account_size - tlv_start].as_slice()
+        );
+    }
+
     #[test]
     fn account_len() {
         let small_variable_len = VariableLenMintTest {
@@ -2789,6 +3126,30 @@ mod test {
         assert_eq!(new_len, current_len);
     }
 
+    #[test]
+    fn account_len_avoids_weighted_multisig_len() {
+        // variable-length data that would make the mint exactly as big as a
+        // weighted multisig
+        let data_len = PodWeightedMultisig::SIZE_OF
+            - BASE_ACCOUNT_AND_TYPE_LENGTH
+            - add_type_and_length_to_len(size_of::<u64>());
+        let variable_len = VariableLenMintTest {
+            data: vec![1; data_len],
+        };
+        let mut buffer = vec![0; PodMint::SIZE_OF];
+        let state =
+            PodStateWithExtensionsMut::<PodMint>::unpack_uninitialized(&mut buffer).unwrap();
+        let new_len = state
+            .try_get_new_account_len_for_variable_len_extension::<VariableLenMintTest>(
+                &variable_len,
+            )
+            .unwrap();
+        assert_eq!(
+            new_len,
+            PodWeightedMultisig::SIZE_OF + size_of::<ExtensionType>()
+        );
+    }
+
     /// Test helper for mimicking the data layout an on-chain `AccountInfo`,
     /// which permits "reallocs" as the Solana runtime does it
     struct SolanaAccountData {
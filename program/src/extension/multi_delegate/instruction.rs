@@ -0,0 +1,137 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        check_program_account,
+        instruction::{encode_instruction, TokenInstruction},
+    },
+    bytemuck::{Pod, Zeroable},
+    ethnum::U256,
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    spl_pod::primitives::{PodI64, PodU256},
+};
+
+/// Multiple delegates extension instructions
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum MultiDelegateInstruction {
+    /// Approves a delegate in the account's delegate table, with its own
+    /// allowance and optional expiry. Unlike `Approve`, this leaves the
+    /// account's main delegate and any other table entries untouched.
+    ///
+    /// Re-approving a delegate replaces its entry. Otherwise, the delegate
+    /// takes an entry that is unused, used up, or expired, failing if there is
+    /// none.
+    ///
+    /// Implicitly initializes the extension in the case where it is not
+    /// present, so the account must have room for it, see `Reallocate`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The delegate.
+    ///   2. `[signer]` The source account owner.
+    ///
+    ///   * Multisignature owner
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The delegate.
+    ///   2. `[]` The source account's multisignature owner.
+    ///   3. `..3+M` `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::multi_delegate::instruction::ApproveInstructionData`
+    Approve,
+    /// Removes a delegate from the account's delegate table, signed by either
+    /// the account owner or the delegate itself.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The delegate.
+    ///   2. `[signer]` The source account owner or the delegate.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The delegate.
+    ///   2. `[]` The source account's multisignature owner or delegate.
+    ///   3. `..3+M` `[signer]` M signer accounts.
+    Revoke,
+}
+
+/// Data expected by `MultiDelegateInstruction::Approve`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct ApproveInstructionData {
+    /// The amount of tokens the delegate is approved for
+    pub amount: PodU256,
+    /// Time at which the approval expires, or 0 if it never does
+    pub expires_at: PodI64,
+}
+
+/// Create an `Approve` instruction
+pub fn approve(
+    token_program_id: &Pubkey,
+    source: &Pubkey,
+    delegate: &Pubkey,
+    owner: &Pubkey,
+    signers: &[&Pubkey],
+    amount: U256,
+    expires_at: Option<i64>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*source, false),
+        AccountMeta::new_readonly(*delegate, false),
+        AccountMeta::new_readonly(*owner, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::MultiDelegateExtension,
+        MultiDelegateInstruction::Approve,
+        &ApproveInstructionData {
+            amount: amount.into(),
+            expires_at: expires_at.unwrap_or_default().into(),
+        },
+    ))
+}
+
+/// Create a `Revoke` instruction
+pub fn revoke(
+    token_program_id: &Pubkey,
+    source: &Pubkey,
+    delegate: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*source, false),
+        AccountMeta::new_readonly(*delegate, false),
+        AccountMeta::new_readonly(*authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::MultiDelegateExtension,
+        MultiDelegateInstruction::Revoke,
+        &(),
+    ))
+}
@@ -0,0 +1,218 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        error::TokenError,
+        extension::{BaseState, BaseStateWithExtensions, Extension, ExtensionType},
+    },
+    bytemuck::{Pod, Zeroable},
+    ethnum::U256,
+    solana_program::{
+        clock::Clock, entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey,
+        sysvar::Sysvar,
+    },
+    spl_pod::{
+        optional_keys::OptionalNonZeroPubkey,
+        primitives::{PodI64, PodU256},
+    },
+};
+
+/// Multiple delegates extension instructions
+pub mod instruction;
+
+/// Multiple delegates extension processor
+pub mod processor;
+
+/// `UnixTimestamp` expressed with an alignment-independent type
+pub type UnixTimestamp = PodI64;
+
+/// Maximum number of delegates in the table of an account
+pub const MAX_DELEGATES: usize = 4;
+
+/// Delegate approved through the table, next to the account's main delegate
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct DelegateEntry {
+    /// The delegate, or none if the entry is free
+    pub delegate: OptionalNonZeroPubkey,
+    /// Amount the delegate may still transfer or burn
+    pub allowance: PodU256,
+    /// Time at which the approval expires, or 0 if it never does
+    pub expires_at: UnixTimestamp,
+}
+impl DelegateEntry {
+    /// Check if the entry approves `delegate` at the given time
+    pub fn is_active_for(&self, delegate: &Pubkey, unix_timestamp: i64) -> bool {
+        let expires_at = i64::from(self.expires_at);
+        Option::<Pubkey>::from(self.delegate).as_ref() == Some(delegate)
+            && U256::from(self.allowance) > 0
+            && (expires_at == 0 || unix_timestamp < expires_at)
+    }
+
+    /// Check if the entry can hold a new approval at the given time
+    pub fn is_free(&self, unix_timestamp: i64) -> bool {
+        match Option::<Pubkey>::from(self.delegate) {
+            Some(delegate) => !self.is_active_for(&delegate, unix_timestamp),
+            None => true,
+        }
+    }
+}
+
+/// Table of delegates that each hold their own allowance on the account
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct MultiDelegate {
+    /// The delegate entries
+    pub delegates: [DelegateEntry; MAX_DELEGATES],
+}
+impl MultiDelegate {
+    /// Find the entry approving `delegate` at the given time
+    pub fn find_active(&self, delegate: &Pubkey, unix_timestamp: i64) -> Option<usize> {
+        self.delegates
+            .iter()
+            .position(|entry| entry.is_active_for(delegate, unix_timestamp))
+    }
+
+    /// Approve `delegate` for `amount`, replacing its previous approval, or
+    /// taking a free entry otherwise
+    pub fn approve(
+        &mut self,
+        delegate: &Pubkey,
+        amount: U256,
+        expires_at: i64,
+        unix_timestamp: i64,
+    ) -> ProgramResult {
+        let index = self
+            .delegates
+            .iter()
+            .position(|entry| Option::<Pubkey>::from(entry.delegate).as_ref() == Some(delegate))
+            .or_else(|| {
+                self.delegates
+                    .iter()
+                    .position(|entry| entry.is_free(unix_timestamp))
+            })
+            .ok_or(TokenError::DelegateTableFull)?;
+        self.delegates[index] = DelegateEntry {
+            delegate: Some(*delegate).try_into()?,
+            allowance: amount.into(),
+            expires_at: expires_at.into(),
+        };
+        Ok(())
+    }
+
+    /// Remove the approval of `delegate`, if any
+    pub fn revoke(&mut self, delegate: &Pubkey) {
+        for entry in self.delegates.iter_mut() {
+            if Option::<Pubkey>::from(entry.delegate).as_ref() == Some(delegate) {
+                *entry = DelegateEntry::default();
+            }
+        }
+    }
+
+    /// Take `amount` from the allowance of the entry at `index`, freeing the
+    /// entry once the allowance is used up
+    pub fn spend(&mut self, index: usize, amount: U256) -> ProgramResult {
+        let entry = &mut self.delegates[index];
+        let allowance = U256::from(entry.allowance)
+            .checked_sub(amount)
+            .ok_or(TokenError::InsufficientFunds)?;
+        if allowance == 0 {
+            *entry = DelegateEntry::default();
+        } else {
+            entry.allowance = allowance.into();
+        }
+        Ok(())
+    }
+
+    /// Check if no entry holds an approval, expired or not
+    pub fn is_empty(&self) -> bool {
+        self.delegates
+            .iter()
+            .all(|entry| Option::<Pubkey>::from(entry.delegate).is_none())
+    }
+}
+impl Extension for MultiDelegate {
+    const TYPE: ExtensionType = ExtensionType::MultiDelegate;
+}
+
+/// Find the table entry approving `delegate`, only reading the clock if the
+/// account has a delegate table
+pub fn find_active_delegate<S: BaseState, BSE: BaseStateWithExtensions<S>>(
+    state: &BSE,
+    delegate: &Pubkey,
+) -> Result<Option<usize>, ProgramError> {
+    match state.get_extension::<MultiDelegate>() {
+        Ok(extension) => Ok(extension.find_active(delegate, Clock::get()?.unix_timestamp)),
+        Err(_) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approve_spend_revoke() {
+        let delegates = [(); MAX_DELEGATES + 1].map(|_| Pubkey::new_unique());
+        let mut table = MultiDelegate::default();
+        assert!(table.is_empty());
+
+        for delegate in &delegates[..MAX_DELEGATES] {
+            table.approve(delegate, U256::new(10), 0, 0).unwrap();
+        }
+        assert_eq!(
+            table.approve(&delegates[MAX_DELEGATES], U256::new(10), 0, 0),
+            Err(TokenError::DelegateTableFull.into())
+        );
+
+        // re-approving updates the existing entry
+        table.approve(&delegates[1], U256::new(20), 0, 0).unwrap();
+        assert_eq!(table.find_active(&delegates[1], 0), Some(1));
+        assert_eq!(U256::from(table.delegates[1].allowance), 20);
+
+        // spending the full allowance frees the entry
+        assert_eq!(
+            table.spend(0, U256::new(11)),
+            Err(TokenError::InsufficientFunds.into())
+        );
+        table.spend(0, U256::new(4)).unwrap();
+        assert_eq!(U256::from(table.delegates[0].allowance), 6);
+        table.spend(0, U256::new(6)).unwrap();
+        assert_eq!(table.find_active(&delegates[0], 0), None);
+        table
+            .approve(&delegates[MAX_DELEGATES], U256::new(10), 0, 0)
+            .unwrap();
+        assert_eq!(table.find_active(&delegates[MAX_DELEGATES], 0), Some(0));
+
+        table.revoke(&delegates[MAX_DELEGATES]);
+        assert_eq!(table.find_active(&delegates[MAX_DELEGATES], 0), None);
+    }
+
+    #[test]
+    fn expiry() {
+        let delegate = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let mut table = MultiDelegate::default();
+        for _ in 0..MAX_DELEGATES {
+            table
+                .approve(&Pubkey::new_unique(), U256::new(10), 100, 0)
+                .unwrap();
+        }
+        table.revoke(&Option::<Pubkey>::from(table.delegates[0].delegate).unwrap());
+        table.approve(&delegate, U256::new(10), 100, 0).unwrap();
+
+        assert_eq!(table.find_active(&delegate, 99), Some(0));
+        assert_eq!(table.find_active(&delegate, 100), None);
+        assert_eq!(
+            table.approve(&other, U256::new(10), 0, 99),
+            Err(TokenError::DelegateTableFull.into())
+        );
+        // expired entries can be reused
+        table.approve(&other, U256::new(10), 0, 100).unwrap();
+        assert_eq!(table.find_active(&other, 100), Some(0));
+    }
+}
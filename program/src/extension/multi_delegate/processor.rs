@@ -0,0 +1,144 @@
+use {
+    crate::{
+        check_program_account,
+        error::TokenError,
+        event::{self, MultiDelegateApproveEvent, MultiDelegateRevokeEvent},
+        extension::{
+            cpi_guard::cpi_guard_blocks,
+            multi_delegate::{
+                instruction::{ApproveInstructionData, MultiDelegateInstruction},
+                MultiDelegate,
+            },
+            BaseStateWithExtensionsMut, PodStateWithExtensionsMut,
+        },
+        instruction::{decode_instruction_data, decode_instruction_type},
+        pod::PodAccount,
+        processor::Processor,
+    },
+    ethnum::U256,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        clock::Clock,
+        entrypoint::ProgramResult,
+        msg,
+        pubkey::Pubkey,
+        sysvar::Sysvar,
+    },
+};
+
+/// Approve a delegate in the table, initializing the extension if not already
+/// present.
+fn process_approve(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: U256,
+    expires_at: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let source_account_info = next_account_info(account_info_iter)?;
+    let delegate_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut source_account_data = source_account_info.data.borrow_mut();
+    let mut source_account =
+        PodStateWithExtensionsMut::<PodAccount>::unpack(&mut source_account_data)?;
+    if source_account.base.is_frozen() {
+        return Err(TokenError::AccountFrozen.into());
+    }
+
+    Processor::validate_owner(
+        program_id,
+        &source_account.base.owner,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    if cpi_guard_blocks(&source_account, account_info_iter.as_slice())? {
+        return Err(TokenError::CpiGuardApproveBlocked.into());
+    }
+
+    let unix_timestamp = Clock::get()?.unix_timestamp;
+    if expires_at != 0 && expires_at <= unix_timestamp {
+        return Err(TokenError::InvalidDelegateExpiry.into());
+    }
+
+    let extension = if let Ok(extension) = source_account.get_extension_mut::<MultiDelegate>() {
+        extension
+    } else {
+        source_account.init_extension::<MultiDelegate>(true)?
+    };
+    extension.approve(delegate_info.key, amount, expires_at, unix_timestamp)?;
+
+    event::emit(&MultiDelegateApproveEvent {
+        account: *source_account_info.key,
+        delegate: *delegate_info.key,
+        allowance: amount.into(),
+        expires_at: expires_at.into(),
+    });
+    Ok(())
+}
+
+/// Remove a delegate from the table
+fn process_revoke(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let source_account_info = next_account_info(account_info_iter)?;
+    let delegate_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let authority_info_data_len = authority_info.data_len();
+
+    let mut source_account_data = source_account_info.data.borrow_mut();
+    let mut source_account =
+        PodStateWithExtensionsMut::<PodAccount>::unpack(&mut source_account_data)?;
+    if source_account.base.is_frozen() {
+        return Err(TokenError::AccountFrozen.into());
+    }
+
+    Processor::validate_owner(
+        program_id,
+        if authority_info.key == delegate_info.key {
+            delegate_info.key
+        } else {
+            &source_account.base.owner
+        },
+        authority_info,
+        authority_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    source_account
+        .get_extension_mut::<MultiDelegate>()?
+        .revoke(delegate_info.key);
+
+    event::emit(&MultiDelegateRevokeEvent {
+        account: *source_account_info.key,
+        delegate: *delegate_info.key,
+    });
+    Ok(())
+}
+
+pub(crate) fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    check_program_account(program_id)?;
+
+    match decode_instruction_type(input)? {
+        MultiDelegateInstruction::Approve => {
+            msg!("MultiDelegateInstruction::Approve");
+            let ApproveInstructionData { amount, expires_at } = decode_instruction_data(input)?;
+            process_approve(
+                program_id,
+                accounts,
+                U256::from(*amount),
+                i64::from(*expires_at),
+            )
+        }
+        MultiDelegateInstruction::Revoke => {
+            msg!("MultiDelegateInstruction::Revoke");
+            process_revoke(program_id, accounts)
+        }
+    }
+}
@@ -0,0 +1,141 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        check_program_account,
+        instruction::{encode_instruction, TokenInstruction},
+    },
+    bytemuck::{Pod, Zeroable},
+    ethnum::U256,
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    spl_pod::{optional_keys::OptionalNonZeroPubkey, primitives::PodU256},
+    std::convert::TryInto,
+};
+
+/// Non-transferable extension instructions
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum NonTransferableInstruction {
+    /// Initialize the recovery authority on a new non-transferable mint.
+    ///
+    /// Fails if the mint has already been initialized, so must be called before
+    /// `InitializeMint`, which fails if the mint isn't non-transferable.
+    ///
+    /// The mint must have exactly enough space allocated for the base mint (82
+    /// bytes), plus 83 bytes of padding, 1 byte reserved for the account type,
+    /// then space required for this extension, plus any others.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to initialize.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::non_transferable::instruction::InitializeRecoveryAuthorityInstructionData`
+    InitializeRecoveryAuthority,
+    /// Move a balance of non-transferable tokens between accounts, signed by
+    /// the mint's recovery authority instead of the source account's owner.
+    ///
+    /// This is the only way to move non-transferable tokens, and it can't be
+    /// used on other mints. Recoveries are logged as such.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[signer]` The mint's recovery authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[]` The mint's multisignature recovery authority.
+    ///   4. `..4+M` `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::non_transferable::instruction::RecoverInstructionData`
+    Recover,
+}
+
+/// Data expected by `NonTransferableInstruction::InitializeRecoveryAuthority`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct InitializeRecoveryAuthorityInstructionData {
+    /// The recovery authority
+    pub authority: OptionalNonZeroPubkey,
+}
+
+/// Data expected by `NonTransferableInstruction::Recover`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct RecoverInstructionData {
+    /// The amount of tokens to recover
+    pub amount: PodU256,
+    /// Expected number of base 10 digits to the right of the decimal place
+    pub decimals: u8,
+}
+
+/// Create an `InitializeRecoveryAuthority` instruction
+pub fn initialize_recovery_authority(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    authority: Option<Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::NonTransferableExtension,
+        NonTransferableInstruction::InitializeRecoveryAuthority,
+        &InitializeRecoveryAuthorityInstructionData {
+            authority: authority.try_into()?,
+        },
+    ))
+}
+
+/// Create a `Recover` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn recover(
+    token_program_id: &Pubkey,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    recovery_authority: &Pubkey,
+    signers: &[&Pubkey],
+    amount: U256,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*source, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(*recovery_authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::NonTransferableExtension,
+        NonTransferableInstruction::Recover,
+        &RecoverInstructionData {
+            amount: amount.into(),
+            decimals,
+        },
+    ))
+}
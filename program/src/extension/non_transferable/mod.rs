@@ -0,0 +1,63 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::extension::{BaseState, BaseStateWithExtensions, Extension, ExtensionType},
+    bytemuck::{Pod, Zeroable},
+    solana_program::pubkey::Pubkey,
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+};
+
+/// Non-transferable extension instructions
+pub mod instruction;
+
+/// Non-transferable extension processor
+pub mod processor;
+
+/// Indicates that the tokens from this mint can't be transferred
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+#[repr(transparent)]
+pub struct NonTransferable;
+
+/// Indicates that the tokens from this account belong to a non-transferable
+/// mint
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+#[repr(transparent)]
+pub struct NonTransferableAccount;
+
+impl Extension for NonTransferable {
+    const TYPE: ExtensionType = ExtensionType::NonTransferable;
+}
+
+impl Extension for NonTransferableAccount {
+    const TYPE: ExtensionType = ExtensionType::NonTransferableAccount;
+}
+
+/// Recovery authority for a non-transferable mint, which can move a holder's
+/// balance to a new account, for example after the holder lost their wallet
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct NonTransferableRecovery {
+    /// Authority that can recover balances, or none if recovery is disabled
+    pub authority: OptionalNonZeroPubkey,
+}
+
+impl Extension for NonTransferableRecovery {
+    const TYPE: ExtensionType = ExtensionType::NonTransferableRecovery;
+}
+
+/// Attempts to get the recovery authority from the TLV data, returning None if
+/// the extension is not found or recovery is disabled
+pub fn get_recovery_authority<S: BaseState, BSE: BaseStateWithExtensions<S>>(
+    state: &BSE,
+) -> Option<Pubkey> {
+    state
+        .get_extension::<NonTransferableRecovery>()
+        .ok()
+        .and_then(|e| Option::<Pubkey>::from(e.authority))
+}
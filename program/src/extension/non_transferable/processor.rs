@@ -0,0 +1,61 @@
+use {
+    crate::{
+        check_program_account,
+        extension::{
+            non_transferable::{
+                instruction::{
+                    InitializeRecoveryAuthorityInstructionData, NonTransferableInstruction,
+                    RecoverInstructionData,
+                },
+                NonTransferableRecovery,
+            },
+            BaseStateWithExtensionsMut, PodStateWithExtensionsMut,
+        },
+        instruction::{decode_instruction_data, decode_instruction_type},
+        pod::PodMint,
+        processor::Processor,
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        msg,
+        pubkey::Pubkey,
+    },
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+};
+
+fn process_initialize_recovery_authority(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    authority: &OptionalNonZeroPubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack_uninitialized(&mut mint_data)?;
+
+    let extension = mint.init_extension::<NonTransferableRecovery>(true)?;
+    extension.authority = *authority;
+    Ok(())
+}
+
+pub(crate) fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    check_program_account(program_id)?;
+    match decode_instruction_type(input)? {
+        NonTransferableInstruction::InitializeRecoveryAuthority => {
+            msg!("NonTransferableInstruction::InitializeRecoveryAuthority");
+            let InitializeRecoveryAuthorityInstructionData { authority } =
+                decode_instruction_data(input)?;
+            process_initialize_recovery_authority(program_id, accounts, authority)
+        }
+        NonTransferableInstruction::Recover => {
+            msg!("NonTransferableInstruction::Recover");
+            let data = decode_instruction_data::<RecoverInstructionData>(input)?;
+            Processor::process_recover(program_id, accounts, data.amount.into(), data.decimals)
+        }
+    }
+}
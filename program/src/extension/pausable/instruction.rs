@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use {
     crate::{
         check_program_account,
+        extension::pausable::{PausableOperation, UnixTimestamp},
         instruction::{encode_instruction, TokenInstruction},
     },
     bytemuck::{Pod, Zeroable},
@@ -12,6 +13,7 @@ use {
         program_error::ProgramError,
         pubkey::Pubkey,
     },
+    spl_pod::primitives::PodBool,
 };
 
 /// Pausable extension instructions
@@ -34,6 +36,8 @@ pub enum PausableInstruction {
     Initialize,
     /// Pause minting, burning, and transferring for the mint.
     ///
+    /// To pause a single operation, use `SetPaused`.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   0. `[writable]` The mint to update.
@@ -46,6 +50,10 @@ pub enum PausableInstruction {
     Pause,
     /// Resume minting, burning, and transferring for the mint.
     ///
+    /// Also clears the pause flag of every operation, if the mint has the
+    /// `PausableOperations` extension. To resume a single operation, use
+    /// `SetPaused`.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   0. `[writable]` The mint to update.
@@ -56,6 +64,75 @@ pub enum PausableInstruction {
     ///   1. `[]` The mint's multisignature pause authority.
     ///   2. `..2+M` `[signer]` M signer accounts.
     Resume,
+    /// Pause or resume a single operation on the mint, leaving the other
+    /// operations untouched.
+    ///
+    /// Fails if the mint does not have the `PausableOperations` extension.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to update.
+    ///   1. `[signer]` The mint's pause authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint to update.
+    ///   1. `[]` The mint's multisignature pause authority.
+    ///   2. `..2+M` `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::pausable::instruction::SetPausedInstructionData`
+    SetPaused,
+    /// Schedule a pause of all operations and / or an automatic resume of all
+    /// operations at the given unix timestamps, replacing any previous
+    /// schedule. A timestamp of 0 leaves that part of the schedule unset, and
+    /// a timestamp in the past takes effect immediately.
+    ///
+    /// If both timestamps are set, the resume must come after the pause.
+    ///
+    /// Fails if the mint does not have the `PauseSchedule` extension.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to update.
+    ///   1. `[signer]` The mint's pause authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint to update.
+    ///   1. `[]` The mint's multisignature pause authority.
+    ///   2. `..2+M` `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::pausable::instruction::SetPauseScheduleInstructionData`
+    SetPauseSchedule,
+    /// Initialize the per-operation pause flags for the given mint account,
+    /// which allow `SetPaused` to pause minting, burning, and transferring
+    /// independently.
+    ///
+    /// Fails if the account has already been initialized, so must be called
+    /// before `InitializeMint`. The extension may also be added to an
+    /// initialized mint with `ReallocateMint`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]`  The mint account to initialize.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    InitializeOperations,
+    /// Initialize the pause schedule for the given mint account, which allows
+    /// `SetPauseSchedule` to pause and resume the mint at given timestamps.
+    ///
+    /// Fails if the account has already been initialized, so must be called
+    /// before `InitializeMint`. The extension may also be added to an
+    /// initialized mint with `ReallocateMint`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]`  The mint account to initialize.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    InitializeSchedule,
 }
 
 /// Data expected by `PausableInstruction::Initialize`
@@ -68,6 +145,30 @@ pub struct InitializeInstructionData {
     pub authority: Pubkey,
 }
 
+/// Data expected by `PausableInstruction::SetPaused`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct SetPausedInstructionData {
+    /// The operation to update, as a `PausableOperation`
+    pub operation: u8,
+    /// Whether the operation is paused
+    pub paused: PodBool,
+}
+
+/// Data expected by `PausableInstruction::SetPauseSchedule`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct SetPauseScheduleInstructionData {
+    /// Timestamp at which all operations become paused, or 0 for none
+    pub pause_timestamp: UnixTimestamp,
+    /// Timestamp at which all operations are resumed, or 0 for none
+    pub resume_timestamp: UnixTimestamp,
+}
+
 /// Create an `Initialize` instruction
 pub fn initialize(
     token_program_id: &Pubkey,
@@ -87,6 +188,38 @@ pub fn initialize(
     ))
 }
 
+/// Create an `InitializeOperations` instruction
+pub fn initialize_operations(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::PausableExtension,
+        PausableInstruction::InitializeOperations,
+        &(),
+    ))
+}
+
+/// Create an `InitializeSchedule` instruction
+pub fn initialize_schedule(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::PausableExtension,
+        PausableInstruction::InitializeSchedule,
+        &(),
+    ))
+}
+
 /// Create a `Pause` instruction
 pub fn pause(
     token_program_id: &Pubkey,
@@ -134,3 +267,61 @@ pub fn resume(
         &(),
     ))
 }
+
+/// Create a `SetPaused` instruction
+pub fn set_paused(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    operation: PausableOperation,
+    paused: bool,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::PausableExtension,
+        PausableInstruction::SetPaused,
+        &SetPausedInstructionData {
+            operation: operation.into(),
+            paused: paused.into(),
+        },
+    ))
+}
+
+/// Create a `SetPauseSchedule` instruction
+pub fn set_pause_schedule(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    pause_timestamp: Option<i64>,
+    resume_timestamp: Option<i64>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::PausableExtension,
+        PausableInstruction::SetPauseSchedule,
+        &SetPauseScheduleInstructionData {
+            pause_timestamp: pause_timestamp.unwrap_or_default().into(),
+            resume_timestamp: resume_timestamp.unwrap_or_default().into(),
+        },
+    ))
+}
@@ -1,9 +1,14 @@
 #[cfg(feature = "serde-traits")]
 use serde::{Deserialize, Serialize};
 use {
-    crate::extension::{Extension, ExtensionType},
+    crate::extension::{BaseState, BaseStateWithExtensions, Extension, ExtensionType},
     bytemuck::{Pod, Zeroable},
-    spl_pod::{optional_keys::OptionalNonZeroPubkey, primitives::PodBool},
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{clock::Clock, program_error::ProgramError, sysvar::Sysvar},
+    spl_pod::{
+        optional_keys::OptionalNonZeroPubkey,
+        primitives::{PodBool, PodI64},
+    },
 };
 
 /// Instruction types for the pausable extension
@@ -11,6 +16,9 @@ pub mod instruction;
 /// Instruction processor for the pausable extension
 pub mod processor;
 
+/// `UnixTimestamp` expressed with an alignment-independent type
+pub type UnixTimestamp = PodI64;
+
 /// Indicates that the tokens from this mint can be paused
 #[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
@@ -23,6 +31,167 @@ pub struct PausableConfig {
     pub paused: PodBool,
 }
 
+impl PausableConfig {
+    /// Check if the given operation is paused on the mint, taking the
+    /// per-operation flags into account
+    pub fn is_paused(
+        &self,
+        operations: Option<&PausableOperations>,
+        operation: PausableOperation,
+    ) -> bool {
+        bool::from(self.paused)
+            || operations.is_some_and(|operations| operations.is_paused(operation))
+    }
+
+    /// Resume every operation on the mint
+    pub fn resume(&mut self, operations: Option<&mut PausableOperations>) {
+        self.paused = false.into();
+        if let Some(operations) = operations {
+            *operations = PausableOperations::default();
+        }
+    }
+
+    /// Pause or resume a single operation on the mint, leaving the other
+    /// operations untouched
+    pub fn set_paused(
+        &mut self,
+        operations: &mut PausableOperations,
+        operation: PausableOperation,
+        paused: bool,
+    ) {
+        // a pause of the whole mint is carried over to every operation, so
+        // that a single one can be resumed
+        if bool::from(self.paused) {
+            self.paused = false.into();
+            for operation in PausableOperation::ALL {
+                operations.set_paused(operation, true);
+            }
+        }
+        operations.set_paused(operation, paused);
+    }
+}
+
+/// Scheduled pause and automatic resume of all operations on a pausable mint
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct PauseSchedule {
+    /// Unix timestamp at which all operations become paused, or 0 if no pause
+    /// is scheduled
+    pub scheduled_pause_timestamp: UnixTimestamp,
+    /// Unix timestamp at which all operations are resumed, or 0 if no resume
+    /// is scheduled
+    pub scheduled_resume_timestamp: UnixTimestamp,
+}
+
+impl PauseSchedule {
+    /// Apply the schedule at the given time to the pause state of an
+    /// operation
+    pub fn is_paused(&self, paused: bool, unix_timestamp: i64) -> bool {
+        let resume_timestamp = i64::from(self.scheduled_resume_timestamp);
+        if resume_timestamp != 0 && unix_timestamp >= resume_timestamp {
+            return false;
+        }
+        let pause_timestamp = i64::from(self.scheduled_pause_timestamp);
+        paused || (pause_timestamp != 0 && unix_timestamp >= pause_timestamp)
+    }
+
+    /// Write the effect of every elapsed scheduled timestamp into the pause
+    /// flags, and clear those timestamps
+    pub fn settle(
+        &mut self,
+        config: &mut PausableConfig,
+        operations: Option<&mut PausableOperations>,
+        unix_timestamp: i64,
+    ) {
+        let resume_timestamp = i64::from(self.scheduled_resume_timestamp);
+        let pause_timestamp = i64::from(self.scheduled_pause_timestamp);
+        if resume_timestamp != 0 && unix_timestamp >= resume_timestamp {
+            config.resume(operations);
+        } else if pause_timestamp != 0 && unix_timestamp >= pause_timestamp {
+            config.paused = true.into();
+        }
+        if unix_timestamp >= pause_timestamp {
+            self.scheduled_pause_timestamp = 0.into();
+        }
+        if unix_timestamp >= resume_timestamp {
+            self.scheduled_resume_timestamp = 0.into();
+        }
+    }
+}
+
+/// Pause flags of the individual operations on a pausable mint, on top of the
+/// pause of the whole mint in `PausableConfig`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct PausableOperations {
+    /// Whether minting tokens is paused
+    pub mint_paused: PodBool,
+    /// Whether burning tokens is paused
+    pub burn_paused: PodBool,
+    /// Whether transferring tokens is paused
+    pub transfer_paused: PodBool,
+}
+
+impl PausableOperations {
+    /// Check if the given operation is flagged as paused
+    pub fn is_paused(&self, operation: PausableOperation) -> bool {
+        match operation {
+            PausableOperation::Mint => self.mint_paused.into(),
+            PausableOperation::Burn => self.burn_paused.into(),
+            PausableOperation::Transfer => self.transfer_paused.into(),
+        }
+    }
+
+    /// Pause or resume the given operation
+    pub fn set_paused(&mut self, operation: PausableOperation, paused: bool) {
+        match operation {
+            PausableOperation::Mint => self.mint_paused = paused.into(),
+            PausableOperation::Burn => self.burn_paused = paused.into(),
+            PausableOperation::Transfer => self.transfer_paused = paused.into(),
+        }
+    }
+}
+
+/// Check if the given operation is paused on the mint
+pub fn is_paused<S: BaseState, BSE: BaseStateWithExtensions<S>>(
+    mint: &BSE,
+    operation: PausableOperation,
+) -> Result<bool, ProgramError> {
+    let Ok(extension) = mint.get_extension::<PausableConfig>() else {
+        return Ok(false);
+    };
+    let paused = extension.is_paused(mint.get_extension::<PausableOperations>().ok(), operation);
+    if let Ok(schedule) = mint.get_extension::<PauseSchedule>() {
+        Ok(schedule.is_paused(paused, Clock::get()?.unix_timestamp))
+    } else {
+        Ok(paused)
+    }
+}
+
+/// Operations on a pausable mint that can be paused independently
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum PausableOperation {
+    /// Minting tokens, including confidential mints
+    Mint,
+    /// Burning tokens, including confidential burns
+    Burn,
+    /// Transferring tokens, including confidential deposits, withdrawals and
+    /// transfers
+    Transfer,
+}
+
+impl PausableOperation {
+    /// All operations that can be paused on a mint
+    pub const ALL: [Self; 3] = [Self::Mint, Self::Burn, Self::Transfer];
+}
+
 /// Indicates that the tokens from this account belong to a pausable mint
 #[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
@@ -37,3 +206,90 @@ impl Extension for PausableConfig {
 impl Extension for PausableAccount {
     const TYPE: ExtensionType = ExtensionType::PausableAccount;
 }
+
+impl Extension for PausableOperations {
+    const TYPE: ExtensionType = ExtensionType::PausableOperations;
+}
+
+impl Extension for PauseSchedule {
+    const TYPE: ExtensionType = ExtensionType::PauseSchedule;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scheduled_pause_window() {
+        let mut config = PausableConfig::default();
+        let mut schedule = PauseSchedule {
+            scheduled_pause_timestamp: UnixTimestamp::from(10),
+            scheduled_resume_timestamp: UnixTimestamp::from(20),
+        };
+        assert!(!schedule.is_paused(false, 9));
+        assert!(schedule.is_paused(false, 10));
+        assert!(schedule.is_paused(false, 19));
+        assert!(!schedule.is_paused(true, 20));
+
+        // settling inside the window keeps the scheduled resume
+        schedule.settle(&mut config, None, 15);
+        assert!(bool::from(config.paused));
+        assert_eq!(i64::from(schedule.scheduled_pause_timestamp), 0);
+        assert_eq!(i64::from(schedule.scheduled_resume_timestamp), 20);
+        assert!(schedule.is_paused(config.is_paused(None, PausableOperation::Mint), 15));
+        assert!(!schedule.is_paused(config.is_paused(None, PausableOperation::Mint), 20));
+
+        // settling after the window clears the pause
+        schedule.settle(&mut config, None, 20);
+        assert_eq!(config, PausableConfig::default());
+        assert_eq!(schedule, PauseSchedule::default());
+    }
+
+    #[test]
+    fn scheduled_resume_after_manual_pause() {
+        let mut config = PausableConfig::default();
+        let mut operations = PausableOperations::default();
+        let mut schedule = PauseSchedule {
+            scheduled_resume_timestamp: UnixTimestamp::from(20),
+            ..Default::default()
+        };
+        config.set_paused(&mut operations, PausableOperation::Transfer, true);
+        let transfer_paused = config.is_paused(Some(&operations), PausableOperation::Transfer);
+        let mint_paused = config.is_paused(Some(&operations), PausableOperation::Mint);
+        assert!(schedule.is_paused(transfer_paused, 19));
+        assert!(!schedule.is_paused(mint_paused, 19));
+        assert!(!schedule.is_paused(transfer_paused, 20));
+
+        // a manual pause after the resume does not get overridden once settled
+        schedule.settle(&mut config, Some(&mut operations), 21);
+        config.set_paused(&mut operations, PausableOperation::Mint, true);
+        assert_eq!(schedule, PauseSchedule::default());
+        assert!(config.is_paused(Some(&operations), PausableOperation::Mint));
+        assert!(!config.is_paused(Some(&operations), PausableOperation::Transfer));
+    }
+
+    #[test]
+    fn resume_single_operation_of_paused_mint() {
+        let mut config = PausableConfig {
+            paused: true.into(),
+            ..Default::default()
+        };
+        let mut operations = PausableOperations::default();
+        config.set_paused(&mut operations, PausableOperation::Burn, false);
+        assert!(!bool::from(config.paused));
+        assert!(config.is_paused(Some(&operations), PausableOperation::Mint));
+        assert!(!config.is_paused(Some(&operations), PausableOperation::Burn));
+        assert!(config.is_paused(Some(&operations), PausableOperation::Transfer));
+
+        config.resume(Some(&mut operations));
+        assert_eq!(operations, PausableOperations::default());
+        for operation in PausableOperation::ALL {
+            assert!(!config.is_paused(Some(&operations), operation));
+        }
+    }
+
+    #[test]
+    fn pausable_config_layout() {
+        assert_eq!(std::mem::size_of::<PausableConfig>(), 33);
+    }
+}
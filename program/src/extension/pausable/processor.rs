@@ -2,12 +2,17 @@ use {
     crate::{
         check_program_account,
         error::TokenError,
+        event::{self, UpdatePauseStateEvent},
         extension::{
             pausable::{
-                instruction::{InitializeInstructionData, PausableInstruction},
-                PausableConfig,
+                instruction::{
+                    InitializeInstructionData, PausableInstruction,
+                    SetPauseScheduleInstructionData, SetPausedInstructionData,
+                },
+                PausableConfig, PausableOperation, PausableOperations, PauseSchedule,
+                UnixTimestamp,
             },
-            BaseStateWithExtensionsMut, PodStateWithExtensionsMut,
+            BaseStateWithExtensions, BaseStateWithExtensionsMut, PodStateWithExtensionsMut,
         },
         instruction::{decode_instruction_data, decode_instruction_type},
         pod::PodMint,
@@ -15,12 +20,36 @@ use {
     },
     solana_program::{
         account_info::{next_account_info, AccountInfo},
+        clock::Clock,
         entrypoint::ProgramResult,
         msg,
+        program_error::ProgramError,
         pubkey::Pubkey,
+        sysvar::Sysvar,
     },
+    spl_pod::primitives::PodBool,
 };
 
+/// Emit the pause state of the mint, once the elapsed part of its schedule
+/// has been settled
+fn emit_pause_state(
+    mint: &Pubkey,
+    config: &PausableConfig,
+    operations: Option<&PausableOperations>,
+    schedule: Option<&PauseSchedule>,
+) {
+    let is_paused = |operation| PodBool::from(config.is_paused(operations, operation));
+    let schedule = schedule.copied().unwrap_or_default();
+    event::emit(&UpdatePauseStateEvent {
+        mint: *mint,
+        mint_paused: is_paused(PausableOperation::Mint),
+        burn_paused: is_paused(PausableOperation::Burn),
+        transfer_paused: is_paused(PausableOperation::Transfer),
+        scheduled_pause_timestamp: schedule.scheduled_pause_timestamp,
+        scheduled_resume_timestamp: schedule.scheduled_resume_timestamp,
+    });
+}
+
 fn process_initialize(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -37,12 +66,38 @@ fn process_initialize(
     Ok(())
 }
 
-/// Pause or resume minting / burning / transferring on the mint
-fn process_toggle_pause(
-    program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    pause: bool,
-) -> ProgramResult {
+fn process_initialize_operations(_program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack_uninitialized(&mut mint_data)?;
+
+    mint.init_extension::<PausableOperations>(true)?;
+
+    Ok(())
+}
+
+fn process_initialize_schedule(_program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack_uninitialized(&mut mint_data)?;
+
+    mint.init_extension::<PauseSchedule>(true)?;
+
+    Ok(())
+}
+
+/// Update the pause state of the mint after validating the pause authority,
+/// once the elapsed part of the pause schedule has been applied
+fn update_pause_state<F>(program_id: &Pubkey, accounts: &[AccountInfo], update: F) -> ProgramResult
+where
+    F: FnOnce(
+        &mut PausableConfig,
+        Option<&mut PausableOperations>,
+        Option<&mut PauseSchedule>,
+    ) -> ProgramResult,
+{
     let account_info_iter = &mut accounts.iter();
     let mint_account_info = next_account_info(account_info_iter)?;
     let authority_info = next_account_info(account_info_iter)?;
@@ -50,8 +105,10 @@ fn process_toggle_pause(
 
     let mut mint_data = mint_account_info.data.borrow_mut();
     let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack(&mut mint_data)?;
-    let extension = mint.get_extension_mut::<PausableConfig>()?;
-    let maybe_authority: Option<Pubkey> = extension.authority.into();
+    let mut config = *mint.get_extension::<PausableConfig>()?;
+    let mut operations = mint.get_extension::<PausableOperations>().ok().copied();
+    let mut schedule = mint.get_extension::<PauseSchedule>().ok().copied();
+    let maybe_authority: Option<Pubkey> = config.authority.into();
     let authority = maybe_authority.ok_or(TokenError::AuthorityTypeNotSupported)?;
 
     Processor::validate_owner(
@@ -62,10 +119,60 @@ fn process_toggle_pause(
         account_info_iter.as_slice(),
     )?;
 
-    extension.paused = pause.into();
+    if let Some(schedule) = schedule.as_mut() {
+        schedule.settle(
+            &mut config,
+            operations.as_mut(),
+            Clock::get()?.unix_timestamp,
+        );
+    }
+    update(&mut config, operations.as_mut(), schedule.as_mut())?;
+
+    *mint.get_extension_mut::<PausableConfig>()? = config;
+    if let Some(operations) = operations {
+        *mint.get_extension_mut::<PausableOperations>()? = operations;
+    }
+    if let Some(schedule) = schedule {
+        *mint.get_extension_mut::<PauseSchedule>()? = schedule;
+    }
+    emit_pause_state(
+        mint_account_info.key,
+        &config,
+        operations.as_ref(),
+        schedule.as_ref(),
+    );
     Ok(())
 }
 
+/// Schedule a pause and / or resume of all operations on the mint
+fn process_set_pause_schedule(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    pause_timestamp: &UnixTimestamp,
+    resume_timestamp: &UnixTimestamp,
+) -> ProgramResult {
+    let int_pause_timestamp = i64::from(*pause_timestamp);
+    let int_resume_timestamp = i64::from(*resume_timestamp);
+    if int_pause_timestamp < 0
+        || int_resume_timestamp < 0
+        || (int_pause_timestamp != 0
+            && int_resume_timestamp != 0
+            && int_resume_timestamp <= int_pause_timestamp)
+    {
+        return Err(TokenError::InvalidPauseSchedule.into());
+    }
+
+    update_pause_state(program_id, accounts, |config, operations, schedule| {
+        let schedule = schedule.ok_or(TokenError::ExtensionNotFound)?;
+        // the previous schedule was applied up to now before replacing it, so
+        // only apply any timestamp of the new schedule that has already passed
+        schedule.scheduled_pause_timestamp = *pause_timestamp;
+        schedule.scheduled_resume_timestamp = *resume_timestamp;
+        schedule.settle(config, operations, Clock::get()?.unix_timestamp);
+        Ok(())
+    })
+}
+
 pub(crate) fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -81,11 +188,44 @@ pub(crate) fn process_instruction(
         }
         PausableInstruction::Pause => {
             msg!("PausableInstruction::Pause");
-            process_toggle_pause(program_id, accounts, true /* pause */)
+            update_pause_state(program_id, accounts, |config, _, _| {
+                config.paused = true.into();
+                Ok(())
+            })
         }
         PausableInstruction::Resume => {
             msg!("PausableInstruction::Resume");
-            process_toggle_pause(program_id, accounts, false /* resume */)
+            update_pause_state(program_id, accounts, |config, operations, _| {
+                config.resume(operations);
+                Ok(())
+            })
+        }
+        PausableInstruction::SetPaused => {
+            msg!("PausableInstruction::SetPaused");
+            let SetPausedInstructionData { operation, paused } = decode_instruction_data(input)?;
+            let operation = PausableOperation::try_from(*operation)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            update_pause_state(program_id, accounts, |config, operations, _| {
+                let operations = operations.ok_or(TokenError::ExtensionNotFound)?;
+                config.set_paused(operations, operation, (*paused).into());
+                Ok(())
+            })
+        }
+        PausableInstruction::SetPauseSchedule => {
+            msg!("PausableInstruction::SetPauseSchedule");
+            let SetPauseScheduleInstructionData {
+                pause_timestamp,
+                resume_timestamp,
+            } = decode_instruction_data(input)?;
+            process_set_pause_schedule(program_id, accounts, pause_timestamp, resume_timestamp)
+        }
+        PausableInstruction::InitializeOperations => {
+            msg!("PausableInstruction::InitializeOperations");
+            process_initialize_operations(program_id, accounts)
+        }
+        PausableInstruction::InitializeSchedule => {
+            msg!("PausableInstruction::InitializeSchedule");
+            process_initialize_schedule(program_id, accounts)
         }
     }
 }
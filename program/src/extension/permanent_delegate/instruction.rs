@@ -0,0 +1,130 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        check_program_account,
+        extension::permanent_delegate::PermanentDelegateOperations,
+        instruction::{encode_instruction, TokenInstruction},
+    },
+    bytemuck::{Pod, Zeroable},
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+/// Permanent delegate extension instructions
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum PermanentDelegateInstruction {
+    /// Initialize the permanent delegate scope on a new mint, limiting the
+    /// permanent delegate to the given operations. Without this extension,
+    /// the permanent delegate may both transfer and burn tokens.
+    ///
+    /// Fails if the mint has already been initialized, so must be called before
+    /// `InitializeMint`. The permanent delegate itself is set with
+    /// `TokenInstruction::InitializePermanentDelegate`.
+    ///
+    /// The mint must have exactly enough space allocated for the base mint (82
+    /// bytes), plus 83 bytes of padding, 1 byte reserved for the account type,
+    /// then space required for this extension, plus any others.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to initialize.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::permanent_delegate::instruction::InitializeInstructionData`
+    Initialize,
+    /// Narrow the scope of operations the permanent delegate may perform.
+    ///
+    /// Fails if the new scope includes an operation outside of the current
+    /// one. The mint must have the `PermanentDelegateScope` extension, which
+    /// can be added to an initialized mint with `ReallocateMint`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The permanent delegate.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The mint's multisignature permanent delegate.
+    ///   2. `..2+M` `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::permanent_delegate::instruction::NarrowScopeInstructionData`
+    NarrowScope,
+}
+
+/// Data expected by `PermanentDelegateInstruction::Initialize`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct InitializeInstructionData {
+    /// The operations the delegate may perform, as a
+    /// `PermanentDelegateOperations`
+    pub scope: u8,
+}
+
+/// Data expected by `PermanentDelegateInstruction::NarrowScope`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct NarrowScopeInstructionData {
+    /// The new scope, as a `PermanentDelegateOperations`
+    pub scope: u8,
+}
+
+/// Create an `Initialize` instruction
+pub fn initialize(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    scope: PermanentDelegateOperations,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::PermanentDelegateExtension,
+        PermanentDelegateInstruction::Initialize,
+        &InitializeInstructionData {
+            scope: scope.into(),
+        },
+    ))
+}
+
+/// Create a `NarrowScope` instruction
+pub fn narrow_scope(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    delegate: &Pubkey,
+    signers: &[&Pubkey],
+    scope: PermanentDelegateOperations,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*delegate, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::PermanentDelegateExtension,
+        PermanentDelegateInstruction::NarrowScope,
+        &NarrowScopeInstructionData {
+            scope: scope.into(),
+        },
+    ))
+}
@@ -0,0 +1,140 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::extension::{BaseState, BaseStateWithExtensions, Extension, ExtensionType},
+    bytemuck::{Pod, Zeroable},
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{program_error::ProgramError, pubkey::Pubkey},
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+};
+
+/// Permanent delegate extension instructions
+pub mod instruction;
+
+/// Permanent delegate extension processor
+pub mod processor;
+
+/// Permanent delegate extension data for mints.
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct PermanentDelegate {
+    /// Optional permanent delegate for transferring or burning tokens
+    pub delegate: OptionalNonZeroPubkey,
+}
+impl Extension for PermanentDelegate {
+    const TYPE: ExtensionType = ExtensionType::PermanentDelegate;
+}
+
+/// Permanent delegate scope extension data for mints. Without it, the
+/// permanent delegate may both transfer and burn tokens.
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct PermanentDelegateScope {
+    /// The operations the delegate may perform, stored as a
+    /// [`PermanentDelegateOperations`]
+    pub scope: u8,
+}
+impl PermanentDelegateScope {
+    /// Get the operations the delegate may perform
+    pub fn scope(&self) -> Result<PermanentDelegateOperations, ProgramError> {
+        PermanentDelegateOperations::try_from(self.scope)
+            .map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+impl Extension for PermanentDelegateScope {
+    const TYPE: ExtensionType = ExtensionType::PermanentDelegateScope;
+}
+
+/// Operations a permanent delegate may perform on every account of the mint
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum PermanentDelegateOperations {
+    /// Transfer and burn tokens
+    #[default]
+    TransferAndBurn,
+    /// Only transfer tokens
+    Transfer,
+    /// Only burn tokens
+    Burn,
+}
+impl PermanentDelegateOperations {
+    /// Check if the scope covers transfers
+    pub fn allows_transfer(self) -> bool {
+        matches!(self, Self::TransferAndBurn | Self::Transfer)
+    }
+
+    /// Check if the scope covers burns
+    pub fn allows_burn(self) -> bool {
+        matches!(self, Self::TransferAndBurn | Self::Burn)
+    }
+
+    /// Check if the scope covers no operation outside of `other`
+    pub fn is_within(self, other: Self) -> bool {
+        (!self.allows_transfer() || other.allows_transfer())
+            && (!self.allows_burn() || other.allows_burn())
+    }
+}
+
+/// Attempts to get the permanent delegate from the TLV data, returning None
+/// if the extension is not found
+pub fn get_permanent_delegate<S: BaseState, BSE: BaseStateWithExtensions<S>>(
+    state: &BSE,
+) -> Option<Pubkey> {
+    state
+        .get_extension::<PermanentDelegate>()
+        .ok()
+        .and_then(|e| Option::<Pubkey>::from(e.delegate))
+}
+
+/// Get the operations the permanent delegate may perform, which are all of
+/// them if the scope extension is not found
+pub fn get_permanent_delegate_scope<S: BaseState, BSE: BaseStateWithExtensions<S>>(
+    state: &BSE,
+) -> Result<PermanentDelegateOperations, ProgramError> {
+    match state.get_extension::<PermanentDelegateScope>() {
+        Ok(extension) => extension.scope(),
+        Err(_) => Ok(PermanentDelegateOperations::default()),
+    }
+}
+
+/// Attempts to get the permanent delegate from the TLV data, returning None
+/// if the extension is not found or its scope doesn't cover transfers
+pub fn get_permanent_delegate_for_transfer<S: BaseState, BSE: BaseStateWithExtensions<S>>(
+    state: &BSE,
+) -> Option<Pubkey> {
+    get_permanent_delegate(state)
+        .filter(|_| get_permanent_delegate_scope(state).is_ok_and(|scope| scope.allows_transfer()))
+}
+
+/// Attempts to get the permanent delegate from the TLV data, returning None
+/// if the extension is not found or its scope doesn't cover burns
+pub fn get_permanent_delegate_for_burn<S: BaseState, BSE: BaseStateWithExtensions<S>>(
+    state: &BSE,
+) -> Option<Pubkey> {
+    get_permanent_delegate(state)
+        .filter(|_| get_permanent_delegate_scope(state).is_ok_and(|scope| scope.allows_burn()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_is_within() {
+        use PermanentDelegateOperations::*;
+        assert!(TransferAndBurn.is_within(TransferAndBurn));
+        assert!(Transfer.is_within(TransferAndBurn));
+        assert!(Burn.is_within(TransferAndBurn));
+        assert!(Transfer.is_within(Transfer));
+        assert!(!TransferAndBurn.is_within(Transfer));
+        assert!(!Burn.is_within(Transfer));
+        assert!(!TransferAndBurn.is_within(Burn));
+        assert!(!Transfer.is_within(Burn));
+    }
+}
@@ -0,0 +1,92 @@
+use {
+    crate::{
+        check_program_account,
+        error::TokenError,
+        extension::{
+            permanent_delegate::{
+                instruction::{
+                    InitializeInstructionData, NarrowScopeInstructionData,
+                    PermanentDelegateInstruction,
+                },
+                PermanentDelegate, PermanentDelegateOperations, PermanentDelegateScope,
+            },
+            BaseStateWithExtensions, BaseStateWithExtensionsMut, PodStateWithExtensionsMut,
+        },
+        instruction::{decode_instruction_data, decode_instruction_type},
+        pod::PodMint,
+        processor::Processor,
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        msg,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+fn decode_scope(scope: u8) -> Result<PermanentDelegateOperations, ProgramError> {
+    PermanentDelegateOperations::try_from(scope).map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+fn process_initialize(_program_id: &Pubkey, accounts: &[AccountInfo], scope: u8) -> ProgramResult {
+    let scope = decode_scope(scope)?;
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack_uninitialized(&mut mint_data)?;
+
+    let extension = mint.init_extension::<PermanentDelegateScope>(true)?;
+    extension.scope = scope.into();
+    Ok(())
+}
+
+fn process_narrow_scope(program_id: &Pubkey, accounts: &[AccountInfo], scope: u8) -> ProgramResult {
+    let new_scope = decode_scope(scope)?;
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack(&mut mint_data)?;
+    let delegate = Option::<Pubkey>::from(mint.get_extension::<PermanentDelegate>()?.delegate)
+        .ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &delegate,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    // holders rely on the scope, so the delegate can give up powers but never
+    // gain them back
+    let extension = mint.get_extension_mut::<PermanentDelegateScope>()?;
+    if !new_scope.is_within(extension.scope()?) {
+        return Err(TokenError::PermanentDelegateScopeWidened.into());
+    }
+    extension.scope = new_scope.into();
+    Ok(())
+}
+
+pub(crate) fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    check_program_account(program_id)?;
+    match decode_instruction_type(input)? {
+        PermanentDelegateInstruction::Initialize => {
+            msg!("PermanentDelegateInstruction::Initialize");
+            let InitializeInstructionData { scope } = decode_instruction_data(input)?;
+            process_initialize(program_id, accounts, *scope)
+        }
+        PermanentDelegateInstruction::NarrowScope => {
+            msg!("PermanentDelegateInstruction::NarrowScope");
+            let NarrowScopeInstructionData { scope } = decode_instruction_data(input)?;
+            process_narrow_scope(program_id, accounts, *scope)
+        }
+    }
+}
@@ -2,11 +2,27 @@ use {
     crate::{
         error::TokenError,
         extension::{
-            set_account_type, AccountType, BaseStateWithExtensions, ExtensionType,
-            StateWithExtensions, StateWithExtensionsMut,
+            confidential_transfer::ConfidentialTransferAccount,
+            confidential_transfer_fee::ConfidentialTransferFeeAmount,
+            cpi_guard::{CpiGuard, CpiGuardAllowlist},
+            delegate_expiry::DelegateExpiry,
+            group_member_pointer::GroupMemberPointer,
+            group_pointer::GroupPointer,
+            memo_transfer::{MemoRules, MemoTransfer, MemoTransferRules},
+            metadata_pointer::MetadataPointer,
+            multi_delegate::MultiDelegate,
+            permanent_delegate::PermanentDelegateScope,
+            refund_freed_rent,
+            scaled_ui_amount::ScaledUiAmountConfig,
+            set_account_type,
+            transfer_fee::{TransferFeeRecipient, TransferFeeTiers},
+            transfer_limit::TransferLimit,
+            AccountType, BaseState, BaseStateWithExtensions, BaseStateWithExtensionsMut,
+            ExtensionType, PodStateWithExtensionsMut, StateWithExtensions, StateWithExtensionsMut,
         },
+        pod::{PodAccount, PodCOption},
         processor::Processor,
-        state::Account,
+        state::{Account, Mint},
     },
     ethnum::U256,
     solana_program::{
@@ -19,6 +35,7 @@ use {
         system_instruction,
         sysvar::{rent::Rent, Sysvar},
     },
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
 };
 
 /// Processes a [Reallocate](enum.TokenInstruction.html) instruction
@@ -115,3 +132,292 @@ pub fn process_reallocate(
 
     Ok(())
 }
+
+/// Mint extensions that can be added once the mint is initialized, because
+/// they don't require anything from the token accounts already created.
+///
+/// The pausable extensions are left out: accounts created before them have no
+/// `PausableAccount`, so a pause could be bypassed through their transfers.
+fn is_addable_to_initialized_mint(extension_type: &ExtensionType) -> bool {
+    matches!(
+        extension_type,
+        ExtensionType::MetadataPointer
+            | ExtensionType::GroupPointer
+            | ExtensionType::GroupMemberPointer
+            | ExtensionType::TransferFeeTiers
+            | ExtensionType::TransferFeeRecipient
+            | ExtensionType::PermanentDelegateScope
+            | ExtensionType::ScaledUiAmount
+    )
+}
+
+/// Processes a [ReallocateMint](enum.TokenInstruction.html) instruction
+pub fn process_reallocate_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_extension_types: Vec<ExtensionType>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let authority_info_data_len = authority_info.data_len();
+
+    // check that account is the right type and validate mint authority
+    let (mut current_extension_types, mint_authority) = {
+        let mint_data = mint_info.data.borrow();
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+        let mint_authority = match mint.base.mint_authority {
+            COption::Some(mint_authority) => mint_authority,
+            COption::None => return Err(TokenError::NoAuthorityExists.into()),
+        };
+        Processor::validate_owner(
+            program_id,
+            &mint_authority,
+            authority_info,
+            authority_info_data_len,
+            account_info_iter.as_slice(),
+        )?;
+        (mint.get_extension_types()?, mint_authority)
+    };
+
+    // check that all desired extensions can be added to a live mint
+    if let Some(extension_type) = new_extension_types
+        .iter()
+        .find(|extension_type| !is_addable_to_initialized_mint(extension_type))
+    {
+        msg!(
+            "Extension {:?} cannot be added to an initialized mint",
+            extension_type
+        );
+        return Err(TokenError::InvalidState.into());
+    }
+    let mut added_extension_types = vec![];
+    for extension_type in new_extension_types {
+        if !current_extension_types.contains(&extension_type) {
+            current_extension_types.push(extension_type);
+            added_extension_types.push(extension_type);
+        }
+    }
+    ExtensionType::check_for_invalid_mint_extension_combinations(&current_extension_types)?;
+    let needed_account_len =
+        ExtensionType::try_calculate_account_len::<Mint>(&current_extension_types)?;
+
+    if mint_info.data_len() < needed_account_len {
+        msg!(
+            "mint needs realloc, +{:?} bytes",
+            needed_account_len - mint_info.data_len()
+        );
+        mint_info.realloc(needed_account_len, false)?;
+
+        // if additional lamports needed to remain rent-exempt, transfer them
+        let rent = Rent::get()?;
+        let lamports_diff = rent
+            .minimum_balance(needed_account_len)
+            .saturating_sub(mint_info.lamports());
+        if lamports_diff > 0 {
+            invoke(
+                &system_instruction::transfer(payer_info.key, mint_info.key, lamports_diff),
+                &[
+                    payer_info.clone(),
+                    mint_info.clone(),
+                    system_program_info.clone(),
+                ],
+            )?;
+        }
+    }
+
+    // set account_type, if needed, and initialize the new extensions
+    let mut mint_data = mint_info.data.borrow_mut();
+    set_account_type::<Mint>(&mut mint_data)?;
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let authority = OptionalNonZeroPubkey::try_from(Some(mint_authority))?;
+    for extension_type in added_extension_types {
+        match extension_type {
+            ExtensionType::MetadataPointer => {
+                mint.init_extension::<MetadataPointer>(false)?.authority = authority;
+            }
+            ExtensionType::GroupPointer => {
+                mint.init_extension::<GroupPointer>(false)?.authority = authority;
+            }
+            ExtensionType::GroupMemberPointer => {
+                mint.init_extension::<GroupMemberPointer>(false)?.authority = authority;
+            }
+            ExtensionType::TransferFeeTiers => {
+                mint.init_extension::<TransferFeeTiers>(false)?;
+            }
+            ExtensionType::TransferFeeRecipient => {
+                mint.init_extension::<TransferFeeRecipient>(false)?;
+            }
+            ExtensionType::PermanentDelegateScope => {
+                mint.init_extension::<PermanentDelegateScope>(false)?;
+            }
+            ExtensionType::ScaledUiAmount => {
+                let extension = mint.init_extension::<ScaledUiAmountConfig>(false)?;
+                extension.authority = authority;
+                extension.multiplier = 1.0.into();
+                extension.new_multiplier_effective_timestamp = 0.into();
+                extension.new_multiplier = 1.0.into();
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that an account extension is disabled or empty, so that removing it
+/// doesn't change how the account behaves
+pub fn check_account_extension_removable<S: BaseState>(
+    account: &impl BaseStateWithExtensions<S>,
+    extension_type: ExtensionType,
+) -> ProgramResult {
+    let in_use = match extension_type {
+        ExtensionType::MemoTransfer => bool::from(
+            account
+                .get_extension::<MemoTransfer>()?
+                .require_incoming_transfer_memos,
+        ),
+        ExtensionType::MemoTransferRules => {
+            let extension = account.get_extension::<MemoTransferRules>()?;
+            // the rules also apply to required incoming memos
+            bool::from(extension.require_outgoing_transfer_memos)
+                || extension.rules != MemoRules::default()
+        }
+        ExtensionType::CpiGuard => bool::from(account.get_extension::<CpiGuard>()?.lock_cpi),
+        ExtensionType::CpiGuardAllowlist => {
+            !account.get_extension::<CpiGuardAllowlist>()?.is_empty()
+        }
+        ExtensionType::TransferLimit => {
+            let extension = account.get_extension::<TransferLimit>()?;
+            // a pending limit may still take effect
+            extension.is_enabled() || i64::from(extension.pending_effective_timestamp) != 0
+        }
+        ExtensionType::DelegateExpiry => {
+            // removing an expiry would revive an expired delegation
+            i64::from(account.get_extension::<DelegateExpiry>()?.expires_at) != 0
+        }
+        ExtensionType::MultiDelegate => !account.get_extension::<MultiDelegate>()?.is_empty(),
+        ExtensionType::ConfidentialTransferAccount => {
+            account
+                .get_extension::<ConfidentialTransferAccount>()?
+                .closable()?;
+            false
+        }
+        ExtensionType::ConfidentialTransferFeeAmount => {
+            account
+                .get_extension::<ConfidentialTransferFeeAmount>()?
+                .closable()?;
+            false
+        }
+        _ => true,
+    };
+    if in_use {
+        Err(TokenError::ExtensionNotRemovable.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Processes a [RemoveAccountExtensions](enum.TokenInstruction.html)
+/// instruction
+pub fn process_remove_account_extensions(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    extension_types: Vec<ExtensionType>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let token_account_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let required_extension_types = Processor::get_required_account_extensions(mint_info)?;
+    let previous_account_len = token_account_info.try_data_len()?;
+    let (new_account_len, native_rent_exempt_reserve) = {
+        let mut token_account_data = token_account_info.data.borrow_mut();
+        let mut token_account =
+            PodStateWithExtensionsMut::<PodAccount>::unpack(&mut token_account_data)?;
+        if token_account.base.mint != *mint_info.key {
+            return Err(TokenError::MintMismatch.into());
+        }
+        Processor::validate_owner(
+            program_id,
+            &token_account.base.owner,
+            owner_info,
+            owner_info_data_len,
+            account_info_iter.as_slice(),
+        )?;
+
+        let mut removed_extension_types = vec![];
+        for extension_type in extension_types {
+            if removed_extension_types.contains(&extension_type) {
+                continue;
+            }
+            if required_extension_types.contains(&extension_type) {
+                msg!("Extension {:?} is required by the mint", extension_type);
+                return Err(TokenError::ExtensionNotRemovable.into());
+            }
+            check_account_extension_removable(&token_account, extension_type)?;
+            match extension_type {
+                ExtensionType::MemoTransfer => token_account.remove_extension::<MemoTransfer>()?,
+                ExtensionType::MemoTransferRules => {
+                    token_account.remove_extension::<MemoTransferRules>()?
+                }
+                ExtensionType::CpiGuard => token_account.remove_extension::<CpiGuard>()?,
+                ExtensionType::CpiGuardAllowlist => {
+                    token_account.remove_extension::<CpiGuardAllowlist>()?
+                }
+                ExtensionType::TransferLimit => {
+                    token_account.remove_extension::<TransferLimit>()?
+                }
+                ExtensionType::DelegateExpiry => {
+                    token_account.remove_extension::<DelegateExpiry>()?
+                }
+                ExtensionType::MultiDelegate => {
+                    token_account.remove_extension::<MultiDelegate>()?
+                }
+                ExtensionType::ConfidentialTransferAccount => {
+                    token_account.remove_extension::<ConfidentialTransferAccount>()?
+                }
+                ExtensionType::ConfidentialTransferFeeAmount => {
+                    token_account.remove_extension::<ConfidentialTransferFeeAmount>()?
+                }
+                _ => return Err(TokenError::ExtensionNotRemovable.into()),
+            }
+            removed_extension_types.push(extension_type);
+        }
+
+        let native_rent_exempt_reserve = token_account.base.is_native.ok_or(()).ok();
+        (
+            token_account.try_get_account_len()?,
+            native_rent_exempt_reserve,
+        )
+    };
+
+    if new_account_len < previous_account_len {
+        msg!(
+            "account shrinks, -{:?} bytes",
+            previous_account_len - new_account_len
+        );
+        token_account_info.realloc(new_account_len, false)?;
+        let starting_lamports = token_account_info.lamports();
+        refund_freed_rent(token_account_info, owner_info, previous_account_len)?;
+
+        // the refund comes out of the rent exempt reserve of native accounts
+        if let Some(rent_exempt_reserve) = native_rent_exempt_reserve {
+            let refund_amount = starting_lamports.saturating_sub(token_account_info.lamports());
+            let mut token_account_data = token_account_info.data.borrow_mut();
+            let mut token_account =
+                PodStateWithExtensionsMut::<PodAccount>::unpack(&mut token_account_data)?;
+            token_account.base.is_native = PodCOption::some(
+                u64::from(rent_exempt_reserve)
+                    .saturating_sub(refund_amount)
+                    .into(),
+            );
+        }
+    }
+
+    Ok(())
+}
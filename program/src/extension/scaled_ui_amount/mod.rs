@@ -1,10 +1,10 @@
-use ethnum::{AsU256, U256};
+use ethnum::U256;
 #[cfg(feature = "serde-traits")]
 use serde::{Deserialize, Serialize};
 use {
     crate::{
         extension::{Extension, ExtensionType},
-        trim_ui_amount_string,
+        ui_amount::UiAmountScale,
     },
     bytemuck::{Pod, Zeroable},
     solana_program::program_error::ProgramError,
@@ -58,13 +58,17 @@ pub struct ScaledUiAmountConfig {
     pub new_multiplier: PodF64,
 }
 impl ScaledUiAmountConfig {
-    fn total_multiplier(&self, decimals: u8, unix_timestamp: i64) -> f64 {
+    fn current_multiplier(&self, unix_timestamp: i64) -> f64 {
         let multiplier = if unix_timestamp >= self.new_multiplier_effective_timestamp.into() {
             self.new_multiplier
         } else {
             self.multiplier
         };
-        f64::from(multiplier) / 10_f64.powi(decimals as i32)
+        f64::from(multiplier)
+    }
+
+    fn scale(&self, unix_timestamp: i64) -> Option<UiAmountScale> {
+        UiAmountScale::from_f64(self.current_multiplier(unix_timestamp))
     }
 
     /// Convert a raw amount to its UI representation using the given decimals
@@ -75,9 +79,8 @@ impl ScaledUiAmountConfig {
         decimals: u8,
         unix_timestamp: i64,
     ) -> Option<String> {
-        let scaled_amount = amount.as_f64() * self.total_multiplier(decimals, unix_timestamp);
-        let ui_amount = format!("{scaled_amount:.*}", decimals as usize);
-        Some(trim_ui_amount_string(ui_amount, decimals))
+        let scale = self.scale(unix_timestamp)?;
+        Some(scale.amount_to_ui_amount(amount, decimals))
     }
 
     /// Try to convert a UI representation of a token amount to its raw amount
@@ -88,17 +91,9 @@ impl ScaledUiAmountConfig {
         decimals: u8,
         unix_timestamp: i64,
     ) -> Result<U256, ProgramError> {
-        let scaled_amount = ui_amount
-            .parse::<f64>()
-            .map_err(|_| ProgramError::InvalidArgument)?;
-        let amount = scaled_amount / self.total_multiplier(decimals, unix_timestamp);
-        if amount > (U256::MAX.as_f64()) || amount < (U256::MIN.as_f64()) || amount.is_nan() {
-            Err(ProgramError::InvalidArgument)
-        } else {
-            // this is important, if you round earlier, you'll get wrong "inf"
-            // answers
-            Ok(amount.round().as_u256())
-        }
+        self.scale(unix_timestamp)
+            .ok_or(ProgramError::InvalidArgument)?
+            .try_ui_amount_into_amount(ui_amount, decimals)
     }
 }
 impl Extension for ScaledUiAmountConfig {
@@ -125,16 +120,16 @@ mod tests {
             ),
         };
         assert_eq!(
-            config.total_multiplier(0, new_multiplier_effective_timestamp),
+            config.current_multiplier(new_multiplier_effective_timestamp),
             new_multiplier
         );
         assert_eq!(
-            config.total_multiplier(0, new_multiplier_effective_timestamp - 1),
+            config.current_multiplier(new_multiplier_effective_timestamp - 1),
             multiplier
         );
-        assert_eq!(config.total_multiplier(0, 0), multiplier);
-        assert_eq!(config.total_multiplier(0, i64::MIN), multiplier);
-        assert_eq!(config.total_multiplier(0, i64::MAX), new_multiplier);
+        assert_eq!(config.current_multiplier(0), multiplier);
+        assert_eq!(config.current_multiplier(i64::MIN), multiplier);
+        assert_eq!(config.current_multiplier(i64::MAX), new_multiplier);
     }
 
     #[test]
@@ -170,8 +165,32 @@ mod tests {
             new_multiplier_effective_timestamp: UnixTimestamp::from(1),
             ..Default::default()
         };
+        // no precision is lost, even for the largest values
         let ui_amount = config.amount_to_ui_amount(U256::MAX, 0, 0).unwrap();
-        assert_eq!(ui_amount, "inf");
+        assert_eq!(ui_amount, "20815864389328795852825288403957631545631339212474106592359418955705714247813673243450439556277238052778008020598246863424243169758204316083063379931967105666562571937950791458983336666590159097421318801571584992756124993592986565873318693551980212522852077330512495585056909259339707446319046550902197347677485484932120411771576690158078972476301279401205243486835121164510654711726080");
+
+        // every digit of a huge amount is kept
+        let config = ScaledUiAmountConfig {
+            authority: OptionalNonZeroPubkey::default(),
+            multiplier: PodF64::from(1.0),
+            new_multiplier_effective_timestamp: UnixTimestamp::from(1),
+            ..Default::default()
+        };
+        let ui_amount = config.amount_to_ui_amount(U256::MAX, 18, 0).unwrap();
+        assert_eq!(
+            ui_amount,
+            "115792089237316195423570985008687907853269984665640564039457.584007913129639935"
+        );
+
+        // scales below one show extra digits, so that the raw amount can be recovered
+        let config = ScaledUiAmountConfig {
+            authority: OptionalNonZeroPubkey::default(),
+            multiplier: PodF64::from(0.5),
+            new_multiplier_effective_timestamp: UnixTimestamp::from(1),
+            ..Default::default()
+        };
+        let ui_amount = config.amount_to_ui_amount(U256::from(3_u64), 2, 0).unwrap();
+        assert_eq!(ui_amount, "0.015");
     }
 
     #[test]
@@ -212,6 +231,14 @@ mod tests {
         let amount = config
             .try_ui_amount_into_amount("92233720368547758075", 0, 0)
             .unwrap();
+        assert_eq!(amount, u64::MAX as u128);
+        let amount = config
+            .try_ui_amount_into_amount(
+                "578960446186580977117854925043439539266349923328202820197287920039565648199675",
+                0,
+                0,
+            )
+            .unwrap();
         assert_eq!(amount, U256::MAX);
         let config = ScaledUiAmountConfig {
             authority: OptionalNonZeroPubkey::default(),
@@ -230,17 +257,18 @@ mod tests {
             new_multiplier_effective_timestamp: UnixTimestamp::from(1),
             ..Default::default()
         };
+        // the division is exact, so this is the nearest raw amount
         let amount = config
             .try_ui_amount_into_amount("1.7976931348623157e308", 0, 0)
             .unwrap();
-        assert_eq!(amount, U256::MAX);
+        assert_eq!(amount, 18_446_744_073_709_551_532_u128);
         // scientific notation "E"
         let amount = config
             .try_ui_amount_into_amount("1.7976931348623157E308", 0, 0)
             .unwrap();
-        assert_eq!(amount, U256::MAX);
+        assert_eq!(amount, 18_446_744_073_709_551_532_u128);
 
-        // this is unfortunate, but underflows can happen due to floats
+        // the full range is available without any rounding
         let config = ScaledUiAmountConfig {
             authority: OptionalNonZeroPubkey::default(),
             multiplier: 1.0.into(),
@@ -250,11 +278,23 @@ mod tests {
         assert_eq!(
             U256::MAX,
             config
-                .try_ui_amount_into_amount("115792089237316195423570985008687907853269984665640564039457584007913129639935", 0, 0)
-                .unwrap() // u64::MAX + 1
+                .try_ui_amount_into_amount(
+                    "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+                    0,
+                    0
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            Err(ProgramError::InvalidArgument),
+            config.try_ui_amount_into_amount(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639936", // U256::MAX + 1
+                0,
+                0
+            )
         );
 
-        // overflow u64 fail
+        // overflow U256 fail
         let config = ScaledUiAmountConfig {
             authority: OptionalNonZeroPubkey::default(),
             multiplier: 0.1.into(),
@@ -263,10 +303,14 @@ mod tests {
         };
         assert_eq!(
             Err(ProgramError::InvalidArgument),
-            config.try_ui_amount_into_amount("18446744073709551615", 0, 0) // u64::MAX + 1
+            config.try_ui_amount_into_amount(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+                0,
+                0
+            )
         );
 
-        for fail_ui_amount in ["-0.0000000000000000000001", "inf", "-inf", "NaN"] {
+        for fail_ui_amount in ["-0.0000000000000000000001", "inf", "-inf", "NaN", "1e99999"] {
             assert_eq!(
                 Err(ProgramError::InvalidArgument),
                 config.try_ui_amount_into_amount(fail_ui_amount, 0, 0)
@@ -339,7 +383,7 @@ mod tests {
         #[test]
         fn amount_to_ui_amount(
             scale in 0f64..=f64::MAX,
-            amount in 0..=u64::MAX, // TODO: change to U256::MAX
+            (high, low) in (any::<u128>(), any::<u128>()),
             decimals in 0u8..20u8,
         ) {
             let config = ScaledUiAmountConfig {
@@ -348,8 +392,15 @@ mod tests {
                 new_multiplier_effective_timestamp: UnixTimestamp::from(1),
                 ..Default::default()
             };
-            let ui_amount = config.amount_to_ui_amount(U256::from(amount), decimals, 0);
+            let amount = U256::from_words(high, low);
+            let ui_amount = config.amount_to_ui_amount(amount, decimals, 0);
             assert!(ui_amount.is_some());
+            if scale > 0.0 {
+                let round_trip = config
+                    .try_ui_amount_into_amount(&ui_amount.unwrap(), decimals, 0)
+                    .unwrap();
+                assert_eq!(round_trip, amount);
+            }
         }
     }
 }
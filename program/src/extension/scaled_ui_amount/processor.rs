@@ -2,6 +2,7 @@ use {
     crate::{
         check_program_account,
         error::TokenError,
+        event::{self, UpdateMultiplierEvent},
         extension::{
             scaled_ui_amount::{
                 instruction::{
@@ -96,6 +97,13 @@ fn process_update_multiplier(
     if clock.unix_timestamp >= int_effective_timestamp {
         extension.multiplier = *new_multiplier;
     }
+
+    event::emit(&UpdateMultiplierEvent {
+        mint: *mint_account_info.key,
+        multiplier: extension.multiplier,
+        new_multiplier: extension.new_multiplier,
+        effective_timestamp: extension.new_multiplier_effective_timestamp,
+    });
     Ok(())
 }
 
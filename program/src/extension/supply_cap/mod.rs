@@ -0,0 +1,43 @@
+use ethnum::U256;
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        error::TokenError,
+        extension::{Extension, ExtensionType},
+    },
+    bytemuck::{Pod, Zeroable},
+    solana_program::entrypoint::ProgramResult,
+    spl_pod::{optional_keys::OptionalNonZeroPubkey, primitives::PodU256},
+};
+
+/// Supply cap extension instructions
+pub mod instruction;
+
+/// Supply cap extension processor
+pub mod processor;
+
+/// Hard cap on the supply of a mint
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SupplyCapConfig {
+    /// Authority that can lower the maximum supply
+    pub authority: OptionalNonZeroPubkey,
+    /// Maximum supply of the mint
+    pub max_supply: PodU256,
+}
+impl SupplyCapConfig {
+    /// Check that the given supply does not exceed the cap
+    pub fn check_supply(&self, supply: U256) -> ProgramResult {
+        if supply > U256::from(self.max_supply) {
+            Err(TokenError::MaxSupplyExceeded.into())
+        } else {
+            Ok(())
+        }
+    }
+}
+impl Extension for SupplyCapConfig {
+    const TYPE: ExtensionType = ExtensionType::SupplyCap;
+}
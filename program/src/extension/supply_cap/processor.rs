@@ -0,0 +1,100 @@
+use {
+    crate::{
+        check_program_account,
+        error::TokenError,
+        extension::{
+            supply_cap::{
+                instruction::{
+                    InitializeInstructionData, SupplyCapInstruction, UpdateMaxSupplyInstructionData,
+                },
+                SupplyCapConfig,
+            },
+            BaseStateWithExtensionsMut, PodStateWithExtensionsMut,
+        },
+        instruction::{decode_instruction_data, decode_instruction_type},
+        pod::PodMint,
+        processor::Processor,
+    },
+    ethnum::U256,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        msg,
+        pubkey::Pubkey,
+    },
+    spl_pod::{optional_keys::OptionalNonZeroPubkey, primitives::PodU256},
+};
+
+fn process_initialize(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    authority: &OptionalNonZeroPubkey,
+    max_supply: &PodU256,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack_uninitialized(&mut mint_data)?;
+
+    let extension = mint.init_extension::<SupplyCapConfig>(true)?;
+    extension.authority = *authority;
+    extension.max_supply = *max_supply;
+    Ok(())
+}
+
+fn process_update_max_supply(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_max_supply: &PodU256,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack(&mut mint_data)?;
+    let supply = U256::from(mint.base.supply);
+    let extension = mint.get_extension_mut::<SupplyCapConfig>()?;
+    let authority =
+        Option::<Pubkey>::from(extension.authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    // the cap can only ever be lowered, so holders can rely on it
+    if U256::from(*new_max_supply) > U256::from(extension.max_supply) {
+        return Err(TokenError::MaxSupplyIncrease.into());
+    }
+    extension.max_supply = *new_max_supply;
+    extension.check_supply(supply)?;
+    Ok(())
+}
+
+pub(crate) fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    check_program_account(program_id)?;
+    match decode_instruction_type(input)? {
+        SupplyCapInstruction::Initialize => {
+            msg!("SupplyCapInstruction::Initialize");
+            let InitializeInstructionData {
+                authority,
+                max_supply,
+            } = decode_instruction_data(input)?;
+            process_initialize(program_id, accounts, authority, max_supply)
+        }
+        SupplyCapInstruction::UpdateMaxSupply => {
+            msg!("SupplyCapInstruction::UpdateMaxSupply");
+            let UpdateMaxSupplyInstructionData { max_supply } = decode_instruction_data(input)?;
+            process_update_max_supply(program_id, accounts, max_supply)
+        }
+    }
+}
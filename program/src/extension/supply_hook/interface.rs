@@ -0,0 +1,191 @@
+//! Interface implemented by supply hook programs
+//!
+//! On every mint and burn, the token program invokes the hook program's
+//! `Execute` instruction. The instruction has its own discriminator and
+//! validation account, so a program can implement both the transfer hook and
+//! the supply hook interfaces without mixing up the two.
+
+use {
+    crate::{
+        check_program_account,
+        extension::{supply_hook::SupplyHook, BaseStateWithExtensions, PodStateWithExtensions},
+        pod::PodMint,
+    },
+    ethnum::U256,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        program::invoke,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    spl_discriminator::{ArrayDiscriminator, SplDiscriminate},
+    spl_pod::primitives::PodU256,
+    spl_tlv_account_resolution::state::ExtraAccountMetaList,
+};
+
+/// Seed for the validation account holding the supply hook's extra account
+/// metas for a mint
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"supply-hook-extra-account-metas";
+
+/// `Execute` instruction of a supply hook program.
+///
+/// Accounts expected by this instruction:
+///
+///   0. `[]` Source account, the mint on mints
+///   1. `[]` Token mint
+///   2. `[]` Destination account, the mint on burns
+///   3. `[]` Source account's owner/delegate, or the mint authority on mints
+///   4. `[]` Validation account
+///   5. `..5+M` `[]` `M` additional accounts, written in validation account
+///      data
+///
+/// Data expected by this instruction: the discriminator, followed by the
+/// amount as a little-endian `u256`.
+#[derive(SplDiscriminate)]
+#[discriminator_hash_input("spl-token-2022-supply-hook-interface:execute")]
+pub struct ExecuteInstruction;
+
+/// Get the address of the validation account holding the extra account metas
+/// for a mint and supply hook program
+pub fn get_extra_account_metas_address(mint: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    get_extra_account_metas_address_and_bump_seed(mint, program_id).0
+}
+
+/// Get the address and bump seed of the validation account holding the extra
+/// account metas for a mint and supply hook program
+pub fn get_extra_account_metas_address_and_bump_seed(
+    mint: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&collect_extra_account_metas_seeds(mint), program_id)
+}
+
+/// Get the seeds of the validation account for a mint, without the bump
+pub fn collect_extra_account_metas_seeds(mint: &Pubkey) -> [&[u8]; 2] {
+    [EXTRA_ACCOUNT_METAS_SEED, mint.as_ref()]
+}
+
+/// Create an `Execute` instruction for a supply hook program
+pub fn execute(
+    program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    amount: U256,
+) -> Instruction {
+    let mut data = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE.to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(&PodU256::from(amount)));
+    let accounts = vec![
+        AccountMeta::new_readonly(*source_pubkey, false),
+        AccountMeta::new_readonly(*mint_pubkey, false),
+        AccountMeta::new_readonly(*destination_pubkey, false),
+        AccountMeta::new_readonly(*authority_pubkey, false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Unpack the amount of an `Execute` instruction, failing if the data is for
+/// any other instruction
+pub fn decode_execute(input: &[u8]) -> Result<U256, ProgramError> {
+    let (discriminator, amount) = input
+        .split_at_checked(ArrayDiscriminator::LENGTH)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    if discriminator != ExecuteInstruction::SPL_DISCRIMINATOR_SLICE {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    amount
+        .try_into()
+        .map(U256::from_le_bytes)
+        .map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+/// Check that the token program is executing the supply hook of the mint.
+///
+/// Supply hook programs should call this on the mint they receive, since only
+/// the token program can set the flag, and only for the duration of the
+/// `Execute` CPI during a mint or burn.
+pub fn is_executing(mint_info: &AccountInfo) -> Result<bool, ProgramError> {
+    check_program_account(mint_info.owner)?;
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)?;
+    Ok(mint.get_extension::<SupplyHook>()?.executing.into())
+}
+
+/// Invoke the supply hook program's `Execute` instruction, resolving the extra
+/// account metas from the validation account if it's provided
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn invoke_execute<'a>(
+    program_id: &Pubkey,
+    source_info: AccountInfo<'a>,
+    mint_info: AccountInfo<'a>,
+    destination_info: AccountInfo<'a>,
+    authority_info: AccountInfo<'a>,
+    additional_accounts: &[AccountInfo<'a>],
+    amount: U256,
+) -> ProgramResult {
+    let validation_pubkey = get_extra_account_metas_address(mint_info.key, program_id);
+    let mut cpi_instruction = execute(
+        program_id,
+        source_info.key,
+        mint_info.key,
+        destination_info.key,
+        authority_info.key,
+        amount,
+    );
+    let mut cpi_account_infos = vec![source_info, mint_info, destination_info, authority_info];
+
+    if let Some(validation_info) = additional_accounts
+        .iter()
+        .find(|&x| *x.key == validation_pubkey)
+    {
+        cpi_instruction
+            .accounts
+            .push(AccountMeta::new_readonly(validation_pubkey, false));
+        cpi_account_infos.push(validation_info.clone());
+
+        ExtraAccountMetaList::add_to_cpi_instruction::<ExecuteInstruction>(
+            &mut cpi_instruction,
+            &mut cpi_account_infos,
+            &validation_info.try_borrow_data()?,
+            additional_accounts,
+        )?;
+    }
+
+    invoke(&cpi_instruction, &cpi_account_infos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execute_round_trip() {
+        let amount = U256::from(u64::MAX) + 1;
+        let instruction = execute(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            amount,
+        );
+        assert_eq!(decode_execute(&instruction.data), Ok(amount));
+
+        // the transfer hook's execute instruction is rejected
+        let mut data = instruction.data;
+        data[..ArrayDiscriminator::LENGTH].copy_from_slice(
+            spl_transfer_hook_interface::instruction::ExecuteInstruction::SPL_DISCRIMINATOR_SLICE,
+        );
+        assert_eq!(
+            decode_execute(&data),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        extension::{
+            BaseState, BaseStateWithExtensions, BaseStateWithExtensionsMut, Extension,
+            ExtensionType, PodStateWithExtensionsMut,
+        },
+        pod::PodMint,
+    },
+    bytemuck::{Pod, Zeroable},
+    solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey},
+    spl_pod::{optional_keys::OptionalNonZeroPubkey, primitives::PodBool},
+};
+
+/// Instructions for the `SupplyHook` extension
+pub mod instruction;
+/// Interface implemented by supply hook programs
+pub mod interface;
+/// Instruction processor for the `SupplyHook` extension
+pub mod processor;
+
+/// Supply hook extension data for mints.
+///
+/// The hook program is invoked through the supply hook interface's `Execute`
+/// instruction on every mint and burn. On mints, the mint is passed as the
+/// source account, and on burns, it's passed as the destination account.
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SupplyHook {
+    /// Authority that can set the supply hook program id
+    pub authority: OptionalNonZeroPubkey,
+    /// Program that authorizes mints and burns
+    pub program_id: OptionalNonZeroPubkey,
+    /// Flag to indicate that the mint is in the middle of a mint or burn,
+    /// letting the hook program check that the token program invoked it
+    pub executing: PodBool,
+}
+
+impl Extension for SupplyHook {
+    const TYPE: ExtensionType = ExtensionType::SupplyHook;
+}
+
+/// Attempts to get the supply hook program id from the TLV data, returning
+/// None if the extension is not found
+pub fn get_program_id<S: BaseState, BSE: BaseStateWithExtensions<S>>(
+    state: &BSE,
+) -> Option<Pubkey> {
+    state
+        .get_extension::<SupplyHook>()
+        .ok()
+        .and_then(|e| Option::<Pubkey>::from(e.program_id))
+}
+
+/// Helper function to set the executing flag before calling into the supply
+/// hook
+pub fn set_executing<BSE: BaseStateWithExtensionsMut<S>, S: BaseState>(
+    mint: &mut BSE,
+) -> Result<(), ProgramError> {
+    let extension = mint.get_extension_mut::<SupplyHook>()?;
+    extension.executing = true.into();
+    Ok(())
+}
+
+/// Helper function to unset the executing flag after a mint or burn
+pub fn unset_executing(mint_info: &AccountInfo) -> Result<(), ProgramError> {
+    let mut mint_data = mint_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<SupplyHook>()?;
+    extension.executing = false.into();
+    Ok(())
+}
@@ -0,0 +1,80 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        check_program_account,
+        instruction::{encode_instruction, TokenInstruction},
+    },
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+/// Token group instructions that are not part of the token-group interface
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum TokenGroupExtensionInstruction {
+    /// Remove a member from a group.
+    ///
+    /// Removes the `TokenGroupMember` extension from the member mint, shrinking
+    /// the mint account, and decrements the size of the group. If a refund
+    /// destination is provided, the rent freed by the smaller mint account is
+    /// transferred to it, which must be approved by the member mint authority.
+    /// Otherwise the freed rent stays on the member mint.
+    ///
+    /// The first removal from a group adds a `TokenGroupMemberCounter`
+    /// extension to the group mint, so that member numbers are never reused.
+    /// The group mint must already hold enough lamports to be rent-exempt
+    /// with the extra space.
+    ///
+    /// The instruction must be signed by either the update authority of the
+    /// group or the mint authority of the member mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The member mint.
+    ///   1. `[writable]` The group mint.
+    ///   2. `[signer]` The group update authority or the member mint authority.
+    ///   3. `[writable]` (Optional) The destination for the rent freed by the
+    ///      smaller member mint account.
+    ///   4. `[signer]` The member mint authority, required with a refund
+    ///      destination.
+    ///   5. `..5+M` `[signer]` M signer accounts, if the member mint authority
+    ///      is a multisig.
+    RemoveMember,
+}
+
+/// Create a `RemoveMember` instruction
+///
+/// `refund` is the destination of the freed rent and the member mint
+/// authority approving it
+pub fn remove_member(
+    token_program_id: &Pubkey,
+    member_mint: &Pubkey,
+    group_mint: &Pubkey,
+    authority: &Pubkey,
+    refund: Option<(&Pubkey, &Pubkey)>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*member_mint, false),
+        AccountMeta::new(*group_mint, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+    if let Some((refund_destination, mint_authority)) = refund {
+        accounts.push(AccountMeta::new(*refund_destination, false));
+        accounts.push(AccountMeta::new_readonly(*mint_authority, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::TokenGroupExtension,
+        TokenGroupExtensionInstruction::RemoveMember,
+        &(),
+    ))
+}
@@ -1,8 +1,18 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
 use {
-    crate::extension::{Extension, ExtensionType},
+    crate::{
+        error::TokenError,
+        extension::{Extension, ExtensionType},
+    },
+    bytemuck::{Pod, Zeroable},
+    solana_program::program_error::ProgramError,
+    spl_pod::primitives::PodU64,
     spl_token_group_interface::state::{TokenGroup, TokenGroupMember},
 };
 
+/// Instruction types for the `TokenGroup` extension
+pub mod instruction;
 /// Instruction processor for the `TokenGroup` extension
 pub mod processor;
 
@@ -13,3 +23,29 @@ impl Extension for TokenGroup {
 impl Extension for TokenGroupMember {
     const TYPE: ExtensionType = ExtensionType::TokenGroupMember;
 }
+
+/// Counter of the member numbers handed out by a group, added to the group
+/// mint when a member is first removed. Since the group size goes down on
+/// removal, it can no longer be used to number new members.
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct TokenGroupMemberCounter {
+    /// Member number given to the next member of the group
+    pub next_member_number: PodU64,
+}
+impl TokenGroupMemberCounter {
+    /// Take the next member number, so that it's never handed out again
+    pub fn take_next_member_number(&mut self) -> Result<u64, ProgramError> {
+        let member_number = u64::from(self.next_member_number);
+        self.next_member_number = member_number
+            .checked_add(1)
+            .ok_or(TokenError::Overflow)?
+            .into();
+        Ok(member_number)
+    }
+}
+impl Extension for TokenGroupMemberCounter {
+    const TYPE: ExtensionType = ExtensionType::TokenGroupMemberCounter;
+}
@@ -5,10 +5,15 @@ use {
         check_program_account,
         error::TokenError,
         extension::{
-            alloc_and_serialize, group_member_pointer::GroupMemberPointer,
-            group_pointer::GroupPointer, BaseStateWithExtensions, BaseStateWithExtensionsMut,
-            PodStateWithExtensions, PodStateWithExtensionsMut,
+            alloc_and_serialize,
+            group_member_pointer::GroupMemberPointer,
+            group_pointer::GroupPointer,
+            refund_freed_mint_rent, remove_and_dealloc,
+            token_group::{instruction::TokenGroupExtensionInstruction, TokenGroupMemberCounter},
+            BaseStateWithExtensions, BaseStateWithExtensionsMut, PodStateWithExtensions,
+            PodStateWithExtensionsMut,
         },
+        instruction::decode_instruction_type,
         pod::{PodCOption, PodMint},
     },
     solana_program::{
@@ -196,7 +201,14 @@ pub fn process_initialize_member(_program_id: &Pubkey, accounts: &[AccountInfo])
     let group = state.get_extension_mut::<TokenGroup>()?;
 
     check_update_authority(group_update_authority_info, &group.update_authority)?;
-    let member_number = group.increment_size()?;
+    let size = group.increment_size()?;
+
+    // Once a member was removed, the size may match a number still in use
+    let member_number = if let Ok(counter) = state.get_extension_mut::<TokenGroupMemberCounter>() {
+        counter.take_next_member_number()?
+    } else {
+        size
+    };
 
     // Allocate a TLV entry for the space and write it in
     let member = TokenGroupMember::new(member_mint_info.key, group_info.key, member_number);
@@ -205,6 +217,103 @@ pub fn process_initialize_member(_program_id: &Pubkey, accounts: &[AccountInfo])
     Ok(())
 }
 
+/// Processes a
+/// [`RemoveMember`](enum.TokenGroupExtensionInstruction.html)
+/// instruction
+pub fn process_remove_member(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let member_mint_info = next_account_info(account_info_iter)?;
+    let group_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+
+    if member_mint_info.key == group_info.key {
+        return Err(TokenGroupError::MemberAccountIsGroupAccount.into());
+    }
+    if !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // scope the member checks, since the member extension is removed afterwards
+    let is_member_mint_authority = {
+        check_program_account(member_mint_info.owner)?;
+        let member_mint_data = member_mint_info.try_borrow_data()?;
+        let member_mint = PodStateWithExtensions::<PodMint>::unpack(&member_mint_data)?;
+        let member = member_mint.get_extension::<TokenGroupMember>()?;
+        if member.group != *group_info.key {
+            msg!("Group member does not belong to the given group");
+            return Err(TokenError::MintMismatch.into());
+        }
+        member_mint.base.mint_authority == PodCOption::some(*authority_info.key)
+    };
+
+    // Decrement the size of the group
+    let next_member_number = {
+        check_program_account(group_info.owner)?;
+        let mut buffer = group_info.try_borrow_mut_data()?;
+        let mut state = PodStateWithExtensionsMut::<PodMint>::unpack(&mut buffer)?;
+        let group = state.get_extension_mut::<TokenGroup>()?;
+
+        let is_group_update_authority =
+            Option::<Pubkey>::from(group.update_authority) == Some(*authority_info.key);
+        if !is_member_mint_authority && !is_group_update_authority {
+            return Err(TokenGroupError::IncorrectUpdateAuthority.into());
+        }
+
+        let size = u64::from(group.size);
+        group.size = size.checked_sub(1).ok_or(TokenError::Overflow)?.into();
+
+        // Without a counter, members were numbered from 1 to the size
+        if state.get_extension::<TokenGroupMemberCounter>().is_ok() {
+            None
+        } else {
+            Some(size.checked_add(1).ok_or(TokenError::Overflow)?)
+        }
+    };
+
+    // Keep handing out new member numbers, since the decremented size may
+    // match a number that is still in use
+    if let Some(next_member_number) = next_member_number {
+        alloc_and_serialize::<PodMint, TokenGroupMemberCounter>(
+            group_info,
+            &TokenGroupMemberCounter {
+                next_member_number: next_member_number.into(),
+            },
+            false,
+        )?;
+    }
+
+    let previous_account_len = member_mint_info.try_data_len()?;
+    remove_and_dealloc::<PodMint, TokenGroupMember>(member_mint_info)?;
+
+    // Refund the rent freed by a smaller account, if a destination is provided
+    refund_freed_mint_rent(
+        program_id,
+        member_mint_info,
+        account_info_iter.as_slice(),
+        previous_account_len,
+    )?;
+
+    Ok(())
+}
+
+/// Processes a token group instruction that is not part of the token-group
+/// interface
+pub(crate) fn process_extension_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    check_program_account(program_id)?;
+
+    match decode_instruction_type(input)? {
+        TokenGroupExtensionInstruction::RemoveMember => {
+            msg!("TokenGroupExtensionInstruction::RemoveMember");
+            process_remove_member(program_id, accounts)
+        }
+    }
+}
+
 /// Processes an [`Instruction`](enum.Instruction.html).
 pub fn process_instruction(
     program_id: &Pubkey,
@@ -6,7 +6,7 @@ use {
         error::TokenError,
         extension::{
             alloc_and_serialize_variable_len_extension, metadata_pointer::MetadataPointer,
-            BaseStateWithExtensions, PodStateWithExtensions,
+            refund_freed_mint_rent, BaseStateWithExtensions, PodStateWithExtensions,
         },
         pod::{PodCOption, PodMint},
     },
@@ -108,8 +108,12 @@ pub fn process_initialize(
 
 /// Processes an [`UpdateField`](enum.TokenMetadataInstruction.html)
 /// instruction.
+///
+/// Along with the interface accounts, an optional writable account may be
+/// provided after the update authority to receive the rent freed if the
+/// account shrinks, followed by the mint authority and any multisig signers.
 pub fn process_update_field(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     data: UpdateField,
 ) -> ProgramResult {
@@ -131,14 +135,27 @@ pub fn process_update_field(
     token_metadata.update(data.field, data.value);
 
     // Update / realloc the account
+    let previous_account_len = metadata_info.try_data_len()?;
     alloc_and_serialize_variable_len_extension::<PodMint, _>(metadata_info, &token_metadata, true)?;
 
+    // Refund the rent freed by a smaller account, if a destination is provided
+    refund_freed_mint_rent(
+        program_id,
+        metadata_info,
+        account_info_iter.as_slice(),
+        previous_account_len,
+    )?;
+
     Ok(())
 }
 
 /// Processes a [`RemoveKey`](enum.TokenMetadataInstruction.html) instruction.
+///
+/// Along with the interface accounts, an optional writable account may be
+/// provided after the update authority to receive the rent freed by the smaller
+/// account, followed by the mint authority and any multisig signers.
 pub fn process_remove_key(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     data: RemoveKey,
 ) -> ProgramResult {
@@ -158,7 +175,16 @@ pub fn process_remove_key(
     if !token_metadata.remove_key(&data.key) && !data.idempotent {
         return Err(TokenMetadataError::KeyNotFound.into());
     }
+    let previous_account_len = metadata_info.try_data_len()?;
     alloc_and_serialize_variable_len_extension::<PodMint, _>(metadata_info, &token_metadata, true)?;
+
+    // Refund the rent freed by a smaller account, if a destination is provided
+    refund_freed_mint_rent(
+        program_id,
+        metadata_info,
+        account_info_iter.as_slice(),
+        previous_account_len,
+    )?;
     Ok(())
 }
 
@@ -4,7 +4,12 @@ use {
     serde::{Deserialize, Serialize},
 };
 use {
-    crate::{check_program_account, error::TokenError, instruction::TokenInstruction},
+    crate::{
+        check_program_account,
+        error::TokenError,
+        extension::transfer_fee::{TransferFeeTier, MAX_TRANSFER_FEE_TIERS},
+        instruction::TokenInstruction,
+    },
     ethnum::U256,
     solana_program::{
         instruction::{AccountMeta, Instruction},
@@ -78,7 +83,8 @@ pub enum TransferFeeInstruction {
         decimals: u8,
         /// Expected fee assessed on this transfer, calculated off-chain based
         /// on the `transfer_fee_basis_points` and `maximum_fee` of the mint.
-        /// May be 0 for a mint without a configured transfer fee.
+        /// May be 0 for a mint without a configured transfer fee, or when
+        /// the source or destination account is fee-exempt.
         fee: U256,
     },
     /// Transfer all withheld tokens in the mint to an account. Signed by the
@@ -155,6 +161,109 @@ pub enum TransferFeeInstruction {
         /// Maximum fee assessed on transfers
         maximum_fee: U256,
     },
+    /// Set a tiered transfer fee, where each amount bracket charges its rate,
+    /// up to its cap, on the part of a transfer amount that falls in the
+    /// bracket. Only supported for mints that include the `TransferFeeConfig`
+    /// extension.
+    ///
+    /// Like `SetTransferFee`, the new fee takes effect two epochs later.
+    /// Fails if the mint does not have the `TransferFeeTiers` extension.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The mint's fee account owner.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The mint's multisignature fee account owner.
+    ///   2. `..2+M` `[signer]` M signer accounts.
+    SetTieredTransferFee {
+        /// Amount of transfer collected as fees below the first tier,
+        /// expressed as basis points of the transfer amount
+        transfer_fee_basis_points: u16,
+        /// Maximum fee assessed on transfers below the first tier
+        maximum_fee: U256,
+        /// Brackets for larger transfers, sorted by strictly increasing
+        /// minimum amount, with zeroed unused tiers at the end
+        tiers: [TransferFeeTier; MAX_TRANSFER_FEE_TIERS],
+    },
+    /// Exempt a token account from transfer fees, or revoke its exemption.
+    /// No fee is assessed on transfers to or from an exempt account. Signed
+    /// by the mint's transfer fee config authority.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The token account. Must include the
+    ///      `TransferFeeExemption` extension or have room for it, which its
+    ///      owner may add with `Reallocate`.
+    ///   1. `[]` The token mint. Must include the `TransferFeeConfig`
+    ///      extension.
+    ///   2. `[signer]` The mint's `transfer_fee_config_authority`.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The token account.
+    ///   1. `[]` The token mint.
+    ///   2. `[]` The mint's multisig `transfer_fee_config_authority`.
+    ///   3. `..3+M` `[signer]` M signer accounts.
+    SetTransferFeeExemption {
+        /// Whether the account is exempt from transfer fees
+        fee_exempt: bool,
+    },
+    /// Set or clear the token account that withheld tokens in the mint may be
+    /// swept to. Signed by the mint's withdraw withheld tokens authority.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The token mint. Must include the `TransferFeeConfig`
+    ///      and `TransferFeeRecipient` extensions.
+    ///   1. `[signer]` The mint's `withdraw_withheld_authority`.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The token mint.
+    ///   1. `[]` The mint's multisig `withdraw_withheld_authority`.
+    ///   2. `..2+M` `[signer]` M signer accounts.
+    SetFeeRecipient {
+        /// Token account to receive swept fees, or `None` to disable sweeping
+        #[cfg_attr(feature = "serde-traits", serde(with = "coption_fromstr"))]
+        fee_recipient: COption<Pubkey>,
+    },
+    /// Permissionless instruction to transfer all withheld tokens in the mint
+    /// to the mint's configured fee recipient.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The token mint. Must include the `TransferFeeConfig`
+    ///      and `TransferFeeRecipient` extensions.
+    ///   1. `[writable]` The fee recipient account.
+    SweepWithheldTokensToFeeRecipient,
+    /// Initialize the transfer fee tiers on a mint with the
+    /// `TransferFeeConfig` extension, which allow `SetTieredTransferFee` to
+    /// charge larger transfers at different rates.
+    ///
+    /// Fails if the mint has already been initialized, so must be called
+    /// before `InitializeMint`. The extension may also be added to an
+    /// initialized mint with `ReallocateMint`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to initialize.
+    InitializeTransferFeeTiers,
+    /// Initialize the fee recipient on a mint with the `TransferFeeConfig`
+    /// extension, which allows `SetFeeRecipient` to configure an account
+    /// that withheld tokens may be swept to.
+    ///
+    /// Fails if the mint has already been initialized, so must be called
+    /// before `InitializeMint`. The extension may also be added to an
+    /// initialized mint with `ReallocateMint`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to initialize.
+    InitializeTransferFeeRecipient,
 }
 impl TransferFeeInstruction {
     /// Unpacks a byte buffer into a `TransferFeeInstruction`
@@ -200,6 +309,44 @@ impl TransferFeeInstruction {
                     maximum_fee,
                 }
             }
+            6 => {
+                let (transfer_fee_basis_points, rest) = TokenInstruction::unpack_u16(rest)?;
+                let (maximum_fee, mut rest) = TokenInstruction::unpack_u256(rest)?;
+                let mut tiers = [TransferFeeTier::default(); MAX_TRANSFER_FEE_TIERS];
+                for tier in tiers.iter_mut() {
+                    let (minimum_amount, tier_rest) = TokenInstruction::unpack_u256(rest)?;
+                    let (tier_maximum_fee, tier_rest) = TokenInstruction::unpack_u256(tier_rest)?;
+                    let (tier_transfer_fee_basis_points, tier_rest) =
+                        TokenInstruction::unpack_u16(tier_rest)?;
+                    *tier = TransferFeeTier {
+                        minimum_amount: minimum_amount.into(),
+                        maximum_fee: tier_maximum_fee.into(),
+                        transfer_fee_basis_points: tier_transfer_fee_basis_points.into(),
+                    };
+                    rest = tier_rest;
+                }
+                Self::SetTieredTransferFee {
+                    transfer_fee_basis_points,
+                    maximum_fee,
+                    tiers,
+                }
+            }
+            7 => {
+                let (&fee_exempt, _) = rest.split_first().ok_or(InvalidInstruction)?;
+                let fee_exempt = match fee_exempt {
+                    0 => false,
+                    1 => true,
+                    _ => return Err(InvalidInstruction.into()),
+                };
+                Self::SetTransferFeeExemption { fee_exempt }
+            }
+            8 => {
+                let (fee_recipient, _) = TokenInstruction::unpack_pubkey_option(rest)?;
+                Self::SetFeeRecipient { fee_recipient }
+            }
+            9 => Self::SweepWithheldTokensToFeeRecipient,
+            10 => Self::InitializeTransferFeeTiers,
+            11 => Self::InitializeTransferFeeRecipient,
             _ => return Err(TokenError::InvalidInstruction.into()),
         })
     }
@@ -247,6 +394,39 @@ impl TransferFeeInstruction {
                 buffer.extend_from_slice(&transfer_fee_basis_points.to_le_bytes());
                 buffer.extend_from_slice(&maximum_fee.to_le_bytes());
             }
+            Self::SetTieredTransferFee {
+                transfer_fee_basis_points,
+                maximum_fee,
+                ref tiers,
+            } => {
+                buffer.push(6);
+                buffer.extend_from_slice(&transfer_fee_basis_points.to_le_bytes());
+                buffer.extend_from_slice(&maximum_fee.to_le_bytes());
+                for tier in tiers.iter() {
+                    buffer.extend_from_slice(&U256::from(tier.minimum_amount).to_le_bytes());
+                    buffer.extend_from_slice(&U256::from(tier.maximum_fee).to_le_bytes());
+                    buffer.extend_from_slice(
+                        &u16::from(tier.transfer_fee_basis_points).to_le_bytes(),
+                    );
+                }
+            }
+            Self::SetTransferFeeExemption { fee_exempt } => {
+                buffer.push(7);
+                buffer.push(fee_exempt.into());
+            }
+            Self::SetFeeRecipient { ref fee_recipient } => {
+                buffer.push(8);
+                TokenInstruction::pack_pubkey_option(fee_recipient, buffer);
+            }
+            Self::SweepWithheldTokensToFeeRecipient => {
+                buffer.push(9);
+            }
+            Self::InitializeTransferFeeTiers => {
+                buffer.push(10);
+            }
+            Self::InitializeTransferFeeRecipient => {
+                buffer.push(11);
+            }
         }
     }
 }
@@ -319,6 +499,34 @@ pub fn transfer_checked_with_fee(
     })
 }
 
+/// Create a `InitializeTransferFeeTiers` instruction
+pub fn initialize_transfer_fee_tiers(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts: vec![AccountMeta::new(*mint, false)],
+        data: encode_instruction_data(TransferFeeInstruction::InitializeTransferFeeTiers),
+    })
+}
+
+/// Create a `InitializeTransferFeeRecipient` instruction
+pub fn initialize_transfer_fee_recipient(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts: vec![AccountMeta::new(*mint, false)],
+        data: encode_instruction_data(TransferFeeInstruction::InitializeTransferFeeRecipient),
+    })
+}
+
 /// Creates a `WithdrawWithheldTokensFromMint` instruction
 pub fn withdraw_withheld_tokens_from_mint(
     token_program_id: &Pubkey,
@@ -421,6 +629,114 @@ pub fn set_transfer_fee(
     })
 }
 
+/// Creates a `SetTieredTransferFee` instruction
+///
+/// Fails if more than `MAX_TRANSFER_FEE_TIERS` tiers are provided.
+pub fn set_tiered_transfer_fee(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    transfer_fee_basis_points: u16,
+    maximum_fee: U256,
+    tiers: &[TransferFeeTier],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    if tiers.len() > MAX_TRANSFER_FEE_TIERS {
+        return Err(TokenError::InvalidTransferFeeTiers.into());
+    }
+    let mut padded_tiers = [TransferFeeTier::default(); MAX_TRANSFER_FEE_TIERS];
+    padded_tiers[..tiers.len()].copy_from_slice(tiers);
+
+    let mut accounts = Vec::with_capacity(2 + signers.len());
+    accounts.push(AccountMeta::new(*mint, false));
+    accounts.push(AccountMeta::new_readonly(*authority, signers.is_empty()));
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data: encode_instruction_data(TransferFeeInstruction::SetTieredTransferFee {
+            transfer_fee_basis_points,
+            maximum_fee,
+            tiers: padded_tiers,
+        }),
+    })
+}
+
+/// Creates a `SetTransferFeeExemption` instruction
+pub fn set_transfer_fee_exemption(
+    token_program_id: &Pubkey,
+    account: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    fee_exempt: bool,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = Vec::with_capacity(3 + signers.len());
+    accounts.push(AccountMeta::new(*account, false));
+    accounts.push(AccountMeta::new_readonly(*mint, false));
+    accounts.push(AccountMeta::new_readonly(*authority, signers.is_empty()));
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data: encode_instruction_data(TransferFeeInstruction::SetTransferFeeExemption {
+            fee_exempt,
+        }),
+    })
+}
+
+/// Creates a `SetFeeRecipient` instruction
+pub fn set_fee_recipient(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    fee_recipient: Option<&Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = Vec::with_capacity(2 + signers.len());
+    accounts.push(AccountMeta::new(*mint, false));
+    accounts.push(AccountMeta::new_readonly(*authority, signers.is_empty()));
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data: encode_instruction_data(TransferFeeInstruction::SetFeeRecipient {
+            fee_recipient: fee_recipient.cloned().into(),
+        }),
+    })
+}
+
+/// Creates a `SweepWithheldTokensToFeeRecipient` instruction
+pub fn sweep_withheld_tokens_to_fee_recipient(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    fee_recipient: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new(*fee_recipient, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data: encode_instruction_data(TransferFeeInstruction::SweepWithheldTokensToFeeRecipient),
+    })
+}
+
 #[cfg(test)]
 mod test {
     use ethnum::AsU256;
@@ -499,5 +815,75 @@ mod test {
         assert_eq!(packed, expect);
         let unpacked = TransferFeeInstruction::unpack(&expect).unwrap();
         assert_eq!(unpacked, check);
+
+        let tier = TransferFeeTier {
+            minimum_amount: U256::from(1_000u64).into(),
+            maximum_fee: U256::MAX.into(),
+            transfer_fee_basis_points: 42.into(),
+        };
+        let check = TransferFeeInstruction::SetTieredTransferFee {
+            transfer_fee_basis_points: u16::MAX,
+            maximum_fee: U256::MAX,
+            tiers: [tier, TransferFeeTier::default(), TransferFeeTier::default()],
+        };
+        let mut packed = vec![];
+        check.pack(&mut packed);
+        let mut expect = vec![6];
+        expect.extend_from_slice(&u16::MAX.to_le_bytes());
+        expect.extend_from_slice(&U256::MAX.to_le_bytes());
+        expect.extend_from_slice(&1_000.as_u256().to_le_bytes());
+        expect.extend_from_slice(&U256::MAX.to_le_bytes());
+        expect.extend_from_slice(&42u16.to_le_bytes());
+        expect.extend_from_slice(&[0u8; 2 * (32 + 32 + 2)]);
+        assert_eq!(packed, expect);
+        let unpacked = TransferFeeInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TransferFeeInstruction::SetTransferFeeExemption { fee_exempt: true };
+        let mut packed = vec![];
+        check.pack(&mut packed);
+        let expect = [7, 1];
+        assert_eq!(packed, expect);
+        let unpacked = TransferFeeInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+        assert_eq!(
+            TransferFeeInstruction::unpack(&[7, 2]),
+            Err(TokenError::InvalidInstruction.into())
+        );
+
+        let check = TransferFeeInstruction::SetFeeRecipient {
+            fee_recipient: COption::Some(Pubkey::new_from_array([12u8; 32])),
+        };
+        let mut packed = vec![];
+        check.pack(&mut packed);
+        let mut expect = vec![8, 1];
+        expect.extend_from_slice(&[12u8; 32]);
+        assert_eq!(packed, expect);
+        let unpacked = TransferFeeInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TransferFeeInstruction::SweepWithheldTokensToFeeRecipient;
+        let mut packed = vec![];
+        check.pack(&mut packed);
+        let expect = [9];
+        assert_eq!(packed, expect);
+        let unpacked = TransferFeeInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TransferFeeInstruction::InitializeTransferFeeTiers;
+        let mut packed = vec![];
+        check.pack(&mut packed);
+        let expect = [10];
+        assert_eq!(packed, expect);
+        let unpacked = TransferFeeInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TransferFeeInstruction::InitializeTransferFeeRecipient;
+        let mut packed = vec![];
+        check.pack(&mut packed);
+        let expect = [11];
+        assert_eq!(packed, expect);
+        let unpacked = TransferFeeInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
     }
 }
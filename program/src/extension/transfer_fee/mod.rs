@@ -5,13 +5,13 @@ use spl_pod::primitives::PodU256;
 use {
     crate::{
         error::TokenError,
-        extension::{Extension, ExtensionType},
+        extension::{BaseState, BaseStateWithExtensions, Extension, ExtensionType},
     },
     bytemuck::{Pod, Zeroable},
     solana_program::{clock::Epoch, entrypoint::ProgramResult},
     spl_pod::{
         optional_keys::OptionalNonZeroPubkey,
-        primitives::{PodU16, PodU64},
+        primitives::{PodBool, PodU16, PodU64},
     },
     std::{cmp, convert::TryInto},
 };
@@ -26,6 +26,27 @@ pub mod processor;
 pub const MAX_FEE_BASIS_POINTS: u16 = 10_000;
 const ONE_IN_BASIS_POINTS: u128 = MAX_FEE_BASIS_POINTS as u128;
 
+/// Maximum number of tiers above the base rate of a transfer fee
+pub const MAX_TRANSFER_FEE_TIERS: usize = 3;
+
+/// Transfer fee bracket for transfers of at least `minimum_amount`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct TransferFeeTier {
+    /// Smallest transfer amount that the tier applies to. A zero amount marks
+    /// an unused tier.
+    pub minimum_amount: PodU256,
+    /// Maximum fee assessed on the part of transfers in the tier, expressed
+    /// as an amount of tokens
+    pub maximum_fee: PodU256,
+    /// Amount of transfer collected as fees in the tier, expressed as basis
+    /// points of the part of the transfer amount in the tier (increments of
+    /// `0.01%`)
+    pub transfer_fee_basis_points: PodU16,
+}
+
 /// Transfer fee information
 #[repr(C)]
 #[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
@@ -129,6 +150,151 @@ impl TransferFee {
     }
 }
 
+/// Transfer fee of a mint for an epoch, along with its tiers
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TieredTransferFee {
+    /// Fee charged on transfers below the first tier
+    pub transfer_fee: TransferFee,
+    /// Brackets for larger transfers, sorted by strictly increasing
+    /// `minimum_amount`, with unused tiers at the end. Each bracket charges
+    /// its rate, up to its maximum fee, on the part of a transfer amount that
+    /// falls in the bracket, so the net amount never decreases as the
+    /// transfer amount grows.
+    pub tiers: [TransferFeeTier; MAX_TRANSFER_FEE_TIERS],
+}
+impl From<TransferFee> for TieredTransferFee {
+    fn from(transfer_fee: TransferFee) -> Self {
+        Self {
+            transfer_fee,
+            tiers: Default::default(),
+        }
+    }
+}
+impl TieredTransferFee {
+    /// Get the tiers in use
+    pub fn active_tiers(&self) -> impl Iterator<Item = &TransferFeeTier> {
+        self.tiers
+            .iter()
+            .take_while(|tier| U256::from(tier.minimum_amount) != U256::ZERO)
+    }
+
+    /// Check if the fee has any tiers above its base rate
+    pub fn is_tiered(&self) -> bool {
+        self.active_tiers().next().is_some()
+    }
+
+    /// Get the brackets of the fee as `(minimum_amount, transfer_fee)`,
+    /// starting with the base rate at 0
+    fn brackets(&self) -> impl Iterator<Item = (U256, TransferFee)> + '_ {
+        std::iter::once((U256::ZERO, self.transfer_fee)).chain(self.active_tiers().map(|tier| {
+            (
+                U256::from(tier.minimum_amount),
+                TransferFee {
+                    epoch: self.transfer_fee.epoch,
+                    maximum_fee: tier.maximum_fee,
+                    transfer_fee_basis_points: tier.transfer_fee_basis_points,
+                },
+            )
+        }))
+    }
+
+    /// Get the brackets of the fee as `(minimum_amount, next_minimum_amount,
+    /// transfer_fee)`, where the last bracket has no upper bound
+    fn bounded_brackets(&self) -> impl Iterator<Item = (U256, Option<U256>, TransferFee)> + '_ {
+        let next_minimum_amounts = self
+            .brackets()
+            .skip(1)
+            .map(|(minimum_amount, _)| Some(minimum_amount))
+            .chain(std::iter::once(None));
+        self.brackets().zip(next_minimum_amounts).map(
+            |((minimum_amount, transfer_fee), next_minimum_amount)| {
+                (minimum_amount, next_minimum_amount, transfer_fee)
+            },
+        )
+    }
+
+    /// Check that every rate is valid and that the tiers are sorted
+    pub fn validate(&self) -> ProgramResult {
+        if self.brackets().any(|(_, transfer_fee)| {
+            u16::from(transfer_fee.transfer_fee_basis_points) > MAX_FEE_BASIS_POINTS
+        }) {
+            return Err(TokenError::TransferFeeExceedsMaximum.into());
+        }
+        let active_tier_count = self.active_tiers().count();
+        let sorted = self
+            .brackets()
+            .zip(self.brackets().skip(1))
+            .all(|(lower, upper)| lower.0 < upper.0);
+        let unused_tiers_cleared = self.tiers[active_tier_count..]
+            .iter()
+            .all(|tier| *tier == TransferFeeTier::default());
+        if sorted && unused_tiers_cleared {
+            Ok(())
+        } else {
+            Err(TokenError::InvalidTransferFeeTiers.into())
+        }
+    }
+
+    /// Calculate the transfer fee, as the sum of the fees of each bracket on
+    /// the part of the amount that falls in it
+    pub fn calculate_fee(&self, pre_fee_amount: U256) -> Option<U256> {
+        let mut fee = U256::ZERO;
+        for (minimum_amount, next_minimum_amount, transfer_fee) in self.bounded_brackets() {
+            if pre_fee_amount <= minimum_amount {
+                break;
+            }
+            let bracket_end = next_minimum_amount.map_or(pre_fee_amount, |next_minimum_amount| {
+                cmp::min(next_minimum_amount, pre_fee_amount)
+            });
+            let bracket_fee =
+                transfer_fee.calculate_fee(bracket_end.checked_sub(minimum_amount)?)?;
+            fee = fee.checked_add(bracket_fee)?;
+        }
+        Some(fee)
+    }
+
+    /// Calculate the gross transfer amount after deducting fees
+    pub fn calculate_post_fee_amount(&self, pre_fee_amount: U256) -> Option<U256> {
+        let fee = self.calculate_fee(pre_fee_amount)?;
+        pre_fee_amount.checked_sub(fee)
+    }
+
+    /// Calculate the smallest transfer amount that will result in a specified
+    /// net transfer amount, see `TransferFee::calculate_pre_fee_amount`.
+    ///
+    /// Since the net amount never decreases as the transfer amount grows, the
+    /// first bracket that reaches the net amount gives the smallest transfer
+    /// amount. If the transfer amount overflows, `None` is returned.
+    pub fn calculate_pre_fee_amount(&self, post_fee_amount: U256) -> Option<U256> {
+        // fee charged on the full brackets below the current one
+        let mut lower_fee = U256::ZERO;
+        for (minimum_amount, next_minimum_amount, transfer_fee) in self.bounded_brackets() {
+            let bracket_len = next_minimum_amount
+                .map(|next_minimum_amount| next_minimum_amount.saturating_sub(minimum_amount));
+            // net amount to reach on the part of the transfer in the bracket
+            let bracket_pre_fee_amount = post_fee_amount
+                .checked_add(lower_fee)
+                .and_then(|amount| amount.checked_sub(minimum_amount))
+                .and_then(|amount| transfer_fee.calculate_pre_fee_amount(amount))
+                .filter(|amount| bracket_len.map_or(true, |bracket_len| *amount < bracket_len));
+            if let Some(bracket_pre_fee_amount) = bracket_pre_fee_amount {
+                return minimum_amount.checked_add(bracket_pre_fee_amount);
+            }
+            if let Some(bracket_len) = bracket_len {
+                lower_fee = lower_fee.checked_add(transfer_fee.calculate_fee(bracket_len)?)?;
+            }
+        }
+        None
+    }
+
+    /// Calculate the fee that would produce the given output, see
+    /// `TransferFee::calculate_inverse_fee`
+    pub fn calculate_inverse_fee(&self, post_fee_amount: U256) -> Option<U256> {
+        let pre_fee_amount = self.calculate_pre_fee_amount(post_fee_amount)?;
+        self.calculate_fee(pre_fee_amount)
+    }
+}
+
 /// Transfer fee extension data for mints.
 #[repr(C)]
 #[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
@@ -156,6 +322,28 @@ impl TransferFeeConfig {
             &self.older_transfer_fee
         }
     }
+    /// Get the fee for the given epoch, along with its tiers from the mint's
+    /// `TransferFeeTiers` extension, if any
+    pub fn get_tiered_epoch_fee(
+        &self,
+        tiers: Option<&TransferFeeTiers>,
+        epoch: Epoch,
+    ) -> TieredTransferFee {
+        let transfer_fee = *self.get_epoch_fee(epoch);
+        let tiers = tiers
+            .map(|tiers| {
+                if epoch >= self.newer_transfer_fee.epoch.into() {
+                    tiers.newer_tiers
+                } else {
+                    tiers.older_tiers
+                }
+            })
+            .unwrap_or_default();
+        TieredTransferFee {
+            transfer_fee,
+            tiers,
+        }
+    }
     /// Calculate the fee for the given epoch and input amount
     pub fn calculate_epoch_fee(&self, epoch: Epoch, pre_fee_amount: U256) -> Option<U256> {
         self.get_epoch_fee(epoch).calculate_fee(pre_fee_amount)
@@ -167,11 +355,62 @@ impl TransferFeeConfig {
             .calculate_inverse_fee(post_fee_u64)
             .map(U256::from)
     }
+    /// Calculate the fee for the given epoch and input amount, along with the
+    /// tiers from the mint's `TransferFeeTiers` extension, if any
+    pub fn calculate_tiered_epoch_fee(
+        &self,
+        tiers: Option<&TransferFeeTiers>,
+        epoch: Epoch,
+        pre_fee_amount: U256,
+    ) -> Option<U256> {
+        self.get_tiered_epoch_fee(tiers, epoch)
+            .calculate_fee(pre_fee_amount)
+    }
+    /// Calculate the fee for the given epoch and output amount, along with the
+    /// tiers from the mint's `TransferFeeTiers` extension, if any
+    pub fn calculate_inverse_tiered_epoch_fee(
+        &self,
+        tiers: Option<&TransferFeeTiers>,
+        epoch: Epoch,
+        post_fee_amount: U256,
+    ) -> Option<U256> {
+        self.get_tiered_epoch_fee(tiers, epoch)
+            .calculate_inverse_fee(post_fee_amount)
+    }
 }
 impl Extension for TransferFeeConfig {
     const TYPE: ExtensionType = ExtensionType::TransferFeeConfig;
 }
 
+/// Tiers of the transfer fees of a mint, for larger transfers
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct TransferFeeTiers {
+    /// Tiers of `TransferFeeConfig::older_transfer_fee`
+    pub older_tiers: [TransferFeeTier; MAX_TRANSFER_FEE_TIERS],
+    /// Tiers of `TransferFeeConfig::newer_transfer_fee`
+    pub newer_tiers: [TransferFeeTier; MAX_TRANSFER_FEE_TIERS],
+}
+impl Extension for TransferFeeTiers {
+    const TYPE: ExtensionType = ExtensionType::TransferFeeTiers;
+}
+
+/// Token account that anyone may sweep the withheld fees of a mint to
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct TransferFeeRecipient {
+    /// Optional token account that withheld fees may be swept to, set by the
+    /// withdraw withheld authority
+    pub fee_recipient: OptionalNonZeroPubkey,
+}
+impl Extension for TransferFeeRecipient {
+    const TYPE: ExtensionType = ExtensionType::TransferFeeRecipient;
+}
+
 /// Transfer fee extension data for accounts.
 #[repr(C)]
 #[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
@@ -195,6 +434,29 @@ impl Extension for TransferFeeAmount {
     const TYPE: ExtensionType = ExtensionType::TransferFeeAmount;
 }
 
+/// Transfer fee exemption extension data for accounts, set by the mint's
+/// transfer fee config authority
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct TransferFeeExemption {
+    /// Whether transfers to or from the account are exempt from transfer
+    /// fees
+    pub fee_exempt: PodBool,
+}
+impl Extension for TransferFeeExemption {
+    const TYPE: ExtensionType = ExtensionType::TransferFeeExemption;
+}
+
+/// Check if an account is exempt from transfer fees
+pub fn is_fee_exempt<BSE: BaseStateWithExtensions<S>, S: BaseState>(account_state: &BSE) -> bool {
+    account_state
+        .get_extension::<TransferFeeExemption>()
+        .map(|extension| bool::from(extension.fee_exempt))
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 pub(crate) mod test {
     use {super::*, proptest::prelude::*, solana_program::pubkey::Pubkey, std::convert::TryFrom};
@@ -226,6 +488,11 @@ pub(crate) mod test {
         }
     }
 
+    #[test]
+    fn transfer_fee_amount_layout() {
+        assert_eq!(std::mem::size_of::<TransferFeeAmount>(), 8);
+    }
+
     #[test]
     fn epoch_fee() {
         let transfer_fee_config = test_transfer_fee_config();
@@ -515,4 +782,203 @@ pub(crate) mod test {
             assert!(fee.as_u64() >= fee_exact_out);
         }
     }
+
+    fn test_tiered_transfer_fee() -> TieredTransferFee {
+        TieredTransferFee {
+            transfer_fee: TransferFee {
+                epoch: PodU64::from(0),
+                maximum_fee: PodU256::from(1_000),
+                transfer_fee_basis_points: PodU16::from(500),
+            },
+            tiers: [
+                TransferFeeTier {
+                    minimum_amount: PodU256::from(100),
+                    maximum_fee: PodU256::from(1_000),
+                    transfer_fee_basis_points: PodU16::from(100),
+                },
+                TransferFeeTier {
+                    minimum_amount: PodU256::from(1_000),
+                    maximum_fee: PodU256::from(5),
+                    transfer_fee_basis_points: PodU16::from(3_000),
+                },
+                TransferFeeTier::default(),
+            ],
+        }
+    }
+
+    #[test]
+    fn calculate_tiered_fee() {
+        let transfer_fee = test_tiered_transfer_fee();
+        assert!(transfer_fee.is_tiered());
+        transfer_fee.validate().unwrap();
+
+        // base rate below the first tier
+        assert_eq!(transfer_fee.calculate_fee(U256::from(99u64)).unwrap(), 5);
+        // each tier only charges the part of the amount above its minimum
+        assert_eq!(transfer_fee.calculate_fee(U256::from(100u64)).unwrap(), 5);
+        assert_eq!(transfer_fee.calculate_fee(U256::from(101u64)).unwrap(), 6);
+        assert_eq!(transfer_fee.calculate_fee(U256::from(999u64)).unwrap(), 14);
+        assert_eq!(
+            transfer_fee.calculate_fee(U256::from(1_000u64)).unwrap(),
+            14
+        );
+        assert_eq!(
+            transfer_fee.calculate_fee(U256::from(1_001u64)).unwrap(),
+            15
+        );
+        // capped in the last tier
+        assert_eq!(transfer_fee.calculate_fee(U256::MAX).unwrap(), 19);
+
+        // 94 is only reached at the base rate
+        assert_eq!(
+            transfer_fee.calculate_pre_fee_amount(U256::from(94u64)),
+            Some(U256::from(99u64))
+        );
+        // 95 is first reached at the start of the first tier
+        assert_eq!(
+            transfer_fee.calculate_pre_fee_amount(U256::from(95u64)),
+            Some(U256::from(100u64))
+        );
+        // 985 is reached at the end of the first tier, 986 at the start of
+        // the last tier
+        assert_eq!(
+            transfer_fee.calculate_pre_fee_amount(U256::from(985u64)),
+            Some(U256::from(999u64))
+        );
+        assert_eq!(
+            transfer_fee.calculate_pre_fee_amount(U256::from(986u64)),
+            Some(U256::from(1_000u64))
+        );
+        assert_eq!(
+            transfer_fee.calculate_inverse_fee(U256::from(986u64)),
+            Some(U256::from(14u64))
+        );
+    }
+
+    #[test]
+    fn tiered_fee_at_tier_boundaries() {
+        let transfer_fee = test_tiered_transfer_fee();
+        for tier in transfer_fee.active_tiers() {
+            let minimum_amount = U256::from(tier.minimum_amount);
+            let post_fee_amounts = [
+                minimum_amount - U256::ONE,
+                minimum_amount,
+                minimum_amount + U256::ONE,
+            ]
+            .map(|amount| transfer_fee.calculate_post_fee_amount(amount).unwrap());
+            // the net amount never drops when entering a tier
+            assert!(post_fee_amounts[0] <= post_fee_amounts[1]);
+            assert!(post_fee_amounts[1] <= post_fee_amounts[2]);
+            // and every net amount around the boundary can be reached
+            let mut post_fee_amount = post_fee_amounts[0];
+            while post_fee_amount <= post_fee_amounts[2] {
+                let pre_fee_amount = transfer_fee
+                    .calculate_pre_fee_amount(post_fee_amount)
+                    .unwrap();
+                assert_eq!(
+                    transfer_fee.calculate_post_fee_amount(pre_fee_amount),
+                    Some(post_fee_amount)
+                );
+                post_fee_amount += U256::ONE;
+            }
+        }
+    }
+
+    #[test]
+    fn validate_tiers() {
+        let mut transfer_fee = test_tiered_transfer_fee();
+        transfer_fee.tiers[1].minimum_amount = PodU256::from(100);
+        assert_eq!(
+            transfer_fee.validate(),
+            Err(TokenError::InvalidTransferFeeTiers.into())
+        );
+
+        let mut transfer_fee = test_tiered_transfer_fee();
+        transfer_fee.tiers[2].transfer_fee_basis_points = PodU16::from(1);
+        assert_eq!(
+            transfer_fee.validate(),
+            Err(TokenError::InvalidTransferFeeTiers.into())
+        );
+
+        let mut transfer_fee = test_tiered_transfer_fee();
+        transfer_fee.tiers[0].transfer_fee_basis_points = PodU16::from(MAX_FEE_BASIS_POINTS + 1);
+        assert_eq!(
+            transfer_fee.validate(),
+            Err(TokenError::TransferFeeExceedsMaximum.into())
+        );
+    }
+
+    #[test]
+    fn get_tiered_epoch_fee() {
+        let transfer_fee_config = test_transfer_fee_config();
+        let transfer_fee_tiers = TransferFeeTiers {
+            older_tiers: Default::default(),
+            newer_tiers: test_tiered_transfer_fee().tiers,
+        };
+
+        let older_transfer_fee =
+            transfer_fee_config.get_tiered_epoch_fee(Some(&transfer_fee_tiers), OLDER_EPOCH);
+        assert_eq!(
+            older_transfer_fee,
+            TieredTransferFee::from(transfer_fee_config.older_transfer_fee)
+        );
+
+        let newer_transfer_fee =
+            transfer_fee_config.get_tiered_epoch_fee(Some(&transfer_fee_tiers), NEWER_EPOCH);
+        assert_eq!(
+            newer_transfer_fee.transfer_fee,
+            transfer_fee_config.newer_transfer_fee
+        );
+        assert_eq!(newer_transfer_fee.tiers, transfer_fee_tiers.newer_tiers);
+
+        // without the extension, the fee has no tiers
+        assert!(!transfer_fee_config
+            .get_tiered_epoch_fee(None, NEWER_EPOCH)
+            .is_tiered());
+    }
+
+    proptest! {
+        #[test]
+        fn tiered_pre_fee_amount_is_exact(
+            transfer_fee_basis_points in 0u16..=MAX_FEE_BASIS_POINTS,
+            tier_transfer_fee_basis_points in 0u16..=MAX_FEE_BASIS_POINTS,
+            maximum_fee in u64::MIN..=u64::MAX,
+            tier_maximum_fee in u64::MIN..=u64::MAX,
+            minimum_amount in 1..=u64::MAX,
+            amount_in in 0..=u64::MAX
+        ) {
+            let transfer_fee = TieredTransferFee {
+                transfer_fee: TransferFee {
+                    epoch: PodU64::from(0),
+                    maximum_fee: PodU256::from(maximum_fee),
+                    transfer_fee_basis_points: PodU16::from(transfer_fee_basis_points),
+                },
+                tiers: [
+                    TransferFeeTier {
+                        minimum_amount: PodU256::from(minimum_amount),
+                        maximum_fee: PodU256::from(tier_maximum_fee),
+                        transfer_fee_basis_points: PodU16::from(tier_transfer_fee_basis_points),
+                    },
+                    TransferFeeTier::default(),
+                    TransferFeeTier::default(),
+                ],
+            };
+            let amount_in = U256::from(amount_in);
+            let amount_out = transfer_fee.calculate_post_fee_amount(amount_in).unwrap();
+            let pre_fee_amount = transfer_fee.calculate_pre_fee_amount(amount_out).unwrap();
+            assert!(pre_fee_amount <= amount_in);
+            assert_eq!(
+                transfer_fee.calculate_post_fee_amount(pre_fee_amount).unwrap(),
+                amount_out
+            );
+            // the net amount never decreases as the transfer amount grows
+            if let Some(next_amount_in) = amount_in.checked_add(U256::ONE) {
+                assert!(
+                    transfer_fee.calculate_post_fee_amount(next_amount_in).unwrap() >= amount_out
+                );
+            }
+            let fee = transfer_fee.calculate_fee(amount_in).unwrap();
+            assert!(fee >= transfer_fee.calculate_inverse_fee(amount_out).unwrap());
+        }
+    }
 }
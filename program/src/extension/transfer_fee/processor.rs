@@ -2,10 +2,14 @@ use {
     crate::{
         check_program_account,
         error::TokenError,
+        event::{
+            self, HarvestWithheldTokensEvent, SweepWithheldTokensEvent, WithdrawWithheldTokensEvent,
+        },
         extension::{
             transfer_fee::{
-                instruction::TransferFeeInstruction, TransferFee, TransferFeeAmount,
-                TransferFeeConfig, MAX_FEE_BASIS_POINTS,
+                instruction::TransferFeeInstruction, TieredTransferFee, TransferFee,
+                TransferFeeAmount, TransferFeeConfig, TransferFeeExemption, TransferFeeRecipient,
+                TransferFeeTier, TransferFeeTiers, MAX_FEE_BASIS_POINTS, MAX_TRANSFER_FEE_TIERS,
             },
             BaseStateWithExtensions, BaseStateWithExtensionsMut, PodStateWithExtensions,
             PodStateWithExtensionsMut,
@@ -17,6 +21,7 @@ use {
         clock::Clock,
         entrypoint::ProgramResult,
         msg,
+        program_error::ProgramError,
         program_option::COption,
         pubkey::Pubkey,
         sysvar::Sysvar,
@@ -57,11 +62,34 @@ fn process_initialize_transfer_fee_config(
     Ok(())
 }
 
+fn process_initialize_transfer_fee_tiers(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack_uninitialized(&mut mint_data)?;
+    mint.init_extension::<TransferFeeTiers>(true)?;
+
+    Ok(())
+}
+
+fn process_initialize_transfer_fee_recipient(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack_uninitialized(&mut mint_data)?;
+    mint.init_extension::<TransferFeeRecipient>(true)?;
+
+    Ok(())
+}
+
 fn process_set_transfer_fee(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     transfer_fee_basis_points: u16,
     maximum_fee: U256,
+    tiers: Option<[TransferFeeTier; MAX_TRANSFER_FEE_TIERS]>,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let mint_account_info = next_account_info(account_info_iter)?;
@@ -94,7 +122,8 @@ fn process_set_transfer_fee(
     // * newer transfer fee epoch >= next epoch: it was never used, so just
     //   overwrite next transfer fee
     let epoch = Clock::get()?.epoch;
-    if u64::from(extension.newer_transfer_fee.epoch) <= epoch {
+    let newer_transfer_fee_active = u64::from(extension.newer_transfer_fee.epoch) <= epoch;
+    if newer_transfer_fee_active {
         extension.older_transfer_fee = extension.newer_transfer_fee;
     }
     // set two epochs ahead to avoid rug pulls at the end of an epoch
@@ -106,40 +135,81 @@ fn process_set_transfer_fee(
     };
     extension.newer_transfer_fee = transfer_fee;
 
+    // The tiers follow the same schedule, and a flat fee clears them
+    if let Ok(extension) = mint.get_extension_mut::<TransferFeeTiers>() {
+        let tiers = tiers.unwrap_or_default();
+        TieredTransferFee {
+            transfer_fee,
+            tiers,
+        }
+        .validate()?;
+        if newer_transfer_fee_active {
+            extension.older_tiers = extension.newer_tiers;
+        }
+        extension.newer_tiers = tiers;
+    } else if tiers.is_some() {
+        return Err(TokenError::ExtensionNotFound.into());
+    }
+
     Ok(())
 }
 
-fn process_withdraw_withheld_tokens_from_mint(
+fn process_set_transfer_fee_exemption(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    fee_exempt: bool,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let token_account_info = next_account_info(account_info_iter)?;
     let mint_account_info = next_account_info(account_info_iter)?;
-    let destination_account_info = next_account_info(account_info_iter)?;
     let authority_info = next_account_info(account_info_iter)?;
     let authority_info_data_len = authority_info.data_len();
 
-    // unnecessary check, but helps for clarity
     check_program_account(mint_account_info.owner)?;
 
-    let mut mint_data = mint_account_info.data.borrow_mut();
-    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack(&mut mint_data)?;
-    let extension = mint.get_extension_mut::<TransferFeeConfig>()?;
+    let mint_data = mint_account_info.data.borrow();
+    let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)?;
+    let extension = mint.get_extension::<TransferFeeConfig>()?;
 
-    let withdraw_withheld_authority = Option::<Pubkey>::from(extension.withdraw_withheld_authority)
-        .ok_or(TokenError::NoAuthorityExists)?;
+    let transfer_fee_config_authority =
+        Option::<Pubkey>::from(extension.transfer_fee_config_authority)
+            .ok_or(TokenError::NoAuthorityExists)?;
     Processor::validate_owner(
         program_id,
-        &withdraw_withheld_authority,
+        &transfer_fee_config_authority,
         authority_info,
         authority_info_data_len,
         account_info_iter.as_slice(),
     )?;
 
+    let mut token_account_data = token_account_info.data.borrow_mut();
+    let mut token_account =
+        PodStateWithExtensionsMut::<PodAccount>::unpack(&mut token_account_data)?;
+    if token_account.base.mint != *mint_account_info.key {
+        return Err(TokenError::MintMismatch.into());
+    }
+    let extension =
+        if let Ok(extension) = token_account.get_extension_mut::<TransferFeeExemption>() {
+            extension
+        } else {
+            token_account.init_extension::<TransferFeeExemption>(true)?
+        };
+    extension.fee_exempt = fee_exempt.into();
+
+    Ok(())
+}
+
+/// Moves all withheld tokens in the mint to the destination account,
+/// returning the amount moved
+fn withdraw_withheld_tokens_to(
+    mint_key: &Pubkey,
+    extension: &mut TransferFeeConfig,
+    destination_account_info: &AccountInfo,
+) -> Result<u64, ProgramError> {
     let mut destination_account_data = destination_account_info.data.borrow_mut();
     let destination_account =
         PodStateWithExtensionsMut::<PodAccount>::unpack(&mut destination_account_data)?;
-    if destination_account.base.mint != *mint_account_info.key {
+    if destination_account.base.mint != *mint_key {
         return Err(TokenError::MintMismatch.into());
     }
     if destination_account.base.is_frozen() {
@@ -152,6 +222,102 @@ fn process_withdraw_withheld_tokens_from_mint(
         .ok_or(TokenError::Overflow)?
         .into();
 
+    Ok(withheld_amount)
+}
+
+fn process_withdraw_withheld_tokens_from_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let destination_account_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let authority_info_data_len = authority_info.data_len();
+
+    // unnecessary check, but helps for clarity
+    check_program_account(mint_account_info.owner)?;
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<TransferFeeConfig>()?;
+
+    let withdraw_withheld_authority = Option::<Pubkey>::from(extension.withdraw_withheld_authority)
+        .ok_or(TokenError::NoAuthorityExists)?;
+    Processor::validate_owner(
+        program_id,
+        &withdraw_withheld_authority,
+        authority_info,
+        authority_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    let amount =
+        withdraw_withheld_tokens_to(mint_account_info.key, extension, destination_account_info)?;
+    event::emit(&WithdrawWithheldTokensEvent {
+        mint: *mint_account_info.key,
+        source: *mint_account_info.key,
+        destination: *destination_account_info.key,
+        amount: U256::from(amount).into(),
+    });
+    Ok(())
+}
+
+fn process_set_fee_recipient(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_recipient: COption<Pubkey>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let authority_info_data_len = authority_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension::<TransferFeeConfig>()?;
+
+    let withdraw_withheld_authority = Option::<Pubkey>::from(extension.withdraw_withheld_authority)
+        .ok_or(TokenError::NoAuthorityExists)?;
+    Processor::validate_owner(
+        program_id,
+        &withdraw_withheld_authority,
+        authority_info,
+        authority_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    let extension = mint.get_extension_mut::<TransferFeeRecipient>()?;
+    extension.fee_recipient = fee_recipient.try_into()?;
+
+    Ok(())
+}
+
+fn process_sweep_withheld_tokens_to_fee_recipient(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let destination_account_info = next_account_info(account_info_iter)?;
+
+    check_program_account(mint_account_info.owner)?;
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack(&mut mint_data)?;
+
+    let fee_recipient =
+        Option::<Pubkey>::from(mint.get_extension::<TransferFeeRecipient>()?.fee_recipient)
+            .ok_or(TokenError::InvalidFeeRecipient)?;
+    if fee_recipient != *destination_account_info.key {
+        return Err(TokenError::InvalidFeeRecipient.into());
+    }
+
+    let extension = mint.get_extension_mut::<TransferFeeConfig>()?;
+    let amount =
+        withdraw_withheld_tokens_to(mint_account_info.key, extension, destination_account_info)?;
+    event::emit(&SweepWithheldTokensEvent {
+        mint: *mint_account_info.key,
+        fee_recipient,
+        amount: U256::from(amount).into(),
+    });
     Ok(())
 }
 
@@ -192,6 +358,11 @@ fn process_harvest_withheld_tokens_to_mint(accounts: &[AccountInfo]) -> ProgramR
                     .checked_add(amount)
                     .ok_or(TokenError::Overflow)?
                     .into();
+                event::emit(&HarvestWithheldTokensEvent {
+                    mint: *mint_account_info.key,
+                    account: *token_account_info.key,
+                    amount: U256::from(amount).into(),
+                });
             }
             Err(e) => {
                 msg!("Error harvesting from {}: {}", token_account_info.key, e);
@@ -254,6 +425,12 @@ fn process_withdraw_withheld_tokens_from_accounts(
                 .checked_add(account_withheld_amount)
                 .ok_or(TokenError::Overflow)?
                 .into();
+            event::emit(&WithdrawWithheldTokensEvent {
+                mint: *mint_account_info.key,
+                source: *account_info.key,
+                destination: *destination_account_info.key,
+                amount: U256::from(account_withheld_amount).into(),
+            });
         } else {
             match harvest_from_account(mint_account_info.key, account_info) {
                 Ok(amount) => {
@@ -261,6 +438,12 @@ fn process_withdraw_withheld_tokens_from_accounts(
                         .checked_add(amount)
                         .ok_or(TokenError::Overflow)?
                         .into();
+                    event::emit(&WithdrawWithheldTokensEvent {
+                        mint: *mint_account_info.key,
+                        source: *account_info.key,
+                        destination: *destination_account_info.key,
+                        amount: U256::from(amount).into(),
+                    });
                 }
                 Err(e) => {
                     msg!("Error harvesting from {}: {}", account_info.key, e);
@@ -318,7 +501,47 @@ pub(crate) fn process_instruction(
             maximum_fee,
         } => {
             msg!("TransferFeeInstruction: SetTransferFee");
-            process_set_transfer_fee(program_id, accounts, transfer_fee_basis_points, maximum_fee)
+            process_set_transfer_fee(
+                program_id,
+                accounts,
+                transfer_fee_basis_points,
+                maximum_fee,
+                None,
+            )
+        }
+        TransferFeeInstruction::SetTieredTransferFee {
+            transfer_fee_basis_points,
+            maximum_fee,
+            tiers,
+        } => {
+            msg!("TransferFeeInstruction: SetTieredTransferFee");
+            process_set_transfer_fee(
+                program_id,
+                accounts,
+                transfer_fee_basis_points,
+                maximum_fee,
+                Some(tiers),
+            )
+        }
+        TransferFeeInstruction::SetTransferFeeExemption { fee_exempt } => {
+            msg!("TransferFeeInstruction: SetTransferFeeExemption");
+            process_set_transfer_fee_exemption(program_id, accounts, fee_exempt)
+        }
+        TransferFeeInstruction::SetFeeRecipient { fee_recipient } => {
+            msg!("TransferFeeInstruction: SetFeeRecipient");
+            process_set_fee_recipient(program_id, accounts, fee_recipient)
+        }
+        TransferFeeInstruction::SweepWithheldTokensToFeeRecipient => {
+            msg!("TransferFeeInstruction: SweepWithheldTokensToFeeRecipient");
+            process_sweep_withheld_tokens_to_fee_recipient(accounts)
+        }
+        TransferFeeInstruction::InitializeTransferFeeTiers => {
+            msg!("TransferFeeInstruction: InitializeTransferFeeTiers");
+            process_initialize_transfer_fee_tiers(accounts)
+        }
+        TransferFeeInstruction::InitializeTransferFeeRecipient => {
+            msg!("TransferFeeInstruction: InitializeTransferFeeRecipient");
+            process_initialize_transfer_fee_recipient(accounts)
         }
     }
 }
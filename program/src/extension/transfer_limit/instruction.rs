@@ -0,0 +1,183 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        check_program_account,
+        instruction::{encode_instruction, TokenInstruction},
+    },
+    bytemuck::{Pod, Zeroable},
+    ethnum::U256,
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    spl_pod::{
+        optional_keys::OptionalNonZeroPubkey,
+        primitives::{PodI64, PodU256},
+    },
+    std::convert::TryInto,
+};
+
+/// Transfer limit extension instructions
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum TransferLimitInstruction {
+    /// Cap the amount of tokens that may be transferred, burned or deposited
+    /// into the confidential balance from the account per time window.
+    /// Transfers and burns by the mint's permanent delegate are not limited.
+    ///
+    /// A tighter limit takes effect immediately. A higher limit, a shorter
+    /// window, or removing the limit with a window of 0 only takes effect after
+    /// one full window of the current limit.
+    ///
+    /// Implicitly initializes the extension in the case where it is not
+    /// present, so the account must have room for it, see `Reallocate`.
+    ///
+    /// The limit can be configured by the account's owner, or by the mint's
+    /// transfer limit policy authority, under the same rules.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The account to update.
+    ///   1. `[signer]` The account's owner.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The account to update.
+    ///   1. `[]` The account's multisignature owner.
+    ///   2. `..2+M` `[signer]` M signer accounts.
+    ///
+    ///   * Policy authority
+    ///   0. `[writable]` The account to update.
+    ///   1. `[signer]` The mint's transfer limit policy authority.
+    ///   2. `[]` The token mint.
+    ///
+    ///   * Multisignature policy authority
+    ///   0. `[writable]` The account to update.
+    ///   1. `[]` The mint's multisignature transfer limit policy authority.
+    ///   2. `[]` The token mint.
+    ///   3. `..3+M` `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::transfer_limit::instruction::ConfigureInstructionData`
+    Configure,
+    /// Initialize the transfer limit policy authority for the given mint
+    /// account
+    ///
+    /// Fails if the account has already been initialized, so must be called
+    /// before `InitializeMint`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint account to initialize.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::transfer_limit::instruction::InitializePolicyInstructionData`
+    InitializePolicy,
+}
+
+/// Data expected by `TransferLimitInstruction::Configure`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct ConfigureInstructionData {
+    /// Maximum amount that may be transferred or burned per window
+    pub limit: PodU256,
+    /// Length of a window in seconds, or 0 to remove the limit
+    pub window_seconds: PodI64,
+}
+
+/// Data expected by `TransferLimitInstruction::InitializePolicy`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct InitializePolicyInstructionData {
+    /// The public key for the account that can configure transfer limits
+    pub authority: OptionalNonZeroPubkey,
+}
+
+/// Create a `Configure` instruction
+pub fn configure_transfer_limit(
+    token_program_id: &Pubkey,
+    account: &Pubkey,
+    owner: &Pubkey,
+    signers: &[&Pubkey],
+    limit: U256,
+    window_seconds: i64,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(*owner, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::TransferLimitExtension,
+        TransferLimitInstruction::Configure,
+        &ConfigureInstructionData {
+            limit: limit.into(),
+            window_seconds: window_seconds.into(),
+        },
+    ))
+}
+
+/// Create a `Configure` instruction signed by the mint's transfer limit
+/// policy authority
+#[allow(clippy::too_many_arguments)]
+pub fn configure_transfer_limit_by_policy_authority(
+    token_program_id: &Pubkey,
+    account: &Pubkey,
+    mint: &Pubkey,
+    policy_authority: &Pubkey,
+    signers: &[&Pubkey],
+    limit: U256,
+    window_seconds: i64,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(*policy_authority, signers.is_empty()),
+        AccountMeta::new_readonly(*mint, false),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::TransferLimitExtension,
+        TransferLimitInstruction::Configure,
+        &ConfigureInstructionData {
+            limit: limit.into(),
+            window_seconds: window_seconds.into(),
+        },
+    ))
+}
+
+/// Create an `InitializePolicy` instruction
+pub fn initialize_policy(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    authority: Option<Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::TransferLimitExtension,
+        TransferLimitInstruction::InitializePolicy,
+        &InitializePolicyInstructionData {
+            authority: authority.try_into()?,
+        },
+    ))
+}
@@ -0,0 +1,229 @@
+use ethnum::U256;
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        error::TokenError,
+        extension::{Extension, ExtensionType},
+    },
+    bytemuck::{Pod, Zeroable},
+    solana_program::entrypoint::ProgramResult,
+    spl_pod::{
+        optional_keys::OptionalNonZeroPubkey,
+        primitives::{PodI64, PodU256},
+    },
+};
+
+/// Transfer limit extension instructions
+pub mod instruction;
+
+/// Transfer limit extension processor
+pub mod processor;
+
+/// `UnixTimestamp` expressed with an alignment-independent type
+pub type UnixTimestamp = PodI64;
+
+/// Indicates that the transfer limits of accounts of this mint can also be
+/// configured by a policy authority
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct TransferLimitPolicy {
+    /// Authority that can configure the transfer limit of accounts of the
+    /// mint, besides their owners
+    pub authority: OptionalNonZeroPubkey,
+}
+impl Extension for TransferLimitPolicy {
+    const TYPE: ExtensionType = ExtensionType::TransferLimitPolicy;
+}
+
+/// Cap on the amount of tokens that may leave an account per time window
+///
+/// The window restarts with the first outflow after the previous window has
+/// elapsed. A limit that is not in effect has a window length of 0.
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct TransferLimit {
+    /// Maximum amount that may be transferred or burned per window
+    pub limit: PodU256,
+    /// Length of a window in seconds
+    pub window_seconds: PodI64,
+    /// Start of the current window
+    pub window_start: UnixTimestamp,
+    /// Amount transferred or burned in the current window
+    pub window_outflow: PodU256,
+    /// Limit that takes effect at `pending_effective_timestamp`
+    pub pending_limit: PodU256,
+    /// Window length that takes effect at `pending_effective_timestamp`
+    pub pending_window_seconds: PodI64,
+    /// Time at which the pending limit takes effect, or 0 if none is pending
+    pub pending_effective_timestamp: UnixTimestamp,
+}
+impl TransferLimit {
+    /// Check if a limit is in effect
+    pub fn is_enabled(&self) -> bool {
+        i64::from(self.window_seconds) > 0
+    }
+
+    /// Apply an elapsed pending limit, and start a new window if the current
+    /// one has elapsed
+    fn settle(&mut self, unix_timestamp: i64) {
+        let pending_effective_timestamp = i64::from(self.pending_effective_timestamp);
+        if pending_effective_timestamp != 0 && unix_timestamp >= pending_effective_timestamp {
+            self.limit = self.pending_limit;
+            self.window_seconds = self.pending_window_seconds;
+            self.clear_pending();
+        }
+        let window_end =
+            i64::from(self.window_start).saturating_add(i64::from(self.window_seconds));
+        if unix_timestamp >= window_end {
+            self.window_start = unix_timestamp.into();
+            self.window_outflow = U256::ZERO.into();
+        }
+    }
+
+    fn clear_pending(&mut self) {
+        self.pending_limit = U256::ZERO.into();
+        self.pending_window_seconds = 0.into();
+        self.pending_effective_timestamp = 0.into();
+    }
+
+    /// Record tokens leaving the account, failing if the limit of the current
+    /// window would be exceeded
+    pub fn record_outflow(&mut self, amount: U256, unix_timestamp: i64) -> ProgramResult {
+        self.settle(unix_timestamp);
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        let window_outflow = U256::from(self.window_outflow)
+            .checked_add(amount)
+            .ok_or(TokenError::Overflow)?;
+        if window_outflow > U256::from(self.limit) {
+            return Err(TokenError::TransferLimitExceeded.into());
+        }
+        self.window_outflow = window_outflow.into();
+        Ok(())
+    }
+
+    /// Set a new limit, where a window length of 0 removes the limit.
+    ///
+    /// A tighter limit takes effect immediately and cancels any pending one.
+    /// Any other change only takes effect after one full current window, so
+    /// that a compromised key cannot lift the limit and drain the account at
+    /// once.
+    pub fn set_limit(
+        &mut self,
+        limit: U256,
+        window_seconds: i64,
+        unix_timestamp: i64,
+    ) -> ProgramResult {
+        if window_seconds < 0 {
+            return Err(TokenError::InvalidTransferLimit.into());
+        }
+        self.settle(unix_timestamp);
+        let tighter = window_seconds > 0
+            && (!self.is_enabled()
+                || (limit <= U256::from(self.limit)
+                    && window_seconds >= i64::from(self.window_seconds)));
+        if tighter {
+            self.limit = limit.into();
+            self.window_seconds = window_seconds.into();
+            self.clear_pending();
+        } else {
+            self.pending_limit = limit.into();
+            self.pending_window_seconds = window_seconds.into();
+            self.pending_effective_timestamp = unix_timestamp
+                .saturating_add(i64::from(self.window_seconds))
+                .into();
+        }
+        Ok(())
+    }
+}
+impl Extension for TransferLimit {
+    const TYPE: ExtensionType = ExtensionType::TransferLimit;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    #[test]
+    fn outflow_within_window() {
+        let mut transfer_limit = TransferLimit::default();
+        // no limit in effect
+        transfer_limit.record_outflow(U256::MAX, 1).unwrap();
+
+        transfer_limit.set_limit(U256::new(100), DAY, 10).unwrap();
+        assert!(transfer_limit.is_enabled());
+        transfer_limit.record_outflow(U256::new(60), 20).unwrap();
+        transfer_limit.record_outflow(U256::new(40), 30).unwrap();
+        assert_eq!(
+            transfer_limit.record_outflow(U256::ONE, 10 + DAY - 1),
+            Err(TokenError::TransferLimitExceeded.into())
+        );
+
+        // new window
+        transfer_limit
+            .record_outflow(U256::new(100), 10 + DAY)
+            .unwrap();
+        assert_eq!(i64::from(transfer_limit.window_start), 10 + DAY);
+        assert_eq!(
+            transfer_limit.record_outflow(U256::ONE, 10 + DAY),
+            Err(TokenError::TransferLimitExceeded.into())
+        );
+    }
+
+    #[test]
+    fn loosening_is_delayed() {
+        let mut transfer_limit = TransferLimit::default();
+        transfer_limit.set_limit(U256::new(100), DAY, 10).unwrap();
+
+        // raising the limit waits a full window
+        transfer_limit.set_limit(U256::new(1_000), DAY, 20).unwrap();
+        assert_eq!(U256::from(transfer_limit.limit), U256::new(100));
+        assert_eq!(
+            i64::from(transfer_limit.pending_effective_timestamp),
+            20 + DAY
+        );
+        assert_eq!(
+            transfer_limit.record_outflow(U256::new(101), 20 + DAY - 1),
+            Err(TokenError::TransferLimitExceeded.into())
+        );
+        transfer_limit
+            .record_outflow(U256::new(1_000), 20 + DAY)
+            .unwrap();
+        assert_eq!(U256::from(transfer_limit.limit), U256::new(1_000));
+        assert_eq!(i64::from(transfer_limit.pending_effective_timestamp), 0);
+
+        // removing the limit waits a full window too
+        transfer_limit.set_limit(U256::ZERO, 0, 30 + DAY).unwrap();
+        assert!(transfer_limit.is_enabled());
+        transfer_limit
+            .record_outflow(U256::MAX, 30 + 2 * DAY)
+            .unwrap();
+        assert!(!transfer_limit.is_enabled());
+    }
+
+    #[test]
+    fn tightening_cancels_pending() {
+        let mut transfer_limit = TransferLimit::default();
+        transfer_limit.set_limit(U256::new(100), DAY, 10).unwrap();
+        transfer_limit.set_limit(U256::MAX, DAY, 20).unwrap();
+        transfer_limit
+            .set_limit(U256::new(50), 2 * DAY, 30)
+            .unwrap();
+        assert_eq!(U256::from(transfer_limit.limit), U256::new(50));
+        assert_eq!(i64::from(transfer_limit.window_seconds), 2 * DAY);
+        assert_eq!(i64::from(transfer_limit.pending_effective_timestamp), 0);
+
+        assert_eq!(
+            transfer_limit.set_limit(U256::new(50), -1, 40),
+            Err(TokenError::InvalidTransferLimit.into())
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use {
+    crate::{
+        check_program_account,
+        error::TokenError,
+        extension::{
+            transfer_limit::{
+                instruction::{
+                    ConfigureInstructionData, InitializePolicyInstructionData,
+                    TransferLimitInstruction,
+                },
+                TransferLimit, TransferLimitPolicy,
+            },
+            BaseStateWithExtensions, BaseStateWithExtensionsMut, PodStateWithExtensions,
+            PodStateWithExtensionsMut,
+        },
+        instruction::{decode_instruction_data, decode_instruction_type},
+        pod::{PodAccount, PodMint},
+        processor::Processor,
+    },
+    ethnum::U256,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        clock::Clock,
+        entrypoint::ProgramResult,
+        msg,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        sysvar::Sysvar,
+    },
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+};
+
+/// Get the transfer limit policy authority of the mint, if any
+fn get_policy_authority(mint_info: &AccountInfo) -> Result<Option<Pubkey>, ProgramError> {
+    check_program_account(mint_info.owner)?;
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)?;
+    Ok(mint
+        .get_extension::<TransferLimitPolicy>()
+        .ok()
+        .and_then(|extension| Option::<Pubkey>::from(extension.authority)))
+}
+
+fn process_initialize_policy(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    authority: &OptionalNonZeroPubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack_uninitialized(&mut mint_data)?;
+
+    let extension = mint.init_extension::<TransferLimitPolicy>(true)?;
+    extension.authority = *authority;
+    Ok(())
+}
+
+/// Set the transfer limit of an account, initializing the extension if not
+/// already present.
+fn process_configure(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    limit: U256,
+    window_seconds: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let token_account_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let authority_info_data_len = authority_info.data_len();
+
+    let mut account_data = token_account_info.data.borrow_mut();
+    let mut account = PodStateWithExtensionsMut::<PodAccount>::unpack(&mut account_data)?;
+
+    // anyone but the owner must be the policy authority of the mint, passed
+    // right after the authority
+    let authority = if *authority_info.key == account.base.owner {
+        account.base.owner
+    } else {
+        let mint_info = account_info_iter.next().ok_or(TokenError::OwnerMismatch)?;
+        if *mint_info.key != account.base.mint {
+            return Err(TokenError::MintMismatch.into());
+        }
+        get_policy_authority(mint_info)?
+            .filter(|policy_authority| policy_authority == authority_info.key)
+            .ok_or(TokenError::OwnerMismatch)?
+    };
+    Processor::validate_owner(
+        program_id,
+        &authority,
+        authority_info,
+        authority_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    let extension = if let Ok(extension) = account.get_extension_mut::<TransferLimit>() {
+        extension
+    } else {
+        account.init_extension::<TransferLimit>(true)?
+    };
+    extension.set_limit(limit, window_seconds, Clock::get()?.unix_timestamp)
+}
+
+pub(crate) fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    check_program_account(program_id)?;
+
+    match decode_instruction_type(input)? {
+        TransferLimitInstruction::Configure => {
+            msg!("TransferLimitInstruction::Configure");
+            let ConfigureInstructionData {
+                limit,
+                window_seconds,
+            } = decode_instruction_data(input)?;
+            process_configure(
+                program_id,
+                accounts,
+                U256::from(*limit),
+                i64::from(*window_seconds),
+            )
+        }
+        TransferLimitInstruction::InitializePolicy => {
+            msg!("TransferLimitInstruction::InitializePolicy");
+            let InitializePolicyInstructionData { authority } = decode_instruction_data(input)?;
+            process_initialize_policy(program_id, accounts, authority)
+        }
+    }
+}
@@ -0,0 +1,158 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        check_program_account,
+        extension::vesting::UnixTimestamp,
+        instruction::{encode_instruction, TokenInstruction},
+    },
+    bytemuck::{Pod, Zeroable},
+    ethnum::U256,
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    spl_pod::{optional_keys::OptionalNonZeroPubkey, primitives::PodU256},
+    std::convert::TryInto,
+};
+
+/// Vesting extension instructions
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum VestingInstruction {
+    /// Initialize the vesting extension for the given mint account
+    ///
+    /// Fails if the account has already been initialized, so must be called
+    /// before `InitializeMint`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint account to initialize.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::vesting::instruction::InitializeInstructionData`
+    Initialize,
+    /// Lock part of a token account's balance under a vesting schedule,
+    /// replacing any previous schedule. Only the unlocked part of the balance
+    /// can be transferred, burned or deposited into the confidential balance,
+    /// except by the mint's permanent delegate.
+    ///
+    /// The locked amount cannot exceed the current balance, so tokens are
+    /// typically minted or transferred into the account first, within the same
+    /// transaction. Locking an amount of 0 removes the schedule.
+    ///
+    /// Both the mint's vesting authority and the account's owner must sign.
+    /// While the previous schedule still locks tokens, the new one cannot
+    /// lock more than is still locked, nor start, reach its cliff or end any
+    /// later.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority and owner
+    ///   0. `[writable]` The token account to lock.
+    ///   1. `[]` The token mint.
+    ///   2. `[signer]` The mint's vesting authority.
+    ///   3. `[signer]` The account's owner.
+    ///
+    ///   * Multisignature authority or owner
+    ///   0. `[writable]` The token account to lock.
+    ///   1. `[]` The token mint.
+    ///   2. `[]` The mint's vesting authority, single or multisignature.
+    ///   3. `[]` The account's owner, single or multisignature.
+    ///   4. `..4+M` `[signer]` M signer accounts for the multisignature
+    ///      accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::vesting::instruction::LockInstructionData`
+    Lock,
+}
+
+/// Data expected by `VestingInstruction::Initialize`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct InitializeInstructionData {
+    /// The public key for the account that can lock tokens
+    pub authority: OptionalNonZeroPubkey,
+}
+
+/// Data expected by `VestingInstruction::Lock`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct LockInstructionData {
+    /// Time at which the schedule starts accruing
+    pub start_timestamp: UnixTimestamp,
+    /// Time before which nothing is unlocked
+    pub cliff_timestamp: UnixTimestamp,
+    /// Time at which everything is unlocked
+    pub end_timestamp: UnixTimestamp,
+    /// Amount to lock
+    pub amount: PodU256,
+}
+
+/// Create an `Initialize` instruction
+pub fn initialize(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    authority: Option<Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::VestingExtension,
+        VestingInstruction::Initialize,
+        &InitializeInstructionData {
+            authority: authority.try_into()?,
+        },
+    ))
+}
+
+/// Create a `Lock` instruction
+///
+/// If the vesting authority or the owner is a multisig, `signers` holds its
+/// signers, along with the other one if it signs directly
+#[allow(clippy::too_many_arguments)]
+pub fn lock(
+    token_program_id: &Pubkey,
+    account: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    owner: &Pubkey,
+    signers: &[&Pubkey],
+    start_timestamp: i64,
+    cliff_timestamp: i64,
+    end_timestamp: i64,
+    amount: U256,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(*authority, signers.is_empty()),
+        AccountMeta::new_readonly(*owner, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::VestingExtension,
+        VestingInstruction::Lock,
+        &LockInstructionData {
+            start_timestamp: start_timestamp.into(),
+            cliff_timestamp: cliff_timestamp.into(),
+            end_timestamp: end_timestamp.into(),
+            amount: amount.into(),
+        },
+    ))
+}
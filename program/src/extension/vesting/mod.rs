@@ -0,0 +1,189 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::extension::{Extension, ExtensionType},
+    bytemuck::{Pod, Zeroable},
+    ethnum::U256,
+    spl_pod::{
+        optional_keys::OptionalNonZeroPubkey,
+        primitives::{PodI64, PodU256},
+    },
+};
+
+/// Vesting extension instructions
+pub mod instruction;
+
+/// Vesting extension processor
+pub mod processor;
+
+/// `UnixTimestamp` expressed with an alignment-independent type
+pub type UnixTimestamp = PodI64;
+
+/// Indicates that the tokens in accounts of this mint can be locked
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct VestingConfig {
+    /// Authority that can lock tokens in accounts of the mint
+    pub authority: OptionalNonZeroPubkey,
+}
+impl Extension for VestingConfig {
+    const TYPE: ExtensionType = ExtensionType::Vesting;
+}
+
+/// Vesting schedule of the locked part of an account's balance
+///
+/// Nothing unlocks before the cliff. From the cliff onwards, the locked total
+/// unlocks linearly between the start and the end, so reaching the cliff
+/// releases everything that accrued since the start.
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct VestingAccount {
+    /// Time at which the schedule starts accruing
+    pub start_timestamp: UnixTimestamp,
+    /// Time before which nothing is unlocked
+    pub cliff_timestamp: UnixTimestamp,
+    /// Time at which everything is unlocked
+    pub end_timestamp: UnixTimestamp,
+    /// Amount locked by the schedule
+    pub locked_total: PodU256,
+}
+impl VestingAccount {
+    /// Check that the timestamps of the schedule are in order
+    pub fn is_valid_schedule(start: i64, cliff: i64, end: i64) -> bool {
+        start <= cliff && cliff <= end
+    }
+
+    /// Amount still locked at the given time
+    pub fn locked_amount(&self, unix_timestamp: i64) -> U256 {
+        let locked_total = U256::from(self.locked_total);
+        let start = i64::from(self.start_timestamp);
+        let end = i64::from(self.end_timestamp);
+        if unix_timestamp >= end {
+            return U256::ZERO;
+        }
+        if unix_timestamp < i64::from(self.cliff_timestamp) {
+            return locked_total;
+        }
+        // start <= cliff <= unix_timestamp < end, so the duration is non-zero.
+        // Split the total to keep the multiplication from overflowing.
+        let duration = U256::from(end.abs_diff(start));
+        let elapsed = U256::from(unix_timestamp.abs_diff(start));
+        let unlocked =
+            locked_total / duration * elapsed + locked_total % duration * elapsed / duration;
+        locked_total.saturating_sub(unlocked)
+    }
+
+    /// Part of the given balance that can be spent at the given time
+    pub fn unlocked_balance(&self, balance: U256, unix_timestamp: i64) -> U256 {
+        balance.saturating_sub(self.locked_amount(unix_timestamp))
+    }
+
+    /// Check that the schedule can be replaced by the new one at the given
+    /// time, i.e. it no longer locks anything, or the new one neither locks
+    /// more than is still locked nor starts, reaches its cliff or ends later
+    pub fn can_be_replaced_by(&self, new: &VestingAccount, unix_timestamp: i64) -> bool {
+        let still_locked = self.locked_amount(unix_timestamp);
+        let new_total = U256::from(new.locked_total);
+        still_locked == U256::ZERO
+            || new_total == U256::ZERO
+            || (new_total <= still_locked
+                && i64::from(new.start_timestamp) <= i64::from(self.start_timestamp)
+                && i64::from(new.cliff_timestamp) <= i64::from(self.cliff_timestamp)
+                && i64::from(new.end_timestamp) <= i64::from(self.end_timestamp))
+    }
+}
+impl Extension for VestingAccount {
+    const TYPE: ExtensionType = ExtensionType::VestingAccount;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(start: i64, cliff: i64, end: i64, locked_total: U256) -> VestingAccount {
+        VestingAccount {
+            start_timestamp: start.into(),
+            cliff_timestamp: cliff.into(),
+            end_timestamp: end.into(),
+            locked_total: locked_total.into(),
+        }
+    }
+
+    #[test]
+    fn no_lock() {
+        let vesting = VestingAccount::default();
+        assert_eq!(vesting.locked_amount(0), U256::ZERO);
+        assert_eq!(vesting.unlocked_balance(U256::new(10), 0), U256::new(10));
+    }
+
+    #[test]
+    fn cliff_and_linear_unlock() {
+        let vesting = schedule(100, 150, 200, U256::new(1_000));
+        assert_eq!(vesting.locked_amount(0), U256::new(1_000));
+        assert_eq!(vesting.locked_amount(149), U256::new(1_000));
+        assert_eq!(vesting.locked_amount(150), U256::new(500));
+        assert_eq!(vesting.locked_amount(175), U256::new(250));
+        assert_eq!(vesting.locked_amount(199), U256::new(10));
+        assert_eq!(vesting.locked_amount(200), U256::ZERO);
+
+        assert_eq!(
+            vesting.unlocked_balance(U256::new(1_200), 175),
+            U256::new(950)
+        );
+        assert_eq!(vesting.unlocked_balance(U256::new(100), 175), U256::ZERO);
+    }
+
+    #[test]
+    fn cliff_only() {
+        let vesting = schedule(100, 200, 200, U256::new(1_000));
+        assert_eq!(vesting.locked_amount(199), U256::new(1_000));
+        assert_eq!(vesting.locked_amount(200), U256::ZERO);
+    }
+
+    #[test]
+    fn rounding_keeps_tokens_locked() {
+        let vesting = schedule(0, 0, 3, U256::new(10));
+        assert_eq!(vesting.locked_amount(1), U256::new(7));
+        assert_eq!(vesting.locked_amount(2), U256::new(4));
+
+        // no overflow for large totals
+        let vesting = schedule(0, 0, 2, U256::MAX);
+        assert_eq!(vesting.locked_amount(1), U256::MAX / 2 + 1);
+    }
+
+    #[test]
+    fn replace_schedule() {
+        let vesting = schedule(100, 150, 200, U256::new(1_000));
+
+        // nothing can be widened while tokens are locked
+        assert!(vesting.can_be_replaced_by(&schedule(100, 150, 200, U256::new(1_000)), 0));
+        assert!(!vesting.can_be_replaced_by(&schedule(100, 150, 200, U256::new(1_001)), 0));
+        assert!(!vesting.can_be_replaced_by(&schedule(101, 150, 200, U256::new(1_000)), 0));
+        assert!(!vesting.can_be_replaced_by(&schedule(100, 151, 200, U256::new(1_000)), 0));
+        assert!(!vesting.can_be_replaced_by(&schedule(100, 150, 201, U256::new(1_000)), 0));
+
+        // the amount is capped by what is still locked
+        assert!(vesting.can_be_replaced_by(&schedule(100, 150, 175, U256::new(250)), 175));
+        assert!(!vesting.can_be_replaced_by(&schedule(100, 150, 175, U256::new(251)), 175));
+
+        // removing the schedule is always allowed
+        assert!(vesting.can_be_replaced_by(&schedule(0, 0, 0, U256::ZERO), 0));
+
+        // anything goes once everything is unlocked
+        assert!(vesting.can_be_replaced_by(&schedule(300, 400, 500, U256::new(5_000)), 200));
+        assert!(VestingAccount::default()
+            .can_be_replaced_by(&schedule(300, 400, 500, U256::new(5_000)), 0));
+    }
+
+    #[test]
+    fn schedule_order() {
+        assert!(VestingAccount::is_valid_schedule(0, 0, 0));
+        assert!(VestingAccount::is_valid_schedule(0, 5, 10));
+        assert!(!VestingAccount::is_valid_schedule(5, 0, 10));
+        assert!(!VestingAccount::is_valid_schedule(0, 10, 5));
+    }
+}
@@ -0,0 +1,139 @@
+use {
+    crate::{
+        check_program_account,
+        error::TokenError,
+        event::{self, VestingLockEvent},
+        extension::{
+            vesting::{
+                instruction::{InitializeInstructionData, LockInstructionData, VestingInstruction},
+                VestingAccount, VestingConfig,
+            },
+            BaseStateWithExtensions, BaseStateWithExtensionsMut, PodStateWithExtensions,
+            PodStateWithExtensionsMut,
+        },
+        instruction::{decode_instruction_data, decode_instruction_type},
+        pod::{PodAccount, PodMint},
+        processor::Processor,
+    },
+    ethnum::U256,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        clock::Clock,
+        entrypoint::ProgramResult,
+        msg,
+        pubkey::Pubkey,
+        sysvar::Sysvar,
+    },
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+};
+
+fn process_initialize(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    authority: &OptionalNonZeroPubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack_uninitialized(&mut mint_data)?;
+
+    let extension = mint.init_extension::<VestingConfig>(true)?;
+    extension.authority = *authority;
+    Ok(())
+}
+
+fn process_lock(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &LockInstructionData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let token_account_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let authority_info_data_len = authority_info.data_len();
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    check_program_account(mint_info.owner)?;
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)?;
+    let extension = mint.get_extension::<VestingConfig>()?;
+    let authority =
+        Option::<Pubkey>::from(extension.authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &authority,
+        authority_info,
+        authority_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    let mut account_data = token_account_info.data.borrow_mut();
+    let mut account = PodStateWithExtensionsMut::<PodAccount>::unpack(&mut account_data)?;
+    if &account.base.mint != mint_info.key {
+        return Err(TokenError::MintMismatch.into());
+    }
+
+    Processor::validate_owner(
+        program_id,
+        &account.base.owner,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    let start = i64::from(data.start_timestamp);
+    let cliff = i64::from(data.cliff_timestamp);
+    let end = i64::from(data.end_timestamp);
+    if !VestingAccount::is_valid_schedule(start, cliff, end) {
+        return Err(TokenError::InvalidVestingSchedule.into());
+    }
+    let amount = U256::from(data.amount);
+    if amount > U256::from(account.base.amount) {
+        return Err(TokenError::InsufficientFunds.into());
+    }
+
+    let new_vesting = VestingAccount {
+        start_timestamp: data.start_timestamp,
+        cliff_timestamp: data.cliff_timestamp,
+        end_timestamp: data.end_timestamp,
+        locked_total: data.amount,
+    };
+    let vesting = account.get_extension_mut::<VestingAccount>()?;
+    if !vesting.can_be_replaced_by(&new_vesting, Clock::get()?.unix_timestamp) {
+        return Err(TokenError::VestingScheduleExtended.into());
+    }
+    *vesting = new_vesting;
+
+    event::emit(&VestingLockEvent {
+        mint: *mint_info.key,
+        account: *token_account_info.key,
+        locked_total: data.amount,
+        start_timestamp: data.start_timestamp,
+        cliff_timestamp: data.cliff_timestamp,
+        end_timestamp: data.end_timestamp,
+    });
+    Ok(())
+}
+
+pub(crate) fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    check_program_account(program_id)?;
+    match decode_instruction_type(input)? {
+        VestingInstruction::Initialize => {
+            msg!("VestingInstruction::Initialize");
+            let InitializeInstructionData { authority } = decode_instruction_data(input)?;
+            process_initialize(program_id, accounts, authority)
+        }
+        VestingInstruction::Lock => {
+            msg!("VestingInstruction::Lock");
+            let data = decode_instruction_data(input)?;
+            process_lock(program_id, accounts, data)
+        }
+    }
+}
@@ -35,6 +35,8 @@ use {
 pub const MIN_SIGNERS: usize = 1;
 /// Maximum number of multisignature signers (max N)
 pub const MAX_SIGNERS: usize = 11;
+/// Maximum number of weighted multisignature signers (max N)
+pub const MAX_WEIGHTED_SIGNERS: usize = 32;
 /// Serialized length of a u16, for unpacking
 const U16_BYTES: usize = 2;
 /// Serialized length of a u64, for unpacking
@@ -517,6 +519,12 @@ pub enum TokenInstruction<'a> {
     /// Convert an Amount of tokens to a UiAmount `string`, using the given
     /// mint.
     ///
+    /// For mints with the interest-bearing or scaled UI amount extension, the
+    /// conversion is exact. If the mint scales amounts down, the UI amount
+    /// carries extra digits beyond the mint's decimals so that it still maps
+    /// back to a unique raw amount, e.g. a raw amount of 1 with 0 decimals
+    /// and a multiplier of 0.5 gives "0.5" rather than "0".
+    ///
     /// Fails on an invalid mint.
     ///
     /// Return data can be fetched using `sol_get_return_data` and deserialized
@@ -720,6 +728,178 @@ pub enum TokenInstruction<'a> {
     ScaledUiAmountExtension,
     /// Instruction prefix for instructions to the pausable extension
     PausableExtension,
+    // 45
+    /// The common instruction prefix for token group instructions that are
+    /// not part of the token-group interface.
+    ///
+    /// See `extension::token_group::instruction::TokenGroupExtensionInstruction`
+    /// for further details about the extended instructions that share this
+    /// instruction prefix
+    TokenGroupExtension,
+    /// The common instruction prefix for supply cap extension instructions.
+    ///
+    /// See `extension::supply_cap::instruction::SupplyCapInstruction` for
+    /// further details about the extended instructions that share this
+    /// instruction prefix
+    SupplyCapExtension,
+    /// The common instruction prefix for transfer limit extension
+    /// instructions.
+    ///
+    /// See `extension::transfer_limit::instruction::TransferLimitInstruction`
+    /// for further details about the extended instructions that share this
+    /// instruction prefix
+    TransferLimitExtension,
+    /// The common instruction prefix for vesting extension instructions.
+    ///
+    /// See `extension::vesting::instruction::VestingInstruction` for further
+    /// details about the extended instructions that share this instruction
+    /// prefix
+    VestingExtension,
+    /// Execute a list of token instructions in order, failing if any of them
+    /// fails.
+    ///
+    /// The instructions share a single list of accounts, and each one runs
+    /// over a contiguous range of it. Batches cannot be nested.
+    ///
+    /// Fails if an instruction uses the instructions sysvar, or an account
+    /// that requires memos or has CPI Guard enabled, since their checks
+    /// would see the batch instead of the instruction.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `..N` The accounts of every instruction in the batch.
+    ///
+    /// Data expected by this instruction, repeated for every instruction in
+    /// the batch:
+    ///   `u8` Index of the first account of the instruction
+    ///   `u8` Number of accounts of the instruction
+    ///   `u16` Little-endian length of the instruction data
+    ///   The instruction data
+    Batch,
+    // 50
+    /// Initializes a weighted multisignature account with N provided signers,
+    /// each with its own weight.
+    ///
+    /// Weighted multisignature accounts can be used in place of any single
+    /// owner/delegate accounts, like multisignature accounts. They are
+    /// validated once the weights of the signers present add up to at least
+    /// the threshold.
+    ///
+    /// The `InitializeWeightedMultisig` instruction requires no signers and
+    /// MUST be included within the same Transaction as the system program's
+    /// `CreateAccount` instruction that creates the account being
+    /// initialized. Otherwise another party can acquire ownership of the
+    /// uninitialized account.
+    ///
+    /// The account must be `PodWeightedMultisig::SIZE_OF` bytes long and
+    /// entirely zeroed, and is marked with `AccountType::WeightedMultisig` at
+    /// the same offset as mints and accounts with extensions.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The weighted multisignature account to initialize.
+    ///   1. ..`1+N`. `[]` The signer accounts, must equal to N where `1 <= N <=
+    ///      32`.
+    InitializeWeightedMultisig {
+        /// The total weight of signers required to validate this
+        /// multisignature account.
+        threshold: u16,
+        /// The weight of each signer, in the same order as the signer accounts
+        weights: Vec<u8>,
+    },
+    /// Adds extensions to an initialized mint, using reallocation to increase
+    /// the data size if needed.
+    ///
+    /// Only extensions that are safe to add to a live mint are accepted:
+    /// `MetadataPointer`, `GroupPointer`, `GroupMemberPointer`,
+    /// `TransferFeeTiers`, `TransferFeeRecipient`, `PermanentDelegateScope`
+    /// and `ScaledUiAmount`. Each new extension is initialized with the mint
+    /// authority as its authority, which can then configure it through the
+    /// extension's own instructions. Extensions already on the mint are left
+    /// untouched.
+    ///
+    /// `Pausable` can't be added, since token accounts created before it
+    /// don't carry the `PausableAccount` extension.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint to reallocate.
+    ///   1. `[signer, writable]` The payer account to fund reallocation
+    ///   2. `[]` System program for reallocation funding
+    ///   3. `[signer]` The mint's minting authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint to reallocate.
+    ///   1. `[signer, writable]` The payer account to fund reallocation
+    ///   2. `[]` System program for reallocation funding
+    ///   3. `[]` The mint's multisignature minting authority.
+    ///   4. ..`4+M` `[signer]` M signer accounts.
+    ReallocateMint {
+        /// New extension types to include in the reallocated mint
+        extension_types: Vec<ExtensionType>,
+    },
+    /// Removes disabled or empty extensions from a token account, compacting
+    /// the remaining extension data and shrinking the account. The rent freed
+    /// by the smaller account is refunded to the account's owner.
+    ///
+    /// Only `MemoTransfer`, `MemoTransferRules`, `CpiGuard`,
+    /// `CpiGuardAllowlist`, `TransferLimit`, `DelegateExpiry`,
+    /// `MultiDelegate`, `ConfidentialTransferAccount` and
+    /// `ConfidentialTransferFeeAmount` can be removed, and never if they are
+    /// required by the mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner
+    ///   0. `[writable]` The account to shrink.
+    ///   1. `[]` The token mint.
+    ///   2. `[signer, writable]` The account's owner, receiving the freed rent.
+    ///
+    ///   * Multisignature owner
+    ///   0. `[writable]` The account to shrink.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The account's multisignature owner, receiving the
+    ///      freed rent.
+    ///   3. ..`3+M` `[signer]` M signer accounts.
+    RemoveAccountExtensions {
+        /// Extension types to remove from the account
+        extension_types: Vec<ExtensionType>,
+    },
+    /// The common instruction prefix for permanent delegate extension
+    /// instructions.
+    ///
+    /// See `extension::permanent_delegate::instruction::PermanentDelegateInstruction`
+    /// for further details about the extended instructions that share this
+    /// instruction prefix
+    PermanentDelegateExtension,
+    /// The common instruction prefix for delegate expiry extension
+    /// instructions.
+    ///
+    /// See `extension::delegate_expiry::instruction::DelegateExpiryInstruction`
+    /// for further details about the extended instructions that share this
+    /// instruction prefix
+    DelegateExpiryExtension,
+    /// The common instruction prefix for multiple delegates extension
+    /// instructions.
+    ///
+    /// See `extension::multi_delegate::instruction::MultiDelegateInstruction`
+    /// for further details about the extended instructions that share this
+    /// instruction prefix
+    MultiDelegateExtension,
+    /// The common instruction prefix for supply hook extension instructions.
+    ///
+    /// See `extension::supply_hook::instruction::SupplyHookInstruction` for
+    /// further details about the extended instructions that share this
+    /// instruction prefix
+    SupplyHookExtension,
+    /// The common instruction prefix for non-transferable extension
+    /// instructions.
+    ///
+    /// See `extension::non_transferable::instruction::NonTransferableInstruction`
+    /// for further details about the extended instructions that share this
+    /// instruction prefix
+    NonTransferableExtension,
 }
 impl<'a> TokenInstruction<'a> {
     /// Unpacks a byte buffer into a
@@ -862,6 +1042,37 @@ impl<'a> TokenInstruction<'a> {
             42 => Self::ConfidentialMintBurnExtension,
             43 => Self::ScaledUiAmountExtension,
             44 => Self::PausableExtension,
+            45 => Self::TokenGroupExtension,
+            46 => Self::SupplyCapExtension,
+            47 => Self::TransferLimitExtension,
+            48 => Self::VestingExtension,
+            49 => Self::Batch,
+            50 => {
+                let (threshold, weights) = Self::unpack_u16(rest)?;
+                Self::InitializeWeightedMultisig {
+                    threshold,
+                    weights: weights.to_vec(),
+                }
+            }
+            51 => {
+                let mut extension_types = vec![];
+                for chunk in rest.chunks(size_of::<ExtensionType>()) {
+                    extension_types.push(chunk.try_into()?);
+                }
+                Self::ReallocateMint { extension_types }
+            }
+            52 => {
+                let mut extension_types = vec![];
+                for chunk in rest.chunks(size_of::<ExtensionType>()) {
+                    extension_types.push(chunk.try_into()?);
+                }
+                Self::RemoveAccountExtensions { extension_types }
+            }
+            53 => Self::PermanentDelegateExtension,
+            54 => Self::DelegateExpiryExtension,
+            55 => Self::MultiDelegateExtension,
+            56 => Self::SupplyHookExtension,
+            57 => Self::NonTransferableExtension,
             _ => return Err(TokenError::InvalidInstruction.into()),
         })
     }
@@ -1042,6 +1253,53 @@ impl<'a> TokenInstruction<'a> {
             &Self::PausableExtension => {
                 buf.push(44);
             }
+            &Self::TokenGroupExtension => {
+                buf.push(45);
+            }
+            &Self::SupplyCapExtension => {
+                buf.push(46);
+            }
+            &Self::TransferLimitExtension => {
+                buf.push(47);
+            }
+            &Self::VestingExtension => {
+                buf.push(48);
+            }
+            &Self::Batch => {
+                buf.push(49);
+            }
+            Self::InitializeWeightedMultisig { threshold, weights } => {
+                buf.push(50);
+                buf.extend_from_slice(&threshold.to_le_bytes());
+                buf.extend_from_slice(weights);
+            }
+            Self::ReallocateMint { extension_types } => {
+                buf.push(51);
+                for extension_type in extension_types {
+                    buf.extend_from_slice(&<[u8; 2]>::from(*extension_type));
+                }
+            }
+            Self::RemoveAccountExtensions { extension_types } => {
+                buf.push(52);
+                for extension_type in extension_types {
+                    buf.extend_from_slice(&<[u8; 2]>::from(*extension_type));
+                }
+            }
+            &Self::PermanentDelegateExtension => {
+                buf.push(53);
+            }
+            &Self::DelegateExpiryExtension => {
+                buf.push(54);
+            }
+            &Self::MultiDelegateExtension => {
+                buf.push(55);
+            }
+            &Self::SupplyHookExtension => {
+                buf.push(56);
+            }
+            &Self::NonTransferableExtension => {
+                buf.push(57);
+            }
         };
         buf
     }
@@ -1145,10 +1403,20 @@ pub enum AuthorityType {
     ScaledUiAmount,
     /// Authority to pause or resume minting / transferring / burning
     Pause,
+    /// Authority to lower the maximum supply
+    SupplyCap,
+    /// Authority to lock tokens under a vesting schedule
+    Vesting,
+    /// Authority to set the supply hook program id
+    SupplyHookProgramId,
+    /// Authority to recover balances of a non-transferable mint
+    RecoveryAuthority,
+    /// Authority to configure the transfer limits of accounts
+    TransferLimitPolicy,
 }
 
 impl AuthorityType {
-    fn into(&self) -> u8 {
+    pub(crate) fn into(&self) -> u8 {
         match self {
             AuthorityType::MintTokens => 0,
             AuthorityType::FreezeAccount => 1,
@@ -1167,6 +1435,11 @@ impl AuthorityType {
             AuthorityType::GroupMemberPointer => 14,
             AuthorityType::ScaledUiAmount => 15,
             AuthorityType::Pause => 16,
+            AuthorityType::SupplyCap => 17,
+            AuthorityType::Vesting => 18,
+            AuthorityType::SupplyHookProgramId => 19,
+            AuthorityType::RecoveryAuthority => 20,
+            AuthorityType::TransferLimitPolicy => 21,
         }
     }
 
@@ -1189,6 +1462,11 @@ impl AuthorityType {
             14 => Ok(AuthorityType::GroupMemberPointer),
             15 => Ok(AuthorityType::ScaledUiAmount),
             16 => Ok(AuthorityType::Pause),
+            17 => Ok(AuthorityType::SupplyCap),
+            18 => Ok(AuthorityType::Vesting),
+            19 => Ok(AuthorityType::SupplyHookProgramId),
+            20 => Ok(AuthorityType::RecoveryAuthority),
+            21 => Ok(AuthorityType::TransferLimitPolicy),
             _ => Err(TokenError::InvalidInstruction.into()),
         }
     }
@@ -1383,6 +1661,42 @@ pub fn initialize_multisig2(
     })
 }
 
+/// Creates a `InitializeWeightedMultisig` instruction.
+pub fn initialize_weighted_multisig(
+    token_program_id: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    weights: &[u8],
+    threshold: u16,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let total_weight: u16 = weights.iter().map(|weight| *weight as u16).sum();
+    if !(MIN_SIGNERS..=MAX_WEIGHTED_SIGNERS).contains(&signer_pubkeys.len())
+        || weights.len() != signer_pubkeys.len()
+        || threshold == 0
+        || threshold > total_weight
+    {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let data = TokenInstruction::InitializeWeightedMultisig {
+        threshold,
+        weights: weights.to_vec(),
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(1 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*multisig_pubkey, false));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, false));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
 /// Creates a `Transfer` instruction.
 #[deprecated(
     since = "4.0.0",
@@ -1908,6 +2222,68 @@ pub fn reallocate(
     })
 }
 
+/// Creates a `ReallocateMint` instruction
+pub fn reallocate_mint(
+    token_program_id: &Pubkey,
+    mint_pubkey: &Pubkey,
+    payer: &Pubkey,
+    mint_authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    extension_types: &[ExtensionType],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+
+    let mut accounts = Vec::with_capacity(4 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*mint_pubkey, false));
+    accounts.push(AccountMeta::new(*payer, true));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new_readonly(
+        *mint_authority_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data: TokenInstruction::ReallocateMint {
+            extension_types: extension_types.to_vec(),
+        }
+        .pack(),
+    })
+}
+
+/// Creates a `RemoveAccountExtensions` instruction
+pub fn remove_account_extensions(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    extension_types: &[ExtensionType],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+
+    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*account_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
+    accounts.push(AccountMeta::new(*owner_pubkey, signer_pubkeys.is_empty()));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data: TokenInstruction::RemoveAccountExtensions {
+            extension_types: extension_types.to_vec(),
+        }
+        .pack(),
+    })
+}
+
 /// Creates a `CreateNativeMint` instruction
 pub fn create_native_mint(
     token_program_id: &Pubkey,
@@ -2053,6 +2429,40 @@ pub fn withdraw_excess_lamports(
     })
 }
 
+/// Creates a `Batch` instruction that executes the given token instructions
+/// in order
+pub fn batch(
+    token_program_id: &Pubkey,
+    instructions: &[Instruction],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+
+    let mut accounts = vec![];
+    let mut data = TokenInstruction::Batch.pack();
+    for instruction in instructions {
+        if instruction.program_id != *token_program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let account_start =
+            u8::try_from(accounts.len()).map_err(|_| ProgramError::InvalidArgument)?;
+        let account_count =
+            u8::try_from(instruction.accounts.len()).map_err(|_| ProgramError::InvalidArgument)?;
+        let data_len =
+            u16::try_from(instruction.data.len()).map_err(|_| ProgramError::InvalidArgument)?;
+        data.push(account_start);
+        data.push(account_count);
+        data.extend_from_slice(&data_len.to_le_bytes());
+        data.extend_from_slice(&instruction.data);
+        accounts.extend_from_slice(&instruction.accounts);
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
 #[cfg(test)]
 mod test {
     use {super::*, crate::pod_instruction::*, proptest::prelude::*};
@@ -2433,6 +2843,62 @@ mod test {
         assert_eq!(pod.m, m);
     }
 
+    #[test]
+    fn test_initialize_weighted_multisig_packing() {
+        let check = TokenInstruction::InitializeWeightedMultisig {
+            threshold: 258,
+            weights: vec![2, 1, 1],
+        };
+        let packed = check.pack();
+        let expect = Vec::from([50u8, 2, 1, 2, 1, 1]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let instruction_type = decode_instruction_type::<PodTokenInstruction>(&packed).unwrap();
+        assert_eq!(
+            instruction_type,
+            PodTokenInstruction::InitializeWeightedMultisig
+        );
+    }
+
+    #[test]
+    fn test_reallocate_mint_packing() {
+        let check = TokenInstruction::ReallocateMint {
+            extension_types: vec![ExtensionType::MetadataPointer, ExtensionType::Pausable],
+        };
+        let packed = check.pack();
+        let mut expect = vec![51u8];
+        expect.extend_from_slice(&<[u8; 2]>::from(ExtensionType::MetadataPointer));
+        expect.extend_from_slice(&<[u8; 2]>::from(ExtensionType::Pausable));
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let instruction_type = decode_instruction_type::<PodTokenInstruction>(&packed).unwrap();
+        assert_eq!(instruction_type, PodTokenInstruction::ReallocateMint);
+    }
+
+    #[test]
+    fn test_remove_account_extensions_packing() {
+        let check = TokenInstruction::RemoveAccountExtensions {
+            extension_types: vec![ExtensionType::MemoTransfer, ExtensionType::CpiGuard],
+        };
+        let packed = check.pack();
+        let mut expect = vec![52u8];
+        expect.extend_from_slice(&<[u8; 2]>::from(ExtensionType::MemoTransfer));
+        expect.extend_from_slice(&<[u8; 2]>::from(ExtensionType::CpiGuard));
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let instruction_type = decode_instruction_type::<PodTokenInstruction>(&packed).unwrap();
+        assert_eq!(
+            instruction_type,
+            PodTokenInstruction::RemoveAccountExtensions
+        );
+    }
+
     #[test]
     fn test_initialize_mint2_packing() {
         let decimals = 2;
@@ -2618,6 +3084,50 @@ mod test {
         assert_eq!(*pod_delegate, delegate);
     }
 
+    #[test]
+    fn test_batch_packing() {
+        let token_program_id = crate::id();
+        let mint = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mint_to = mint_to(
+            &token_program_id,
+            &mint,
+            &account,
+            &owner,
+            &[],
+            U256::new(1),
+        )
+        .unwrap();
+        let burn = burn(
+            &token_program_id,
+            &account,
+            &mint,
+            &owner,
+            &[],
+            U256::new(2),
+        )
+        .unwrap();
+        let check = batch(&token_program_id, &[mint_to.clone(), burn.clone()]).unwrap();
+
+        let mut expect = vec![49u8];
+        expect.extend_from_slice(&[0, 3, 33, 0]);
+        expect.extend_from_slice(&mint_to.data);
+        expect.extend_from_slice(&[3, 3, 33, 0]);
+        expect.extend_from_slice(&burn.data);
+        assert_eq!(check.data, expect);
+        assert_eq!(check.accounts, [mint_to.accounts, burn.accounts].concat());
+
+        let instruction_type = decode_instruction_type::<PodTokenInstruction>(&check.data).unwrap();
+        assert_eq!(instruction_type, PodTokenInstruction::Batch);
+
+        let other_program = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+        assert_eq!(
+            batch(&token_program_id, &[other_program]),
+            Err(ProgramError::IncorrectProgramId)
+        );
+    }
+
     macro_rules! test_instruction {
         ($a:ident($($b:tt)*)) => {
             let instruction_v3 = spl_token::instruction::$a($($b)*).unwrap();
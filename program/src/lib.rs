@@ -5,6 +5,7 @@
 //! An ERC20-like Token program for the Solana blockchain
 
 pub mod error;
+pub mod event;
 pub mod extension;
 pub mod generic_token_account;
 pub mod instruction;
@@ -17,6 +18,7 @@ pub mod processor;
 #[cfg(feature = "serde-traits")]
 pub mod serialization;
 pub mod state;
+pub mod ui_amount;
 
 #[cfg(not(feature = "no-entrypoint"))]
 mod entrypoint;
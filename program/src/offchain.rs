@@ -4,10 +4,18 @@ use ethnum::U256;
 pub use spl_transfer_hook_interface::offchain::{AccountDataResult, AccountFetchError};
 use {
     crate::{
-        extension::{transfer_fee, transfer_hook, StateWithExtensions},
+        extension::{
+            supply_hook::{self, interface::ExecuteInstruction},
+            transfer_fee, transfer_hook, StateWithExtensions,
+        },
         state::Mint,
     },
-    solana_program::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    spl_tlv_account_resolution::state::ExtraAccountMetaList,
     spl_transfer_hook_interface::offchain::add_extra_account_metas_for_execute,
     std::future::Future,
 };
@@ -204,13 +212,200 @@ where
     Ok(())
 }
 
+/// Offchain helper to create a `MintToChecked` instruction with all
+/// additional required account metas for a mint, including the ones required
+/// by the supply hook.
+///
+/// To be client-agnostic and to avoid pulling in the full solana-sdk, this
+/// simply takes a function that will return its data as `Future<Vec<u8>>` for
+/// the given address. Can be called in the following way:
+///
+/// ```rust,ignore
+/// let instruction = create_mint_to_checked_instruction_with_extra_metas(
+///     &spl_token_2022::id(),
+///     &mint,
+///     &destination,
+///     &authority,
+///     &[],
+///     amount,
+///     decimals,
+///     |address| self.client.get_account(&address).map_ok(|opt| opt.map(|acc| acc.data)),
+/// )
+/// .await?
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub async fn create_mint_to_checked_instruction_with_extra_metas<F, Fut>(
+    token_program_id: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: U256,
+    decimals: u8,
+    fetch_account_data_fn: F,
+) -> Result<Instruction, AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>,
+{
+    let mut mint_to_instruction = crate::instruction::mint_to_checked(
+        token_program_id,
+        mint_pubkey,
+        destination_pubkey,
+        authority_pubkey,
+        signer_pubkeys,
+        amount,
+        decimals,
+    )?;
+
+    // the mint stands in for the source of the new tokens
+    add_extra_account_metas_for_supply_hook(
+        &mut mint_to_instruction,
+        mint_pubkey,
+        mint_pubkey,
+        destination_pubkey,
+        authority_pubkey,
+        amount,
+        fetch_account_data_fn,
+    )
+    .await?;
+
+    Ok(mint_to_instruction)
+}
+
+/// Offchain helper to create a `BurnChecked` instruction with all additional
+/// required account metas for a burn, including the ones required by the
+/// supply hook.
+///
+/// To be client-agnostic and to avoid pulling in the full solana-sdk, this
+/// simply takes a function that will return its data as `Future<Vec<u8>>` for
+/// the given address. Can be called in the following way:
+///
+/// ```rust,ignore
+/// let instruction = create_burn_checked_instruction_with_extra_metas(
+///     &spl_token_2022::id(),
+///     &source,
+///     &mint,
+///     &authority,
+///     &[],
+///     amount,
+///     decimals,
+///     |address| self.client.get_account(&address).map_ok(|opt| opt.map(|acc| acc.data)),
+/// )
+/// .await?
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub async fn create_burn_checked_instruction_with_extra_metas<F, Fut>(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: U256,
+    decimals: u8,
+    fetch_account_data_fn: F,
+) -> Result<Instruction, AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>,
+{
+    let mut burn_instruction = crate::instruction::burn_checked(
+        token_program_id,
+        source_pubkey,
+        mint_pubkey,
+        authority_pubkey,
+        signer_pubkeys,
+        amount,
+        decimals,
+    )?;
+
+    // the mint stands in for the destination of the burnt tokens
+    add_extra_account_metas_for_supply_hook(
+        &mut burn_instruction,
+        source_pubkey,
+        mint_pubkey,
+        mint_pubkey,
+        authority_pubkey,
+        amount,
+        fetch_account_data_fn,
+    )
+    .await?;
+
+    Ok(burn_instruction)
+}
+
+/// Offchain helper to add required account metas to a mint or burn
+/// instruction, including the ones required by the supply hook.
+///
+/// For a mint, pass the mint as `source_pubkey`, and for a burn, pass it as
+/// `destination_pubkey`, matching the accounts the supply hook program
+/// receives.
+pub async fn add_extra_account_metas_for_supply_hook<F, Fut>(
+    instruction: &mut Instruction,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    amount: U256,
+    fetch_account_data_fn: F,
+) -> Result<(), AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>,
+{
+    let mint_data = fetch_account_data_fn(*mint_pubkey)
+        .await?
+        .ok_or(ProgramError::InvalidAccountData)?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+
+    if let Some(program_id) = supply_hook::get_program_id(&mint) {
+        let validate_state_pubkey =
+            supply_hook::interface::get_extra_account_metas_address(mint_pubkey, &program_id);
+        let validate_state_data = fetch_account_data_fn(validate_state_pubkey).await?;
+
+        // resolve the extra accounts against the instruction the hook receives
+        let mut execute_instruction = supply_hook::interface::execute(
+            &program_id,
+            source_pubkey,
+            mint_pubkey,
+            destination_pubkey,
+            authority_pubkey,
+            amount,
+        );
+        execute_instruction
+            .accounts
+            .push(AccountMeta::new_readonly(validate_state_pubkey, false));
+        let resolved_index = execute_instruction.accounts.len();
+        if let Some(validate_state_data) = validate_state_data {
+            ExtraAccountMetaList::add_to_instruction::<ExecuteInstruction, _, _>(
+                &mut execute_instruction,
+                fetch_account_data_fn,
+                &validate_state_data,
+            )
+            .await?;
+        }
+
+        instruction
+            .accounts
+            .extend_from_slice(&execute_instruction.accounts[resolved_index..]);
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(program_id, false));
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(validate_state_pubkey, false));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use {
         super::*,
         crate::extension::{
-            transfer_hook::TransferHook, BaseStateWithExtensionsMut, ExtensionType,
-            StateWithExtensionsMut,
+            supply_hook::SupplyHook, transfer_hook::TransferHook, BaseStateWithExtensionsMut,
+            ExtensionType, StateWithExtensionsMut,
         },
         solana_program::{instruction::AccountMeta, program_option::COption},
         solana_program_test::tokio,
@@ -228,6 +423,8 @@ mod tests {
     const TRANSFER_HOOK_PROGRAM_ID: Pubkey = Pubkey::new_from_array([2u8; 32]);
     const EXTRA_META_1: Pubkey = Pubkey::new_from_array([3u8; 32]);
     const EXTRA_META_2: Pubkey = Pubkey::new_from_array([4u8; 32]);
+    const SUPPLY_HOOK_MINT_PUBKEY: Pubkey = Pubkey::new_from_array([5u8; 32]);
+    const SUPPLY_HOOK_PROGRAM_ID: Pubkey = Pubkey::new_from_array([6u8; 32]);
 
     // Mock to return the mint data or the validation state account data
     async fn mock_fetch_account_data_fn(address: Pubkey) -> AccountDataResult {
@@ -249,6 +446,25 @@ mod tests {
             mint.pack_base();
             mint.init_account_type().unwrap();
 
+            Ok(Some(data))
+        } else if address == SUPPLY_HOOK_MINT_PUBKEY {
+            let mint_len =
+                ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::SupplyHook])
+                    .unwrap();
+            let mut data = vec![0u8; mint_len];
+            let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+
+            let extension = mint.init_extension::<SupplyHook>(true).unwrap();
+            extension.program_id =
+                OptionalNonZeroPubkey::try_from(Some(SUPPLY_HOOK_PROGRAM_ID)).unwrap();
+
+            mint.base.mint_authority = COption::Some(Pubkey::new_unique());
+            mint.base.decimals = DECIMALS;
+            mint.base.is_initialized = true;
+            mint.base.freeze_authority = COption::None;
+            mint.pack_base();
+            mint.init_account_type().unwrap();
+
             Ok(Some(data))
         } else if address
             == get_extra_account_metas_address(&MINT_PUBKEY, &TRANSFER_HOOK_PROGRAM_ID)
@@ -285,6 +501,21 @@ mod tests {
             let mut data = vec![0u8; account_size];
             ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_metas)?;
             Ok(Some(data))
+        } else if address
+            == supply_hook::interface::get_extra_account_metas_address(
+                &SUPPLY_HOOK_MINT_PUBKEY,
+                &SUPPLY_HOOK_PROGRAM_ID,
+            )
+        {
+            let extra_metas =
+                vec![ExtraAccountMeta::new_with_pubkey(&EXTRA_META_1, false, false).unwrap()];
+            let account_size = ExtraAccountMetaList::size_of(extra_metas.len()).unwrap();
+            let mut data = vec![0u8; account_size];
+            ExtraAccountMetaList::init::<supply_hook::interface::ExecuteInstruction>(
+                &mut data,
+                &extra_metas,
+            )?;
+            Ok(Some(data))
         } else {
             Ok(None)
         }
@@ -484,4 +715,80 @@ mod tests {
 
         assert_eq!(instruction.accounts, check_metas);
     }
+
+    #[tokio::test]
+    async fn test_create_mint_to_and_burn_checked_instructions_with_extra_metas() {
+        let account = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let amount = U256::from(100u64);
+        let validate_state_pubkey = supply_hook::interface::get_extra_account_metas_address(
+            &SUPPLY_HOOK_MINT_PUBKEY,
+            &SUPPLY_HOOK_PROGRAM_ID,
+        );
+        assert_ne!(
+            validate_state_pubkey,
+            get_extra_account_metas_address(&SUPPLY_HOOK_MINT_PUBKEY, &SUPPLY_HOOK_PROGRAM_ID)
+        );
+
+        let instruction = create_mint_to_checked_instruction_with_extra_metas(
+            &crate::id(),
+            &SUPPLY_HOOK_MINT_PUBKEY,
+            &account,
+            &authority,
+            &[],
+            amount,
+            DECIMALS,
+            mock_fetch_account_data_fn,
+        )
+        .await
+        .unwrap();
+
+        let check_metas = [
+            AccountMeta::new(SUPPLY_HOOK_MINT_PUBKEY, false),
+            AccountMeta::new(account, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(EXTRA_META_1, false),
+            AccountMeta::new_readonly(SUPPLY_HOOK_PROGRAM_ID, false),
+            AccountMeta::new_readonly(validate_state_pubkey, false),
+        ];
+        assert_eq!(instruction.accounts, check_metas);
+
+        let instruction = create_burn_checked_instruction_with_extra_metas(
+            &crate::id(),
+            &account,
+            &SUPPLY_HOOK_MINT_PUBKEY,
+            &authority,
+            &[],
+            amount,
+            DECIMALS,
+            mock_fetch_account_data_fn,
+        )
+        .await
+        .unwrap();
+
+        let check_metas = [
+            AccountMeta::new(account, false),
+            AccountMeta::new(SUPPLY_HOOK_MINT_PUBKEY, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(EXTRA_META_1, false),
+            AccountMeta::new_readonly(SUPPLY_HOOK_PROGRAM_ID, false),
+            AccountMeta::new_readonly(validate_state_pubkey, false),
+        ];
+        assert_eq!(instruction.accounts, check_metas);
+
+        // no supply hook, no extra metas
+        let instruction = create_burn_checked_instruction_with_extra_metas(
+            &crate::id(),
+            &account,
+            &MINT_PUBKEY,
+            &authority,
+            &[],
+            amount,
+            DECIMALS,
+            mock_fetch_account_data_fn,
+        )
+        .await
+        .unwrap();
+        assert_eq!(instruction.accounts.len(), 3);
+    }
 }
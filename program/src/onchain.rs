@@ -1,5 +1,5 @@
-//! On-chain program invoke helper to perform on-chain `transfer_checked` with
-//! correct accounts
+//! On-chain program invoke helpers to perform on-chain `transfer_checked` with
+//! correct accounts, or to execute a batch of token instructions
 
 use {
     crate::{
@@ -9,8 +9,11 @@ use {
     },
     ethnum::U256,
     solana_program::{
-        account_info::AccountInfo, entrypoint::ProgramResult, instruction::AccountMeta,
-        program::invoke_signed, pubkey::Pubkey,
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+        pubkey::Pubkey,
     },
     spl_transfer_hook_interface::onchain::add_extra_accounts_for_execute_cpi,
 };
@@ -147,3 +150,16 @@ pub fn invoke_transfer_checked_with_fee<'a>(
 
     invoke_signed(&cpi_instruction, &cpi_account_infos, seeds)
 }
+
+/// Helper to CPI into token-2022 on-chain, executing the given token
+/// instructions in order as a single `Batch` instruction. The account infos
+/// must include every account used by the instructions.
+pub fn invoke_batch<'a>(
+    token_program_id: &Pubkey,
+    instructions: &[Instruction],
+    account_infos: &[AccountInfo<'a>],
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let cpi_instruction = instruction::batch(token_program_id, instructions)?;
+    invoke_signed(&cpi_instruction, account_infos, seeds)
+}
@@ -4,7 +4,8 @@
 use crate::state::{Account, Mint, Multisig};
 use {
     crate::{
-        instruction::MAX_SIGNERS,
+        extension::AccountType,
+        instruction::{MAX_SIGNERS, MAX_WEIGHTED_SIGNERS},
         state::{AccountState, PackedSizeOf},
     },
     bytemuck::{Pod, Zeroable},
@@ -15,7 +16,7 @@ use {
     spl_pod::{
         bytemuck::pod_get_packed_len,
         optional_keys::OptionalNonZeroPubkey,
-        primitives::{PodBool, PodU256, PodU64},
+        primitives::{PodBool, PodU16, PodU256, PodU64},
     },
 };
 
@@ -156,6 +157,54 @@ impl From<Multisig> for PodMultisig {
     }
 }
 
+/// Weighted multisignature data, where each signer contributes its weight
+/// towards the threshold.
+///
+/// Like mints and accounts with extensions, weighted multisigs store their
+/// `AccountType` at offset `Account::LEN`, so they can't be mistaken for
+/// either based on their length.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct PodWeightedMultisig {
+    /// Total weight of the signers required
+    pub threshold: PodU16,
+    /// Number of valid signers
+    pub n: u8,
+    /// If `true`, this structure has been initialized
+    pub is_initialized: PodBool,
+    /// Weight of each signer
+    pub weights: [u8; MAX_WEIGHTED_SIGNERS],
+    /// Padding up to `account_type`
+    pub padding: [[u8; 43]; 3],
+    /// Always `AccountType::WeightedMultisig` once initialized
+    pub account_type: u8,
+    /// Signer public keys
+    pub signers: [Pubkey; MAX_WEIGHTED_SIGNERS],
+}
+impl PodWeightedMultisig {
+    /// Check if the account data is an initialized weighted multisig
+    pub fn is_weighted_multisig(account_data: &[u8]) -> bool {
+        account_data.len() == Self::SIZE_OF
+            && account_data[PodAccount::SIZE_OF] == AccountType::WeightedMultisig as u8
+    }
+
+    /// Total weight of all valid signers
+    pub fn total_weight(&self) -> u16 {
+        self.weights[..self.n as usize]
+            .iter()
+            .map(|weight| *weight as u16)
+            .sum()
+    }
+}
+impl IsInitialized for PodWeightedMultisig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized.into()
+    }
+}
+impl PackedSizeOf for PodWeightedMultisig {
+    const SIZE_OF: usize = pod_get_packed_len::<Self>();
+}
+
 /// `COption<T>` stored as a Pod type
 #[repr(C, packed)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
@@ -312,4 +361,21 @@ pub(crate) mod test {
         let pod_multisig = pod_from_bytes::<PodMultisig>(TEST_MULTISIG_SLICE).unwrap();
         assert_eq!(*pod_multisig, PodMultisig::from(TEST_MULTISIG));
     }
+
+    #[test]
+    fn pod_weighted_multisig_account_type() {
+        assert_eq!(
+            std::mem::offset_of!(PodWeightedMultisig, account_type),
+            PodAccount::SIZE_OF
+        );
+        let mut data = vec![0; PodWeightedMultisig::SIZE_OF];
+        assert!(!PodWeightedMultisig::is_weighted_multisig(&data));
+        data[PodAccount::SIZE_OF] = AccountType::Account as u8;
+        assert!(!PodWeightedMultisig::is_weighted_multisig(&data));
+        data[PodAccount::SIZE_OF] = AccountType::WeightedMultisig as u8;
+        assert!(PodWeightedMultisig::is_weighted_multisig(&data));
+        assert!(!PodWeightedMultisig::is_weighted_multisig(
+            &data[..PodWeightedMultisig::SIZE_OF - 1]
+        ));
+    }
 }
@@ -117,6 +117,22 @@ pub(crate) enum PodTokenInstruction {
     ConfidentialMintBurnExtension,
     ScaledUiAmountExtension,
     PausableExtension,
+    // 45
+    TokenGroupExtension,
+    SupplyCapExtension,
+    TransferLimitExtension,
+    VestingExtension,
+    Batch,
+    // 50
+    InitializeWeightedMultisig, // u16 threshold, then one u8 weight per signer
+    ReallocateMint,             // &[ExtensionType]
+    RemoveAccountExtensions,    // &[ExtensionType]
+    PermanentDelegateExtension,
+    DelegateExpiryExtension,
+    // 55
+    MultiDelegateExtension,
+    SupplyHookExtension,
+    NonTransferableExtension,
 }
 
 fn unpack_pubkey_option(input: &[u8]) -> Result<PodCOption<Pubkey>, ProgramError> {
@@ -201,7 +217,10 @@ mod tests {
                     let _ = std::str::from_utf8(&input[1..])
                         .map_err(|_| ProgramError::InvalidInstructionData)?;
                 }
-                PodTokenInstruction::GetAccountDataSize | PodTokenInstruction::Reallocate => {
+                PodTokenInstruction::GetAccountDataSize
+                | PodTokenInstruction::Reallocate
+                | PodTokenInstruction::ReallocateMint
+                | PodTokenInstruction::RemoveAccountExtensions => {
                     let _ = input[1..]
                         .chunks(std::mem::size_of::<ExtensionType>())
                         .map(ExtensionType::try_from)
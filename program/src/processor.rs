@@ -4,43 +4,61 @@ use {
     crate::{
         check_program_account,
         error::TokenError,
+        event::{
+            self, BurnEvent, FreezeAccountEvent, MintToEvent, SetAuthorityEvent, ThawAccountEvent,
+            TransferEvent,
+        },
         extension::{
             confidential_mint_burn::{self, ConfidentialMintBurn},
             confidential_transfer::{self, ConfidentialTransferAccount, ConfidentialTransferMint},
             confidential_transfer_fee::{
                 self, ConfidentialTransferFeeAmount, ConfidentialTransferFeeConfig,
             },
-            cpi_guard::{self, in_cpi, CpiGuard},
+            cpi_guard::{self, cpi_guard_blocks, CpiGuard},
             default_account_state::{self, DefaultAccountState},
+            delegate_expiry::{self, delegate_expired, DelegateExpiry},
             group_member_pointer::{self, GroupMemberPointer},
             group_pointer::{self, GroupPointer},
             immutable_owner::ImmutableOwner,
             interest_bearing_mint::{self, InterestBearingConfig},
-            memo_transfer::{self, check_previous_sibling_instruction_is_memo, memo_required},
+            memo_transfer::{self, check_incoming_transfer_memo, check_outgoing_transfer_memo},
             metadata_pointer::{self, MetadataPointer},
             mint_close_authority::MintCloseAuthority,
-            non_transferable::{NonTransferable, NonTransferableAccount},
-            pausable::{self, PausableAccount, PausableConfig},
-            permanent_delegate::{get_permanent_delegate, PermanentDelegate},
+            multi_delegate::{self, find_active_delegate, MultiDelegate},
+            non_transferable::{
+                self, NonTransferable, NonTransferableAccount, NonTransferableRecovery,
+            },
+            pausable::{self, PausableAccount, PausableConfig, PausableOperation},
+            permanent_delegate::{
+                self, get_permanent_delegate_for_burn, get_permanent_delegate_for_transfer,
+                PermanentDelegate,
+            },
             reallocate,
             scaled_ui_amount::{self, ScaledUiAmountConfig},
+            supply_cap::{self, SupplyCapConfig},
+            supply_hook::{self, SupplyHook},
             token_group, token_metadata,
-            transfer_fee::{self, TransferFeeAmount, TransferFeeConfig},
+            transfer_fee::{self, TransferFeeAmount, TransferFeeConfig, TransferFeeTiers},
             transfer_hook::{self, TransferHook, TransferHookAccount},
+            transfer_limit::{self, TransferLimit, TransferLimitPolicy},
+            vesting::{self, VestingAccount, VestingConfig},
             AccountType, BaseStateWithExtensions, BaseStateWithExtensionsMut, ExtensionType,
             PodStateWithExtensions, PodStateWithExtensionsMut,
         },
         instruction::{
             decode_instruction_data, decode_instruction_type, is_valid_signer_index, AuthorityType,
-            MAX_SIGNERS,
+            TokenInstruction, MAX_SIGNERS, MAX_WEIGHTED_SIGNERS, MIN_SIGNERS,
         },
         native_mint,
-        pod::{PodAccount, PodCOption, PodMint, PodMultisig},
+        pod::{PodAccount, PodCOption, PodMint, PodMultisig, PodWeightedMultisig},
         pod_instruction::{
             decode_instruction_data_with_coption_pubkey, AmountCheckedData, AmountData,
             InitializeMintData, InitializeMultisigData, PodTokenInstruction, SetAuthorityData,
         },
         state::{Account, AccountState, Mint, PackedSizeOf},
+        ui_amount::{
+            amount_to_ui_amount_for_mint, try_ui_amount_into_amount_for_mint, uses_unix_timestamp,
+        },
     },
     ethnum::U256,
     solana_program::{
@@ -53,7 +71,7 @@ use {
         program_pack::Pack,
         pubkey::Pubkey,
         system_instruction, system_program,
-        sysvar::{rent::Rent, Sysvar},
+        sysvar::{instructions, rent::Rent, Sysvar},
     },
     spl_pod::{
         bytemuck::{pod_from_bytes, pod_from_bytes_mut},
@@ -287,6 +305,64 @@ impl Processor {
         Self::_process_initialize_multisig(accounts, m, false)
     }
 
+    /// Processes a [`InitializeWeightedMultisig`](enum.TokenInstruction.html)
+    /// instruction.
+    pub fn process_initialize_weighted_multisig(
+        accounts: &[AccountInfo],
+        threshold: u16,
+        weights: &[u8],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let multisig_info = next_account_info(account_info_iter)?;
+        let multisig_info_data_len = multisig_info.data_len();
+        let rent = Rent::get()?;
+
+        let mut multisig_data = multisig_info.data.borrow_mut();
+        let multisig = pod_from_bytes_mut::<PodWeightedMultisig>(&mut multisig_data)?;
+        // A mint or account of the same size keeps its account type at the
+        // same offset and may hold data before being initialized, so only an
+        // untouched buffer can become a weighted multisig
+        if bool::from(multisig.is_initialized)
+            || multisig.account_type != AccountType::Uninitialized as u8
+            || bytemuck::bytes_of(multisig).iter().any(|byte| *byte != 0)
+        {
+            return Err(TokenError::AlreadyInUse.into());
+        }
+
+        if !rent.is_exempt(multisig_info.lamports(), multisig_info_data_len) {
+            return Err(TokenError::NotRentExempt.into());
+        }
+
+        let signer_infos = account_info_iter.as_slice();
+        if !(MIN_SIGNERS..=MAX_WEIGHTED_SIGNERS).contains(&signer_infos.len())
+            || weights.len() != signer_infos.len()
+        {
+            return Err(TokenError::InvalidNumberOfProvidedSigners.into());
+        }
+        let total_weight: u16 = weights.iter().map(|weight| *weight as u16).sum();
+        if threshold == 0 || threshold > total_weight {
+            return Err(TokenError::InvalidNumberOfRequiredSigners.into());
+        }
+        multisig.n = signer_infos.len() as u8;
+        for (i, (signer_info, weight)) in signer_infos.iter().zip(weights).enumerate() {
+            multisig.signers[i] = *signer_info.key;
+            multisig.weights[i] = *weight;
+        }
+        multisig.threshold = threshold.into();
+        multisig.account_type = AccountType::WeightedMultisig.into();
+        multisig.is_initialized = true.into();
+
+        Ok(())
+    }
+
+    /// Checks the fee exemption of a transfer source or destination, before
+    /// it is borrowed mutably
+    pub(crate) fn is_account_fee_exempt(account_info: &AccountInfo) -> Result<bool, ProgramError> {
+        let account_data = account_info.try_borrow_data()?;
+        let account = PodStateWithExtensions::<PodAccount>::unpack(&account_data)?;
+        Ok(transfer_fee::is_fee_exempt(&account))
+    }
+
     /// Processes a [`Transfer`](enum.TokenInstruction.html) instruction.
     pub fn process_transfer(
         program_id: &Pubkey,
@@ -294,6 +370,46 @@ impl Processor {
         amount: U256,
         expected_decimals: Option<u8>,
         expected_fee: Option<U256>,
+    ) -> ProgramResult {
+        Self::transfer(
+            program_id,
+            accounts,
+            amount,
+            expected_decimals,
+            expected_fee,
+            false, /* recovery */
+        )
+    }
+
+    /// Processes a
+    /// [`Recover`](extension/non_transferable/instruction/enum.NonTransferableInstruction.html)
+    /// instruction, the only way to move non-transferable tokens.
+    pub fn process_recover(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: U256,
+        decimals: u8,
+    ) -> ProgramResult {
+        Self::transfer(
+            program_id,
+            accounts,
+            amount,
+            Some(decimals),
+            None,
+            true, /* recovery */
+        )
+    }
+
+    /// Moves tokens between accounts, either as a regular transfer signed by
+    /// the owner or a delegate, or as a recovery of non-transferable tokens
+    /// signed by the mint's recovery authority
+    fn transfer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: U256,
+        expected_decimals: Option<u8>,
+        expected_fee: Option<U256>,
+        recovery: bool,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
@@ -322,75 +438,102 @@ impl Processor {
         if source_amount < amount {
             return Err(TokenError::InsufficientFunds.into());
         }
-        if source_account
-            .get_extension::<NonTransferableAccount>()
-            .is_ok()
+        if !recovery
+            && source_account
+                .get_extension::<NonTransferableAccount>()
+                .is_ok()
         {
             return Err(TokenError::NonTransferable.into());
         }
-        let (fee, maybe_permanent_delegate, maybe_transfer_hook_program_id) =
-            if let Some((mint_info, expected_decimals)) = expected_mint_info {
-                if &source_account.base.mint != mint_info.key {
-                    return Err(TokenError::MintMismatch.into());
-                }
+        let (
+            fee,
+            maybe_permanent_delegate,
+            maybe_transfer_hook_program_id,
+            maybe_recovery_authority,
+        ) = if let Some((mint_info, expected_decimals)) = expected_mint_info {
+            if &source_account.base.mint != mint_info.key {
+                return Err(TokenError::MintMismatch.into());
+            }
 
-                let mint_data = mint_info.try_borrow_data()?;
-                let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)?;
+            let mint_data = mint_info.try_borrow_data()?;
+            let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)?;
 
-                if expected_decimals != mint.base.decimals {
-                    return Err(TokenError::MintDecimalsMismatch.into());
-                }
+            if expected_decimals != mint.base.decimals {
+                return Err(TokenError::MintDecimalsMismatch.into());
+            }
 
-                let fee = if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>()
+            let fee = if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
+                if transfer_fee::is_fee_exempt(&source_account)
+                    || (source_account_info.key != destination_account_info.key
+                        && Self::is_account_fee_exempt(destination_account_info)?)
                 {
+                    U256::ZERO
+                } else {
                     transfer_fee_config
-                        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+                        .get_tiered_epoch_fee(
+                            mint.get_extension::<TransferFeeTiers>().ok(),
+                            Clock::get()?.epoch,
+                        )
+                        .calculate_fee(amount)
                         .ok_or(TokenError::Overflow)?
-                } else {
-                    U256::ZERO
-                };
-
-                if let Ok(extension) = mint.get_extension::<PausableConfig>() {
-                    if extension.paused.into() {
-                        return Err(TokenError::MintPaused.into());
-                    }
                 }
+            } else {
+                U256::ZERO
+            };
 
-                let maybe_permanent_delegate = get_permanent_delegate(&mint);
-                let maybe_transfer_hook_program_id = transfer_hook::get_program_id(&mint);
+            if pausable::is_paused(&mint, PausableOperation::Transfer)? {
+                return Err(TokenError::MintPaused.into());
+            }
 
-                (
-                    fee,
-                    maybe_permanent_delegate,
-                    maybe_transfer_hook_program_id,
+            let maybe_permanent_delegate = get_permanent_delegate_for_transfer(&mint);
+            let maybe_transfer_hook_program_id = transfer_hook::get_program_id(&mint);
+            let maybe_recovery_authority = if recovery {
+                // recovery is reserved to non-transferable mints, which have
+                // no other way to move tokens
+                if mint.get_extension::<NonTransferable>().is_err() {
+                    return Err(TokenError::RecoveryNotEnabled.into());
+                }
+                Some(
+                    non_transferable::get_recovery_authority(&mint)
+                        .ok_or(TokenError::RecoveryNotEnabled)?,
                 )
             } else {
-                // Transfer hook extension exists on the account, but no mint
-                // was provided to figure out required accounts, abort
-                if source_account
-                    .get_extension::<TransferHookAccount>()
-                    .is_ok()
-                {
-                    return Err(TokenError::MintRequiredForTransfer.into());
-                }
+                None
+            };
 
-                // Transfer fee amount extension exists on the account, but no mint
-                // was provided to calculate the fee, abort
-                if source_account
-                    .get_extension_mut::<TransferFeeAmount>()
-                    .is_ok()
-                {
-                    return Err(TokenError::MintRequiredForTransfer.into());
-                }
+            (
+                fee,
+                maybe_permanent_delegate,
+                maybe_transfer_hook_program_id,
+                maybe_recovery_authority,
+            )
+        } else {
+            // Transfer hook extension exists on the account, but no mint
+            // was provided to figure out required accounts, abort
+            if source_account
+                .get_extension::<TransferHookAccount>()
+                .is_ok()
+            {
+                return Err(TokenError::MintRequiredForTransfer.into());
+            }
 
-                // Pausable extension exists on the account, but no mint
-                // was provided to see if it's paused, abort
-                if source_account.get_extension::<PausableAccount>().is_ok() {
-                    return Err(TokenError::MintRequiredForTransfer.into());
-                }
+            // Transfer fee amount extension exists on the account, but no mint
+            // was provided to calculate the fee, abort
+            if source_account
+                .get_extension_mut::<TransferFeeAmount>()
+                .is_ok()
+            {
+                return Err(TokenError::MintRequiredForTransfer.into());
+            }
 
-                (U256::ZERO, None, None)
-            };
+            // Pausable extension exists on the account, but no mint
+            // was provided to see if it's paused, abort
+            if source_account.get_extension::<PausableAccount>().is_ok() {
+                return Err(TokenError::MintRequiredForTransfer.into());
+            }
+
+            (U256::ZERO, None, None, None)
+        };
         if let Some(expected_fee) = expected_fee {
             if expected_fee != fee {
                 msg!("Calculated fee {}, received {}", fee, expected_fee);
@@ -399,19 +542,24 @@ impl Processor {
         }
 
         let self_transfer = source_account_info.key == destination_account_info.key;
-        if let Ok(cpi_guard) = source_account.get_extension::<CpiGuard>() {
-            // Blocks all cases where the authority has signed if CPI Guard is
-            // enabled, including:
-            // * the account is delegated to the owner
-            // * the account owner is the permanent delegate
-            if *authority_info.key == source_account.base.owner
-                && cpi_guard.lock_cpi.into()
-                && in_cpi()
-            {
-                return Err(TokenError::CpiGuardTransferBlocked.into());
-            }
+        // Blocks all cases where the authority has signed if CPI Guard is
+        // enabled, including:
+        // * the account is delegated to the owner
+        // * the account owner is the permanent delegate
+        if *authority_info.key == source_account.base.owner
+            && cpi_guard_blocks(&source_account, account_info_iter.as_slice())?
+        {
+            return Err(TokenError::CpiGuardTransferBlocked.into());
         }
+        let delegate_expired = delegate_expired(&source_account)?;
         match (source_account.base.delegate, maybe_permanent_delegate) {
+            _ if recovery => Self::validate_owner(
+                program_id,
+                &maybe_recovery_authority.ok_or(TokenError::RecoveryNotEnabled)?,
+                authority_info,
+                authority_info_data_len,
+                account_info_iter.as_slice(),
+            )?,
             (_, Some(ref delegate)) if authority_info.key == delegate => Self::validate_owner(
                 program_id,
                 delegate,
@@ -425,7 +573,7 @@ impl Processor {
                     value: delegate,
                 },
                 _,
-            ) if authority_info.key == &delegate => {
+            ) if authority_info.key == &delegate && !delegate_expired => {
                 Self::validate_owner(
                     program_id,
                     &delegate,
@@ -448,13 +596,38 @@ impl Processor {
                 }
             }
             _ => {
-                Self::validate_owner(
-                    program_id,
-                    &source_account.base.owner,
-                    authority_info,
-                    authority_info_data_len,
-                    account_info_iter.as_slice(),
-                )?;
+                let maybe_delegate_index = if authority_info.key != &source_account.base.owner {
+                    find_active_delegate(&source_account, authority_info.key)?
+                } else {
+                    None
+                };
+                if let Some(delegate_index) = maybe_delegate_index {
+                    Self::validate_owner(
+                        program_id,
+                        authority_info.key,
+                        authority_info,
+                        authority_info_data_len,
+                        account_info_iter.as_slice(),
+                    )?;
+                    let multi_delegate = source_account.get_extension_mut::<MultiDelegate>()?;
+                    if self_transfer {
+                        let allowance =
+                            U256::from(multi_delegate.delegates[delegate_index].allowance);
+                        if allowance < amount {
+                            return Err(TokenError::InsufficientFunds.into());
+                        }
+                    } else {
+                        multi_delegate.spend(delegate_index, amount)?;
+                    }
+                } else {
+                    Self::validate_owner(
+                        program_id,
+                        &source_account.base.owner,
+                        authority_info,
+                        authority_info_data_len,
+                        account_info_iter.as_slice(),
+                    )?;
+                }
             }
         }
 
@@ -467,9 +640,8 @@ impl Processor {
         // This check MUST occur just before the amounts are manipulated
         // to ensure self-transfers are fully validated
         if self_transfer {
-            if memo_required(&source_account) {
-                check_previous_sibling_instruction_is_memo()?;
-            }
+            check_incoming_transfer_memo(&source_account)?;
+            check_outgoing_transfer_memo(&source_account)?;
             return Ok(());
         }
 
@@ -485,9 +657,8 @@ impl Processor {
             return Err(TokenError::MintMismatch.into());
         }
 
-        if memo_required(&destination_account) {
-            check_previous_sibling_instruction_is_memo()?;
-        }
+        check_outgoing_transfer_memo(&source_account)?;
+        check_incoming_transfer_memo(&destination_account)?;
 
         if let Ok(confidential_transfer_state) =
             destination_account.get_extension::<ConfidentialTransferAccount>()
@@ -495,6 +666,17 @@ impl Processor {
             confidential_transfer_state.non_confidential_transfer_allowed()?
         }
 
+        if !recovery && maybe_permanent_delegate.as_ref() != Some(authority_info.key) {
+            if let Ok(vesting) = source_account.get_extension::<VestingAccount>() {
+                if vesting.unlocked_balance(source_amount, Clock::get()?.unix_timestamp) < amount {
+                    return Err(TokenError::InsufficientUnlockedFunds.into());
+                }
+            }
+            if let Ok(transfer_limit) = source_account.get_extension_mut::<TransferLimit>() {
+                transfer_limit.record_outflow(amount, Clock::get()?.unix_timestamp)?;
+            }
+        }
+
         source_account.base.amount = source_amount
             .checked_sub(amount)
             .ok_or(TokenError::Overflow)?
@@ -530,6 +712,25 @@ impl Processor {
                 .ok_or(TokenError::Overflow)?;
         }
 
+        event::emit(&TransferEvent {
+            mint: source_account.base.mint,
+            source: *source_account_info.key,
+            destination: *destination_account_info.key,
+            authority: *authority_info.key,
+            amount: amount.into(),
+            fee: fee.into(),
+            recovery: recovery.into(),
+        });
+
+        if recovery {
+            msg!(
+                "Recovered {} tokens from {} to {}",
+                amount,
+                source_account_info.key,
+                destination_account_info.key
+            );
+        }
+
         if let Some(program_id) = maybe_transfer_hook_program_id {
             if let Some((mint_info, _)) = expected_mint_info {
                 // set transferring flags
@@ -581,7 +782,7 @@ impl Processor {
         let owner_info_data_len = owner_info.data_len();
 
         let mut source_account_data = source_account_info.data.borrow_mut();
-        let source_account =
+        let mut source_account =
             PodStateWithExtensionsMut::<PodAccount>::unpack(&mut source_account_data)?;
 
         if source_account.base.is_frozen() {
@@ -608,14 +809,15 @@ impl Processor {
             account_info_iter.as_slice(),
         )?;
 
-        if let Ok(cpi_guard) = source_account.get_extension::<CpiGuard>() {
-            if cpi_guard.lock_cpi.into() && in_cpi() {
-                return Err(TokenError::CpiGuardApproveBlocked.into());
-            }
+        if cpi_guard_blocks(&source_account, account_info_iter.as_slice())? {
+            return Err(TokenError::CpiGuardApproveBlocked.into());
         }
 
         source_account.base.delegate = PodCOption::some(*delegate_info.key);
         source_account.base.delegated_amount = amount.into();
+        if let Ok(delegate_expiry) = source_account.get_extension_mut::<DelegateExpiry>() {
+            delegate_expiry.expires_at = 0.into();
+        }
 
         Ok(())
     }
@@ -628,7 +830,7 @@ impl Processor {
         let authority_info_data_len = authority_info.data_len();
 
         let mut source_account_data = source_account_info.data.borrow_mut();
-        let source_account =
+        let mut source_account =
             PodStateWithExtensionsMut::<PodAccount>::unpack(&mut source_account_data)?;
         if source_account.base.is_frozen() {
             return Err(TokenError::AccountFrozen.into());
@@ -650,6 +852,9 @@ impl Processor {
 
         source_account.base.delegate = PodCOption::none();
         source_account.base.delegated_amount = 0.into();
+        if let Ok(delegate_expiry) = source_account.get_extension_mut::<DelegateExpiry>() {
+            delegate_expiry.expires_at = 0.into();
+        }
 
         Ok(())
     }
@@ -687,10 +892,10 @@ impl Processor {
                         return Err(TokenError::ImmutableOwner.into());
                     }
 
-                    if let Ok(cpi_guard) = account.get_extension::<CpiGuard>() {
-                        if cpi_guard.lock_cpi.into() && in_cpi() {
-                            return Err(TokenError::CpiGuardSetAuthorityBlocked.into());
-                        } else if cpi_guard.lock_cpi.into() {
+                    if cpi_guard_blocks(&account, account_info_iter.as_slice())? {
+                        return Err(TokenError::CpiGuardSetAuthorityBlocked.into());
+                    } else if let Ok(cpi_guard) = account.get_extension::<CpiGuard>() {
+                        if cpi_guard.lock_cpi.into() {
                             return Err(TokenError::CpiGuardOwnerChangeBlocked.into());
                         }
                     }
@@ -722,10 +927,10 @@ impl Processor {
                         account_info_iter.as_slice(),
                     )?;
 
-                    if let Ok(cpi_guard) = account.get_extension::<CpiGuard>() {
-                        if cpi_guard.lock_cpi.into() && in_cpi() && new_authority.is_some() {
-                            return Err(TokenError::CpiGuardSetAuthorityBlocked.into());
-                        }
+                    if new_authority.is_some()
+                        && cpi_guard_blocks(&account, account_info_iter.as_slice())?
+                    {
+                        return Err(TokenError::CpiGuardSetAuthorityBlocked.into());
                     }
 
                     account.base.close_authority = new_authority;
@@ -947,6 +1152,71 @@ impl Processor {
                     )?;
                     extension.authority = new_authority.try_into()?;
                 }
+                AuthorityType::SupplyCap => {
+                    let extension = mint.get_extension_mut::<SupplyCapConfig>()?;
+                    let maybe_authority: Option<Pubkey> = extension.authority.into();
+                    let authority = maybe_authority.ok_or(TokenError::AuthorityTypeNotSupported)?;
+                    Self::validate_owner(
+                        program_id,
+                        &authority,
+                        authority_info,
+                        authority_info_data_len,
+                        account_info_iter.as_slice(),
+                    )?;
+                    extension.authority = new_authority.try_into()?;
+                }
+                AuthorityType::Vesting => {
+                    let extension = mint.get_extension_mut::<VestingConfig>()?;
+                    let maybe_authority: Option<Pubkey> = extension.authority.into();
+                    let authority = maybe_authority.ok_or(TokenError::AuthorityTypeNotSupported)?;
+                    Self::validate_owner(
+                        program_id,
+                        &authority,
+                        authority_info,
+                        authority_info_data_len,
+                        account_info_iter.as_slice(),
+                    )?;
+                    extension.authority = new_authority.try_into()?;
+                }
+                AuthorityType::SupplyHookProgramId => {
+                    let extension = mint.get_extension_mut::<SupplyHook>()?;
+                    let maybe_authority: Option<Pubkey> = extension.authority.into();
+                    let authority = maybe_authority.ok_or(TokenError::AuthorityTypeNotSupported)?;
+                    Self::validate_owner(
+                        program_id,
+                        &authority,
+                        authority_info,
+                        authority_info_data_len,
+                        account_info_iter.as_slice(),
+                    )?;
+                    extension.authority = new_authority.try_into()?;
+                }
+                AuthorityType::RecoveryAuthority => {
+                    let extension = mint.get_extension_mut::<NonTransferableRecovery>()?;
+                    let maybe_authority: Option<Pubkey> = extension.authority.into();
+                    let authority = maybe_authority.ok_or(TokenError::AuthorityTypeNotSupported)?;
+                    Self::validate_owner(
+                        program_id,
+                        &authority,
+                        authority_info,
+                        authority_info_data_len,
+                        account_info_iter.as_slice(),
+                    )?;
+                    extension.authority = new_authority.try_into()?;
+                }
+                AuthorityType::TransferLimitPolicy => {
+                    let extension = mint.get_extension_mut::<TransferLimitPolicy>()?;
+                    let maybe_authority: Option<Pubkey> = extension.authority.into();
+                    let authority = maybe_authority.ok_or(TokenError::AuthorityTypeNotSupported)?;
+                    Self::validate_owner(
+                        program_id,
+                        &authority,
+                        authority_info,
+                        authority_info_data_len,
+                        account_info_iter.as_slice(),
+                    )?;
+                    extension.authority = new_authority.try_into()?;
+                }
                 _ => {
                     return Err(TokenError::AuthorityTypeNotSupported.into());
                 }
@@ -955,6 +1225,12 @@ impl Processor {
             return Err(ProgramError::InvalidAccountData);
         }
 
+        event::emit(&SetAuthorityEvent {
+            account: *account_info.key,
+            authority_type: AuthorityType::into(&authority_type),
+            new_authority,
+        });
+
         Ok(())
     }
 
@@ -986,7 +1262,7 @@ impl Processor {
         }
 
         let mut mint_data = mint_info.data.borrow_mut();
-        let mint = PodStateWithExtensionsMut::<PodMint>::unpack(&mut mint_data)?;
+        let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack(&mut mint_data)?;
 
         // If the mint if non-transferable, only allow minting to accounts
         // with immutable ownership.
@@ -998,10 +1274,8 @@ impl Processor {
             return Err(TokenError::NonTransferableNeedsImmutableOwnership.into());
         }
 
-        if let Ok(extension) = mint.get_extension::<PausableConfig>() {
-            if extension.paused.into() {
-                return Err(TokenError::MintPaused.into());
-            }
+        if pausable::is_paused(&mint, PausableOperation::Mint)? {
+            return Err(TokenError::MintPaused.into());
         }
 
         if mint.get_extension::<ConfidentialMintBurn>().is_ok() {
@@ -1039,10 +1313,40 @@ impl Processor {
             .ok_or(TokenError::Overflow)?
             .into();
 
-        mint.base.supply = U256::from(mint.base.supply)
+        let supply = U256::from(mint.base.supply)
             .checked_add(amount)
-            .ok_or(TokenError::Overflow)?
-            .into();
+            .ok_or(TokenError::Overflow)?;
+        if let Ok(extension) = mint.get_extension::<SupplyCapConfig>() {
+            extension.check_supply(supply)?;
+        }
+        mint.base.supply = supply.into();
+
+        event::emit(&MintToEvent {
+            mint: *mint_info.key,
+            destination: *destination_account_info.key,
+            amount: amount.into(),
+            supply: mint.base.supply,
+        });
+
+        if let Some(supply_hook_program_id) = supply_hook::get_program_id(&mint) {
+            // set executing flag
+            supply_hook::set_executing(&mut mint)?;
+            // must drop these to avoid the double-borrow during CPI
+            drop(destination_account_data);
+            drop(mint_data);
+            // the mint stands in for the source of the new tokens
+            supply_hook::interface::invoke_execute(
+                &supply_hook_program_id,
+                mint_info.clone(),
+                mint_info.clone(),
+                destination_account_info.clone(),
+                owner_info.clone(),
+                account_info_iter.as_slice(),
+                amount,
+            )?;
+            // unset executing flag
+            supply_hook::unset_executing(mint_info)?;
+        }
 
         Ok(())
     }
@@ -1062,10 +1366,10 @@ impl Processor {
         let authority_info_data_len = authority_info.data_len();
 
         let mut source_account_data = source_account_info.data.borrow_mut();
-        let source_account =
+        let mut source_account =
             PodStateWithExtensionsMut::<PodAccount>::unpack(&mut source_account_data)?;
         let mut mint_data = mint_info.data.borrow_mut();
-        let mint = PodStateWithExtensionsMut::<PodMint>::unpack(&mut mint_data)?;
+        let mut mint = PodStateWithExtensionsMut::<PodMint>::unpack(&mut mint_data)?;
 
         if source_account.base.is_frozen() {
             return Err(TokenError::AccountFrozen.into());
@@ -1085,30 +1389,26 @@ impl Processor {
                 return Err(TokenError::MintDecimalsMismatch.into());
             }
         }
-        if let Ok(extension) = mint.get_extension::<PausableConfig>() {
-            if extension.paused.into() {
-                return Err(TokenError::MintPaused.into());
-            }
+        if pausable::is_paused(&mint, PausableOperation::Burn)? {
+            return Err(TokenError::MintPaused.into());
         }
-        let maybe_permanent_delegate = get_permanent_delegate(&mint);
-
-        if let Ok(cpi_guard) = source_account.get_extension::<CpiGuard>() {
-            // Blocks all cases where the authority has signed if CPI Guard is
-            // enabled, including:
-            // * the account is delegated to the owner
-            // * the account owner is the permanent delegate
-            if *authority_info.key == source_account.base.owner
-                && cpi_guard.lock_cpi.into()
-                && in_cpi()
-            {
-                return Err(TokenError::CpiGuardBurnBlocked.into());
-            }
+        let maybe_permanent_delegate = get_permanent_delegate_for_burn(&mint);
+
+        // Blocks all cases where the authority has signed if CPI Guard is
+        // enabled, including:
+        // * the account is delegated to the owner
+        // * the account owner is the permanent delegate
+        if *authority_info.key == source_account.base.owner
+            && cpi_guard_blocks(&source_account, account_info_iter.as_slice())?
+        {
+            return Err(TokenError::CpiGuardBurnBlocked.into());
         }
 
         if !source_account
             .base
             .is_owned_by_system_program_or_incinerator()
         {
+            let delegate_expired = delegate_expired(&source_account)?;
             match (&source_account.base.delegate, maybe_permanent_delegate) {
                 (_, Some(ref delegate)) if authority_info.key == delegate => Self::validate_owner(
                     program_id,
@@ -1123,7 +1423,7 @@ impl Processor {
                         value: delegate,
                     },
                     _,
-                ) if authority_info.key == delegate => {
+                ) if authority_info.key == delegate && !delegate_expired => {
                     Self::validate_owner(
                         program_id,
                         delegate,
@@ -1145,13 +1445,31 @@ impl Processor {
                     }
                 }
                 _ => {
-                    Self::validate_owner(
-                        program_id,
-                        &source_account.base.owner,
-                        authority_info,
-                        authority_info_data_len,
-                        account_info_iter.as_slice(),
-                    )?;
+                    let maybe_delegate_index = if authority_info.key != &source_account.base.owner {
+                        find_active_delegate(&source_account, authority_info.key)?
+                    } else {
+                        None
+                    };
+                    if let Some(delegate_index) = maybe_delegate_index {
+                        Self::validate_owner(
+                            program_id,
+                            authority_info.key,
+                            authority_info,
+                            authority_info_data_len,
+                            account_info_iter.as_slice(),
+                        )?;
+                        source_account
+                            .get_extension_mut::<MultiDelegate>()?
+                            .spend(delegate_index, amount)?;
+                    } else {
+                        Self::validate_owner(
+                            program_id,
+                            &source_account.base.owner,
+                            authority_info,
+                            authority_info_data_len,
+                            account_info_iter.as_slice(),
+                        )?;
+                    }
                 }
             }
         }
@@ -1162,6 +1480,21 @@ impl Processor {
         check_program_account(source_account_info.owner)?;
         check_program_account(mint_info.owner)?;
 
+        if maybe_permanent_delegate.as_ref() != Some(authority_info.key) {
+            if let Ok(vesting) = source_account.get_extension::<VestingAccount>() {
+                if vesting.unlocked_balance(
+                    U256::from(source_account.base.amount),
+                    Clock::get()?.unix_timestamp,
+                ) < amount
+                {
+                    return Err(TokenError::InsufficientUnlockedFunds.into());
+                }
+            }
+            if let Ok(transfer_limit) = source_account.get_extension_mut::<TransferLimit>() {
+                transfer_limit.record_outflow(amount, Clock::get()?.unix_timestamp)?;
+            }
+        }
+
         source_account.base.amount = U256::from(source_account.base.amount)
             .checked_sub(amount)
             .ok_or(TokenError::Overflow)?
@@ -1171,6 +1504,33 @@ impl Processor {
             .ok_or(TokenError::Overflow)?
             .into();
 
+        event::emit(&BurnEvent {
+            mint: *mint_info.key,
+            source: *source_account_info.key,
+            amount: amount.into(),
+            supply: mint.base.supply,
+        });
+
+        if let Some(supply_hook_program_id) = supply_hook::get_program_id(&mint) {
+            // set executing flag
+            supply_hook::set_executing(&mut mint)?;
+            // must drop these to avoid the double-borrow during CPI
+            drop(source_account_data);
+            drop(mint_data);
+            // the mint stands in for the destination of the burnt tokens
+            supply_hook::interface::invoke_execute(
+                &supply_hook_program_id,
+                source_account_info.clone(),
+                mint_info.clone(),
+                mint_info.clone(),
+                authority_info.clone(),
+                account_info_iter.as_slice(),
+                amount,
+            )?;
+            // unset executing flag
+            supply_hook::unset_executing(mint_info)?;
+        }
+
         Ok(())
     }
 
@@ -1203,13 +1563,10 @@ impl Processor {
                 .base
                 .is_owned_by_system_program_or_incinerator()
             {
-                if let Ok(cpi_guard) = source_account.get_extension::<CpiGuard>() {
-                    if cpi_guard.lock_cpi.into()
-                        && in_cpi()
-                        && destination_account_info.key != &source_account.base.owner
-                    {
-                        return Err(TokenError::CpiGuardCloseAccountBlocked.into());
-                    }
+                if destination_account_info.key != &source_account.base.owner
+                    && cpi_guard_blocks(&source_account, account_info_iter.as_slice())?
+                {
+                    return Err(TokenError::CpiGuardCloseAccountBlocked.into());
                 }
 
                 Self::validate_owner(
@@ -1318,6 +1675,18 @@ impl Processor {
             AccountState::Initialized.into()
         };
 
+        if freeze {
+            event::emit(&FreezeAccountEvent {
+                mint: *mint_info.key,
+                account: *source_account_info.key,
+            });
+        } else {
+            event::emit(&ThawAccountEvent {
+                mint: *mint_info.key,
+                account: *source_account_info.key,
+            });
+        }
+
         Ok(())
     }
 
@@ -1409,6 +1778,18 @@ impl Processor {
             .map(|_| ())
     }
 
+    /// Get the timestamp used to convert UI amounts for the mint, only reading
+    /// the clock if the mint's UI amounts actually depend on it
+    fn ui_amount_unix_timestamp(
+        mint: &PodStateWithExtensions<PodMint>,
+    ) -> Result<i64, ProgramError> {
+        if uses_unix_timestamp(mint) {
+            Ok(Clock::get()?.unix_timestamp)
+        } else {
+            Ok(0)
+        }
+    }
+
     /// Processes an [`AmountToUiAmount`](enum.TokenInstruction.html)
     /// instruction
     pub fn process_amount_to_ui_amount(accounts: &[AccountInfo], amount: U256) -> ProgramResult {
@@ -1419,19 +1800,10 @@ impl Processor {
         let mint_data = mint_info.data.borrow();
         let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)
             .map_err(|_| Into::<ProgramError>::into(TokenError::InvalidMint))?;
-        let ui_amount = if let Ok(extension) = mint.get_extension::<InterestBearingConfig>() {
-            let unix_timestamp = Clock::get()?.unix_timestamp;
-            extension
-                .amount_to_ui_amount(amount, mint.base.decimals, unix_timestamp)
-                .ok_or(ProgramError::InvalidArgument)?
-        } else if let Ok(extension) = mint.get_extension::<ScaledUiAmountConfig>() {
-            let unix_timestamp = Clock::get()?.unix_timestamp;
-            extension
-                .amount_to_ui_amount(amount, mint.base.decimals, unix_timestamp)
-                .ok_or(ProgramError::InvalidArgument)?
-        } else {
-            crate::amount_to_ui_amount_string_trimmed(amount, mint.base.decimals)
-        };
+        let unix_timestamp = Self::ui_amount_unix_timestamp(&mint)?;
+        let ui_amount =
+            amount_to_ui_amount_for_mint(&mint, mint.base.decimals, amount, unix_timestamp)
+                .ok_or(ProgramError::InvalidArgument)?;
 
         set_return_data(&ui_amount.into_bytes());
         Ok(())
@@ -1447,15 +1819,13 @@ impl Processor {
         let mint_data = mint_info.data.borrow();
         let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)
             .map_err(|_| Into::<ProgramError>::into(TokenError::InvalidMint))?;
-        let amount = if let Ok(extension) = mint.get_extension::<InterestBearingConfig>() {
-            let unix_timestamp = Clock::get()?.unix_timestamp;
-            extension.try_ui_amount_into_amount(ui_amount, mint.base.decimals, unix_timestamp)?
-        } else if let Ok(extension) = mint.get_extension::<ScaledUiAmountConfig>() {
-            let unix_timestamp = Clock::get()?.unix_timestamp;
-            extension.try_ui_amount_into_amount(ui_amount, mint.base.decimals, unix_timestamp)?
-        } else {
-            crate::try_ui_amount_into_amount(ui_amount.to_string(), mint.base.decimals)?
-        };
+        let unix_timestamp = Self::ui_amount_unix_timestamp(&mint)?;
+        let amount = try_ui_amount_into_amount_for_mint(
+            &mint,
+            mint.base.decimals,
+            ui_amount,
+            unix_timestamp,
+        )?;
 
         set_return_data(&amount.to_le_bytes());
         Ok(())
@@ -1579,7 +1949,9 @@ impl Processor {
                 }
                 _ => return Err(TokenError::AuthorityTypeNotSupported.into()),
             }
-        } else if source_data.len() == PodMultisig::SIZE_OF {
+        } else if source_data.len() == PodMultisig::SIZE_OF
+            || PodWeightedMultisig::is_weighted_multisig(&source_data)
+        {
             Self::validate_owner(
                 program_id,
                 source_info.key,
@@ -1611,6 +1983,72 @@ impl Processor {
         Ok(())
     }
 
+    /// Checks the accounts of an instruction in a batch. The surrounding
+    /// instructions of the transaction are those of the batch, so accounts
+    /// requiring memos or guarded against CPI, as well as the instructions
+    /// sysvar, are rejected rather than checked against the wrong instruction.
+    fn check_batch_accounts(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        for account_info in accounts {
+            if instructions::check_id(account_info.key) {
+                return Err(TokenError::InvalidBatchAccount.into());
+            }
+            if account_info.owner != program_id {
+                continue;
+            }
+            let account_data = account_info.try_borrow_data()?;
+            if let Ok(account) = PodStateWithExtensions::<PodAccount>::unpack(&account_data) {
+                let cpi_guarded = account
+                    .get_extension::<CpiGuard>()
+                    .map(|extension| bool::from(extension.lock_cpi))
+                    .unwrap_or(false);
+                if cpi_guarded
+                    || memo_transfer::memo_required(&account)
+                    || memo_transfer::outgoing_memo_required(&account)
+                {
+                    return Err(TokenError::InvalidBatchAccount.into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Processes a [`Batch`](enum.TokenInstruction.html) instruction.
+    pub fn process_batch(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        mut input: &[u8],
+    ) -> ProgramResult {
+        // first account index, number of accounts, u16 data length
+        const HEADER_LEN: usize = 4;
+
+        while !input.is_empty() {
+            let header = input
+                .get(..HEADER_LEN)
+                .ok_or(TokenError::InvalidInstruction)?;
+            let account_start = header[0] as usize;
+            let account_end = account_start.saturating_add(header[1] as usize);
+            let data_end =
+                HEADER_LEN.saturating_add(u16::from_le_bytes([header[2], header[3]]) as usize);
+
+            let instruction_accounts = accounts
+                .get(account_start..account_end)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let instruction_data = input
+                .get(HEADER_LEN..data_end)
+                .ok_or(TokenError::InvalidInstruction)?;
+            if let Ok(PodTokenInstruction::Batch) = decode_instruction_type(instruction_data) {
+                return Err(TokenError::InvalidInstruction.into());
+            }
+            // checked before each instruction, since an earlier one may have
+            // enabled memos or CPI Guard
+            Self::check_batch_accounts(program_id, instruction_accounts)?;
+
+            Self::process(program_id, instruction_accounts, instruction_data)?;
+            input = &input[data_end..];
+        }
+        Ok(())
+    }
+
     /// Processes an [`Instruction`](enum.Instruction.html).
     pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
         if let Ok(instruction_type) = decode_instruction_type(input) {
@@ -1865,26 +2303,115 @@ impl Processor {
                         &input[1..],
                     )
                 }
-                PodTokenInstruction::ConfidentialMintBurnExtension => {
-                    msg!("Instruction: ConfidentialMintBurnExtension");
-                    confidential_mint_burn::processor::process_instruction(
+                PodTokenInstruction::ConfidentialMintBurnExtension => {
+                    msg!("Instruction: ConfidentialMintBurnExtension");
+                    confidential_mint_burn::processor::process_instruction(
+                        program_id,
+                        accounts,
+                        &input[1..],
+                    )
+                }
+                PodTokenInstruction::ScaledUiAmountExtension => {
+                    msg!("Instruction: ScaledUiAmountExtension");
+                    scaled_ui_amount::processor::process_instruction(
+                        program_id,
+                        accounts,
+                        &input[1..],
+                    )
+                }
+                PodTokenInstruction::PausableExtension => {
+                    msg!("Instruction: PausableExtension");
+                    pausable::processor::process_instruction(program_id, accounts, &input[1..])
+                }
+                PodTokenInstruction::TokenGroupExtension => {
+                    msg!("Instruction: TokenGroupExtension");
+                    token_group::processor::process_extension_instruction(
+                        program_id,
+                        accounts,
+                        &input[1..],
+                    )
+                }
+                PodTokenInstruction::SupplyCapExtension => {
+                    msg!("Instruction: SupplyCapExtension");
+                    supply_cap::processor::process_instruction(program_id, accounts, &input[1..])
+                }
+                PodTokenInstruction::TransferLimitExtension => {
+                    msg!("Instruction: TransferLimitExtension");
+                    transfer_limit::processor::process_instruction(
+                        program_id,
+                        accounts,
+                        &input[1..],
+                    )
+                }
+                PodTokenInstruction::VestingExtension => {
+                    msg!("Instruction: VestingExtension");
+                    vesting::processor::process_instruction(program_id, accounts, &input[1..])
+                }
+                PodTokenInstruction::Batch => {
+                    msg!("Instruction: Batch");
+                    Self::process_batch(program_id, accounts, &input[1..])
+                }
+                PodTokenInstruction::InitializeWeightedMultisig => {
+                    msg!("Instruction: InitializeWeightedMultisig");
+                    let (threshold, weights) = TokenInstruction::unpack_u16(&input[1..])?;
+                    Self::process_initialize_weighted_multisig(accounts, threshold, weights)
+                }
+                PodTokenInstruction::ReallocateMint => {
+                    msg!("Instruction: ReallocateMint");
+                    let extension_types = input[1..]
+                        .chunks(std::mem::size_of::<ExtensionType>())
+                        .map(ExtensionType::try_from)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    reallocate::process_reallocate_mint(program_id, accounts, extension_types)
+                }
+                PodTokenInstruction::RemoveAccountExtensions => {
+                    msg!("Instruction: RemoveAccountExtensions");
+                    let extension_types = input[1..]
+                        .chunks(std::mem::size_of::<ExtensionType>())
+                        .map(ExtensionType::try_from)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    reallocate::process_remove_account_extensions(
+                        program_id,
+                        accounts,
+                        extension_types,
+                    )
+                }
+                PodTokenInstruction::PermanentDelegateExtension => {
+                    msg!("Instruction: PermanentDelegateExtension");
+                    permanent_delegate::processor::process_instruction(
+                        program_id,
+                        accounts,
+                        &input[1..],
+                    )
+                }
+                PodTokenInstruction::DelegateExpiryExtension => {
+                    msg!("Instruction: DelegateExpiryExtension");
+                    delegate_expiry::processor::process_instruction(
+                        program_id,
+                        accounts,
+                        &input[1..],
+                    )
+                }
+                PodTokenInstruction::MultiDelegateExtension => {
+                    msg!("Instruction: MultiDelegateExtension");
+                    multi_delegate::processor::process_instruction(
                         program_id,
                         accounts,
                         &input[1..],
                     )
                 }
-                PodTokenInstruction::ScaledUiAmountExtension => {
-                    msg!("Instruction: ScaledUiAmountExtension");
-                    scaled_ui_amount::processor::process_instruction(
+                PodTokenInstruction::SupplyHookExtension => {
+                    msg!("Instruction: SupplyHookExtension");
+                    supply_hook::processor::process_instruction(program_id, accounts, &input[1..])
+                }
+                PodTokenInstruction::NonTransferableExtension => {
+                    msg!("Instruction: NonTransferableExtension");
+                    non_transferable::processor::process_instruction(
                         program_id,
                         accounts,
                         &input[1..],
                     )
                 }
-                PodTokenInstruction::PausableExtension => {
-                    msg!("Instruction: PausableExtension");
-                    pausable::processor::process_instruction(program_id, accounts, &input[1..])
-                }
             }
         } else if let Ok(instruction) = TokenMetadataInstruction::unpack(input) {
             token_metadata::processor::process_instruction(program_id, accounts, instruction)
@@ -1928,13 +2455,42 @@ impl Processor {
                 return Err(ProgramError::MissingRequiredSignature);
             }
             return Ok(());
+        } else if program_id == owner_account_info.owner
+            && owner_account_data_len == PodWeightedMultisig::SIZE_OF
+            && owner_account_info
+                .try_borrow_data()
+                .is_ok_and(|data| PodWeightedMultisig::is_weighted_multisig(&data))
+        {
+            let multisig_data = &owner_account_info.data.borrow();
+            let multisig = pod_from_bytes::<PodWeightedMultisig>(multisig_data)?;
+            if !bool::from(multisig.is_initialized) {
+                return Err(ProgramError::UninitializedAccount);
+            }
+            let mut signed_weight: u16 = 0;
+            let mut matched = [false; MAX_WEIGHTED_SIGNERS];
+            for signer in signers.iter() {
+                for (position, key) in multisig.signers[0..multisig.n as usize].iter().enumerate() {
+                    if key == signer.key && !matched[position] {
+                        if !signer.is_signer {
+                            return Err(ProgramError::MissingRequiredSignature);
+                        }
+                        matched[position] = true;
+                        signed_weight =
+                            signed_weight.saturating_add(multisig.weights[position] as u16);
+                    }
+                }
+            }
+            if signed_weight < u16::from(multisig.threshold) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            return Ok(());
         } else if !owner_account_info.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
         Ok(())
     }
 
-    fn get_required_account_extensions(
+    pub(crate) fn get_required_account_extensions(
         mint_account_info: &AccountInfo,
     ) -> Result<Vec<ExtensionType>, ProgramError> {
         let mint_data = mint_account_info.data.borrow();
@@ -5579,6 +6135,128 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn test_batch() {
+        let program_id = crate::id();
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account_info: AccountInfo = (&account_key, false, &mut account_account).into();
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let owner_info: AccountInfo = (&owner_key, true, &mut owner_account).into();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mint_info: AccountInfo = (&mint_key, false, &mut mint_account).into();
+        let rent_key = rent::id();
+        let mut rent_sysvar = rent_sysvar();
+        let rent_info: AccountInfo = (&rent_key, false, &mut rent_sysvar).into();
+
+        // create mint and account
+        do_process_instruction_dups(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![mint_info.clone(), rent_info.clone()],
+        )
+        .unwrap();
+        do_process_instruction_dups(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                account_info.clone(),
+                mint_info.clone(),
+                owner_info.clone(),
+                rent_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // mint and burn in one batch
+        let mint_to_instruction = mint_to(
+            &program_id,
+            &mint_key,
+            &account_key,
+            &owner_key,
+            &[],
+            U256::from(1000_u64),
+        )
+        .unwrap();
+        let burn_instruction = burn(
+            &program_id,
+            &account_key,
+            &mint_key,
+            &owner_key,
+            &[],
+            U256::from(400_u64),
+        )
+        .unwrap();
+        do_process_instruction_dups(
+            batch(
+                &program_id,
+                &[mint_to_instruction, burn_instruction.clone()],
+            )
+            .unwrap(),
+            vec![
+                mint_info.clone(),
+                account_info.clone(),
+                owner_info.clone(),
+                account_info.clone(),
+                mint_info.clone(),
+                owner_info.clone(),
+            ],
+        )
+        .unwrap();
+        let account = Account::unpack_unchecked(&account_info.data.borrow()).unwrap();
+        assert_eq!(account.amount, U256::from(600_u64));
+        let mint = Mint::unpack_unchecked(&mint_info.data.borrow()).unwrap();
+        assert_eq!(mint.supply, U256::from(600_u64));
+
+        // failing instruction
+        assert_eq!(
+            Err(TokenError::InsufficientFunds.into()),
+            do_process_instruction_dups(
+                batch(
+                    &program_id,
+                    &[burn(
+                        &program_id,
+                        &account_key,
+                        &mint_key,
+                        &owner_key,
+                        &[],
+                        U256::from(601_u64),
+                    )
+                    .unwrap()],
+                )
+                .unwrap(),
+                vec![account_info.clone(), mint_info.clone(), owner_info.clone()],
+            )
+        );
+
+        // missing accounts
+        assert_eq!(
+            Err(ProgramError::NotEnoughAccountKeys),
+            do_process_instruction_dups(
+                batch(&program_id, &[burn_instruction.clone()]).unwrap(),
+                vec![account_info.clone(), mint_info.clone()],
+            )
+        );
+
+        // nested batch
+        assert_eq!(
+            Err(TokenError::InvalidInstruction.into()),
+            do_process_instruction_dups(
+                batch(
+                    &program_id,
+                    &[batch(&program_id, &[burn_instruction]).unwrap()],
+                )
+                .unwrap(),
+                vec![account_info.clone(), mint_info.clone(), owner_info.clone()],
+            )
+        );
+    }
+
     #[test]
     fn test_burn() {
         let program_id = crate::id();
@@ -6393,6 +7071,234 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn test_weighted_multisig() {
+        let program_id = crate::id();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let account_key = Pubkey::new_unique();
+        let mut account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let multisig_key = Pubkey::new_unique();
+        let mut multisig_account = SolanaAccount::new(
+            Rent::default().minimum_balance(PodWeightedMultisig::SIZE_OF),
+            PodWeightedMultisig::SIZE_OF,
+            &program_id,
+        );
+        let signer_keys = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let signer_key_refs: Vec<&Pubkey> = signer_keys.iter().collect();
+        let mut signer0_account = SolanaAccount::default();
+        let mut signer1_account = SolanaAccount::default();
+        let mut signer2_account = SolanaAccount::default();
+        let weights = [2, 1, 1];
+        let mut rent_sysvar = rent_sysvar();
+
+        // threshold above total weight
+        let mut instruction =
+            initialize_weighted_multisig(&program_id, &multisig_key, &signer_key_refs, &weights, 4)
+                .unwrap();
+        instruction.data = TokenInstruction::InitializeWeightedMultisig {
+            threshold: 5,
+            weights: weights.to_vec(),
+        }
+        .pack();
+        assert_eq!(
+            Err(TokenError::InvalidNumberOfRequiredSigners.into()),
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut multisig_account,
+                    &mut signer0_account,
+                    &mut signer1_account,
+                    &mut signer2_account,
+                ],
+            )
+        );
+
+        // weights don't match signers
+        let mut instruction =
+            initialize_weighted_multisig(&program_id, &multisig_key, &signer_key_refs, &weights, 2)
+                .unwrap();
+        instruction.data = TokenInstruction::InitializeWeightedMultisig {
+            threshold: 2,
+            weights: vec![2, 1],
+        }
+        .pack();
+        assert_eq!(
+            Err(TokenError::InvalidNumberOfProvidedSigners.into()),
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut multisig_account,
+                    &mut signer0_account,
+                    &mut signer1_account,
+                    &mut signer2_account,
+                ],
+            )
+        );
+
+        // mints and accounts of the same size can't be taken over, even if not
+        // initialized yet
+        for offset in [PodAccount::SIZE_OF, PodWeightedMultisig::SIZE_OF - 1] {
+            multisig_account.data[offset] = AccountType::Mint as u8;
+            assert_eq!(
+                Err(TokenError::AlreadyInUse.into()),
+                do_process_instruction(
+                    initialize_weighted_multisig(
+                        &program_id,
+                        &multisig_key,
+                        &signer_key_refs,
+                        &weights,
+                        2
+                    )
+                    .unwrap(),
+                    vec![
+                        &mut multisig_account,
+                        &mut signer0_account,
+                        &mut signer1_account,
+                        &mut signer2_account,
+                    ],
+                )
+            );
+            multisig_account.data[offset] = 0;
+        }
+
+        // success
+        do_process_instruction(
+            initialize_weighted_multisig(&program_id, &multisig_key, &signer_key_refs, &weights, 2)
+                .unwrap(),
+            vec![
+                &mut multisig_account,
+                &mut signer0_account,
+                &mut signer1_account,
+                &mut signer2_account,
+            ],
+        )
+        .unwrap();
+        assert!(PodWeightedMultisig::is_weighted_multisig(
+            &multisig_account.data
+        ));
+        let multisig = pod_from_bytes::<PodWeightedMultisig>(&multisig_account.data).unwrap();
+        assert_eq!(multisig.account_type, AccountType::WeightedMultisig as u8);
+        assert_eq!(u16::from(multisig.threshold), 2);
+        assert_eq!(multisig.n, 3);
+        assert_eq!(&multisig.weights[..3], &weights);
+        assert_eq!(&multisig.signers[..3], &signer_keys);
+
+        // already initialized
+        assert_eq!(
+            Err(TokenError::AlreadyInUse.into()),
+            do_process_instruction(
+                initialize_weighted_multisig(
+                    &program_id,
+                    &multisig_key,
+                    &signer_key_refs,
+                    &weights,
+                    2
+                )
+                .unwrap(),
+                vec![
+                    &mut multisig_account,
+                    &mut signer0_account,
+                    &mut signer1_account,
+                    &mut signer2_account,
+                ],
+            )
+        );
+
+        // create mint with weighted multisig authority and an account
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &multisig_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // a single low-weight signer is not enough
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                mint_to(
+                    &program_id,
+                    &mint_key,
+                    &account_key,
+                    &multisig_key,
+                    &[&signer_keys[1]],
+                    U256::from(1000_u64),
+                )
+                .unwrap(),
+                vec![
+                    &mut mint_account,
+                    &mut account,
+                    &mut multisig_account,
+                    &mut signer1_account,
+                ],
+            )
+        );
+
+        // the double-weight signer alone meets the threshold
+        do_process_instruction(
+            mint_to(
+                &program_id,
+                &mint_key,
+                &account_key,
+                &multisig_key,
+                &[&signer_keys[0]],
+                U256::from(1000_u64),
+            )
+            .unwrap(),
+            vec![
+                &mut mint_account,
+                &mut account,
+                &mut multisig_account,
+                &mut signer0_account,
+            ],
+        )
+        .unwrap();
+
+        // two low-weight signers also meet the threshold
+        do_process_instruction(
+            mint_to(
+                &program_id,
+                &mint_key,
+                &account_key,
+                &multisig_key,
+                &[&signer_keys[1], &signer_keys[2]],
+                U256::from(1000_u64),
+            )
+            .unwrap(),
+            vec![
+                &mut mint_account,
+                &mut account,
+                &mut multisig_account,
+                &mut signer1_account,
+                &mut signer2_account,
+            ],
+        )
+        .unwrap();
+        let account = Account::unpack_unchecked(&account.data).unwrap();
+        assert_eq!(account.amount, U256::from(2000_u64));
+    }
+
     #[test]
     fn test_validate_owner() {
         let program_id = crate::id();
@@ -0,0 +1,461 @@
+//! Exact conversions between raw amounts and UI amounts for mints that scale
+//! their UI representation
+//!
+//! Raw amounts are `U256`, so going through `f64` loses digits as soon as an
+//! amount is larger than 2^53. Instead, every scaling factor is represented as
+//! a binary fixed-point number, `numerator / 2^fractional_bits`, and all
+//! conversions are done with big-integer arithmetic. The results only depend on
+//! integer operations, so they are identical on-chain and off-chain.
+
+use {
+    crate::extension::{
+        interest_bearing_mint::InterestBearingConfig, scaled_ui_amount::ScaledUiAmountConfig,
+        BaseState, BaseStateWithExtensions,
+    },
+    ethnum::U256,
+    num_bigint::BigUint,
+    num_traits::{One, Zero},
+    solana_program::program_error::ProgramError,
+};
+
+/// Number of fractional bits kept for scales computed with `exp`, comfortably
+/// more than the 256 bits of a raw amount
+const EXP_PRECISION_BITS: u32 = 320;
+/// Extra bits of working precision used while computing `exp`
+const EXP_GUARD_BITS: u32 = 32;
+/// Largest supported magnitude for the exponent given to `exp`. Anything
+/// bigger would produce factors that are far too large to compute on-chain.
+const MAX_EXP_EXPONENT: u128 = 2048;
+/// Largest supported magnitude for the base-10 exponent of a UI amount
+const MAX_DECIMAL_EXPONENT: u64 = 4096;
+
+/// Scaling factor applied to a raw amount, stored exactly as
+/// `numerator / 2^fractional_bits`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UiAmountScale {
+    numerator: BigUint,
+    fractional_bits: u32,
+}
+impl UiAmountScale {
+    /// Scale that leaves amounts unchanged
+    pub fn one() -> Self {
+        Self {
+            numerator: BigUint::one(),
+            fractional_bits: 0,
+        }
+    }
+
+    /// Exact representation of a finite, non-negative `f64`
+    ///
+    /// Every finite `f64` is an integer mantissa multiplied by a power of two,
+    /// so no precision is lost. Returns `None` for negative, infinite, or NaN
+    /// values.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() || value.is_sign_negative() {
+            return None;
+        }
+        if value == 0.0 {
+            return Some(Self {
+                numerator: BigUint::zero(),
+                fractional_bits: 0,
+            });
+        }
+        let bits = value.to_bits();
+        let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, exponent) = if biased_exponent == 0 {
+            // subnormal
+            (fraction, -1074)
+        } else {
+            (fraction | (1 << 52), biased_exponent - 1075)
+        };
+        Some(if exponent >= 0 {
+            Self {
+                numerator: BigUint::from(mantissa) << exponent as u32,
+                fractional_bits: 0,
+            }
+        } else {
+            // drop trailing zero bits to keep the representation canonical
+            let shift = mantissa.trailing_zeros().min(exponent.unsigned_abs());
+            Self {
+                numerator: BigUint::from(mantissa >> shift),
+                fractional_bits: exponent.unsigned_abs() - shift,
+            }
+        })
+    }
+
+    /// Compute `e^(numerator / denominator)` with at least 320 significant
+    /// bits, using only integer arithmetic
+    ///
+    /// Returns `None` if the denominator is zero or the exponent is larger than
+    /// the supported maximum. Exponents below the negative of that maximum give
+    /// a zero scale.
+    pub fn exp(numerator: i128, denominator: u128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let magnitude = numerator.unsigned_abs();
+        if magnitude / denominator >= MAX_EXP_EXPONENT {
+            return if numerator < 0 {
+                Some(Self {
+                    numerator: BigUint::zero(),
+                    fractional_bits: 0,
+                })
+            } else {
+                None
+            };
+        }
+
+        let working_bits = EXP_PRECISION_BITS + EXP_GUARD_BITS;
+        let magnitude = BigUint::from(magnitude);
+        let denominator = BigUint::from(denominator);
+
+        // Halve the exponent until it's below 1 so that the series converges
+        // quickly, then square the result back up
+        let mut halvings = 0u32;
+        while magnitude >= &denominator << halvings {
+            halvings += 1;
+        }
+        let one = BigUint::one() << working_bits;
+        let x = (magnitude << working_bits) / (denominator << halvings);
+
+        // Taylor series: sum of x^n / n!
+        let mut sum = one.clone();
+        let mut term = one;
+        let mut n = 1u32;
+        loop {
+            term = ((term * &x) >> working_bits) / n;
+            if term.is_zero() {
+                break;
+            }
+            sum += &term;
+            n += 1;
+        }
+        for _ in 0..halvings {
+            sum = (&sum * &sum) >> working_bits;
+        }
+        let fractional_bits = if numerator < 0 {
+            // Take the reciprocal, with enough fractional bits to keep the same
+            // number of significant bits. The sum is at least one, so this
+            // never divides by zero.
+            let fractional_bits = sum.bits() as u32;
+            sum = (BigUint::one() << (working_bits + fractional_bits)) / sum;
+            fractional_bits
+        } else {
+            working_bits
+        };
+
+        Some(Self {
+            numerator: sum >> EXP_GUARD_BITS,
+            fractional_bits: fractional_bits - EXP_GUARD_BITS,
+        })
+    }
+
+    /// Check if the scale is zero, in which case UI amounts can't be converted
+    /// back to raw amounts
+    pub fn is_zero(&self) -> bool {
+        self.numerator.is_zero()
+    }
+
+    /// Number of digits to show beyond the mint's decimals so that the UI
+    /// amount still identifies a unique raw amount.
+    ///
+    /// Scales of at least one need no extra digits. Smaller scales get the
+    /// fewest digits `k` such that `10^-k` is smaller than the scale.
+    fn extra_decimals(&self) -> u32 {
+        let one = BigUint::one() << self.fractional_bits;
+        if self.numerator.is_zero() || self.numerator >= one {
+            return 0;
+        }
+        let mut extra = 0;
+        let mut scaled = self.numerator.clone();
+        while scaled <= one {
+            scaled *= 10u32;
+            extra += 1;
+        }
+        extra
+    }
+
+    /// Convert a raw amount to its UI representation, scaled by this factor,
+    /// using the given decimals field. Excess zeroes or unneeded decimal point
+    /// are trimmed.
+    ///
+    /// The result is rounded to the nearest digit, and carries enough digits
+    /// for `try_ui_amount_into_amount` to give back exactly `amount`.
+    pub fn amount_to_ui_amount(&self, amount: U256, decimals: u8) -> String {
+        let extra_decimals = self.extra_decimals();
+        let precision = u32::from(decimals).saturating_add(extra_decimals) as usize;
+        let scaled =
+            u256_to_biguint(amount) * &self.numerator * BigUint::from(10u32).pow(extra_decimals);
+        let rounded = round_shr(scaled, self.fractional_bits);
+
+        let mut ui_amount = format!("{:01$}", rounded, precision.saturating_add(1));
+        if precision > 0 {
+            ui_amount.insert(ui_amount.len().saturating_sub(precision), '.');
+            let trimmed = ui_amount.trim_end_matches('0').trim_end_matches('.');
+            ui_amount = trimmed.to_string();
+        }
+        ui_amount
+    }
+
+    /// Try to convert a UI representation of a token amount, scaled by this
+    /// factor, to its raw amount using the given decimals field.
+    ///
+    /// The UI amount is parsed exactly, including scientific notation, and the
+    /// resulting raw amount is rounded to the nearest integer.
+    pub fn try_ui_amount_into_amount(
+        &self,
+        ui_amount: &str,
+        decimals: u8,
+    ) -> Result<U256, ProgramError> {
+        if self.numerator.is_zero() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (digits, exponent) = parse_decimal(ui_amount).ok_or(ProgramError::InvalidArgument)?;
+        if digits.is_zero() {
+            return Ok(U256::ZERO);
+        }
+        // amount = digits * 10^(exponent + decimals) * 2^fractional_bits / numerator
+        let exponent = exponent
+            .checked_add(i64::from(decimals))
+            .ok_or(ProgramError::InvalidArgument)?;
+        let power_of_ten = BigUint::from(10u32).pow(exponent.unsigned_abs() as u32);
+        let (dividend, divisor) = if exponent >= 0 {
+            (
+                (digits * power_of_ten) << self.fractional_bits,
+                self.numerator.clone(),
+            )
+        } else {
+            (
+                digits << self.fractional_bits,
+                &self.numerator * power_of_ten,
+            )
+        };
+        let amount = (dividend * 2u32 + &divisor) / (divisor * 2u32);
+        biguint_to_u256(&amount).ok_or(ProgramError::InvalidArgument)
+    }
+}
+
+/// Divide by `2^shift`, rounding half up
+fn round_shr(value: BigUint, shift: u32) -> BigUint {
+    if shift == 0 {
+        value
+    } else {
+        (value + (BigUint::one() << (shift - 1))) >> shift
+    }
+}
+
+fn u256_to_biguint(value: U256) -> BigUint {
+    BigUint::from_bytes_le(&value.to_le_bytes())
+}
+
+fn biguint_to_u256(value: &BigUint) -> Option<U256> {
+    let bytes = value.to_bytes_le();
+    if bytes.len() > 32 {
+        return None;
+    }
+    let mut le_bytes = [0u8; 32];
+    le_bytes[..bytes.len()].copy_from_slice(&bytes);
+    Some(U256::from_le_bytes(le_bytes))
+}
+
+/// Parse a non-negative decimal number, with an optional exponent such as
+/// `1.5e3`, into its digits and base-10 exponent
+fn parse_decimal(input: &str) -> Option<(BigUint, i64)> {
+    let (mantissa, exponent) = match input.find(['e', 'E']) {
+        Some(index) => {
+            let exponent = input[index + 1..].parse::<i64>().ok()?;
+            if exponent.unsigned_abs() > MAX_DECIMAL_EXPONENT {
+                return None;
+            }
+            (&input[..index], exponent)
+        }
+        None => (input, 0),
+    };
+    let mut parts = mantissa.split('.');
+    // splitting a string, even an empty one, will always yield an iterator of at
+    // least length == 1
+    let integer_part = parts.next().unwrap();
+    let fraction_part = parts.next().unwrap_or("");
+    if (integer_part.is_empty() && fraction_part.is_empty())
+        || parts.next().is_some()
+        || !integer_part.bytes().all(|b| b.is_ascii_digit())
+        || !fraction_part.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let all_digits = format!("{integer_part}{fraction_part}");
+    let digits = BigUint::parse_bytes(all_digits.as_bytes(), 10)?;
+    let exponent = exponent.checked_sub(fraction_part.len() as i64)?;
+    Some((digits, exponent))
+}
+
+/// Check if converting amounts for the given mint depends on the current time,
+/// which is the case if it has the interest-bearing or scaled UI amount
+/// extension
+pub fn uses_unix_timestamp<S: BaseState>(mint: &impl BaseStateWithExtensions<S>) -> bool {
+    mint.get_extension::<InterestBearingConfig>().is_ok()
+        || mint.get_extension::<ScaledUiAmountConfig>().is_ok()
+}
+
+/// Convert a raw amount to its UI representation for the given mint, applying
+/// the interest-bearing or scaled UI amount extension if present
+pub fn amount_to_ui_amount_for_mint<S: BaseState>(
+    mint: &impl BaseStateWithExtensions<S>,
+    decimals: u8,
+    amount: U256,
+    unix_timestamp: i64,
+) -> Option<String> {
+    if let Ok(extension) = mint.get_extension::<InterestBearingConfig>() {
+        extension.amount_to_ui_amount(amount, decimals, unix_timestamp)
+    } else if let Ok(extension) = mint.get_extension::<ScaledUiAmountConfig>() {
+        extension.amount_to_ui_amount(amount, decimals, unix_timestamp)
+    } else {
+        Some(crate::amount_to_ui_amount_string_trimmed(amount, decimals))
+    }
+}
+
+/// Try to convert a UI representation of a token amount to its raw amount for
+/// the given mint, applying the interest-bearing or scaled UI amount extension
+/// if present
+pub fn try_ui_amount_into_amount_for_mint<S: BaseState>(
+    mint: &impl BaseStateWithExtensions<S>,
+    decimals: u8,
+    ui_amount: &str,
+    unix_timestamp: i64,
+) -> Result<U256, ProgramError> {
+    if let Ok(extension) = mint.get_extension::<InterestBearingConfig>() {
+        extension.try_ui_amount_into_amount(ui_amount, decimals, unix_timestamp)
+    } else if let Ok(extension) = mint.get_extension::<ScaledUiAmountConfig>() {
+        extension.try_ui_amount_into_amount(ui_amount, decimals, unix_timestamp)
+    } else {
+        crate::try_ui_amount_into_amount(ui_amount.to_string(), decimals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, proptest::prelude::*};
+
+    #[test]
+    fn from_f64_is_exact() {
+        assert_eq!(UiAmountScale::from_f64(1.0).unwrap(), UiAmountScale::one());
+        let scale = UiAmountScale::from_f64(0.1).unwrap();
+        // 0.1 is not representable in binary, so make sure to use the value that
+        // the f64 actually holds
+        assert_eq!(
+            scale.amount_to_ui_amount(U256::new(10u128.pow(30)), 0),
+            "100000000000000005551115123125.8"
+        );
+        assert_eq!(UiAmountScale::from_f64(-1.0), None);
+        assert_eq!(UiAmountScale::from_f64(f64::NAN), None);
+        assert_eq!(UiAmountScale::from_f64(f64::INFINITY), None);
+        assert!(UiAmountScale::from_f64(0.0).unwrap().is_zero());
+    }
+
+    #[test]
+    fn exp_values() {
+        // e^0 = 1
+        let scale = UiAmountScale::exp(0, 1).unwrap();
+        assert_eq!(scale.amount_to_ui_amount(U256::new(1), 0), "1");
+        // e^1
+        let scale = UiAmountScale::exp(1, 1).unwrap();
+        assert_eq!(
+            scale.amount_to_ui_amount(U256::new(10u128.pow(30)), 30),
+            "2.718281828459045235360287471353"
+        );
+        // e^-1
+        let scale = UiAmountScale::exp(-1, 1).unwrap();
+        assert_eq!(
+            scale.amount_to_ui_amount(U256::new(10u128.pow(30)), 30),
+            "0.3678794411714423215955237701615"
+        );
+        // too large
+        assert_eq!(UiAmountScale::exp(MAX_EXP_EXPONENT as i128, 1), None);
+        // too small
+        assert!(UiAmountScale::exp(-(MAX_EXP_EXPONENT as i128), 1)
+            .unwrap()
+            .is_zero());
+        assert_eq!(UiAmountScale::exp(1, 0), None);
+    }
+
+    #[test]
+    fn parse_ui_amounts() {
+        let scale = UiAmountScale::one();
+        for (ui_amount, expected) in [
+            ("1", 1u128),
+            ("1.", 1),
+            (".5", 1),
+            ("1.4", 1),
+            ("1.5e1", 15),
+            ("15E-1", 2),
+            ("0.000", 0),
+            ("1e+2", 100),
+        ] {
+            assert_eq!(
+                scale.try_ui_amount_into_amount(ui_amount, 0).unwrap(),
+                U256::new(expected)
+            );
+        }
+        for ui_amount in [
+            "", ".", "0.t", "-1", "inf", "NaN", "1.2.3", "1e", "e5", "1e99999",
+        ] {
+            assert_eq!(
+                scale.try_ui_amount_into_amount(ui_amount, 0),
+                Err(ProgramError::InvalidArgument)
+            );
+        }
+        // overflow
+        assert_eq!(
+            scale.try_ui_amount_into_amount(&format!("{}0", U256::MAX), 0),
+            Err(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            scale
+                .try_ui_amount_into_amount(&U256::MAX.to_string(), 0)
+                .unwrap(),
+            U256::MAX
+        );
+    }
+
+    #[test]
+    fn extra_digits_for_small_scales() {
+        let scale = UiAmountScale::from_f64(0.5).unwrap();
+        assert_eq!(scale.amount_to_ui_amount(U256::new(1), 0), "0.5");
+        assert_eq!(scale.amount_to_ui_amount(U256::new(3), 2), "0.015");
+        let scale = UiAmountScale::from_f64(0.001).unwrap();
+        assert_eq!(scale.amount_to_ui_amount(U256::new(7), 0), "0.007");
+    }
+
+    prop_compose! {
+        fn any_u256()(high in any::<u128>(), low in any::<u128>()) -> U256 {
+            U256::from_words(high, low)
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn f64_scale_round_trip(
+            amount in any_u256(),
+            multiplier in 1e-20f64..1e20f64,
+            decimals in 0u8..=30u8,
+        ) {
+            let scale = UiAmountScale::from_f64(multiplier).unwrap();
+            let ui_amount = scale.amount_to_ui_amount(amount, decimals);
+            let round_trip = scale.try_ui_amount_into_amount(&ui_amount, decimals).unwrap();
+            prop_assert_eq!(round_trip, amount);
+        }
+
+        #[test]
+        fn exp_scale_round_trip(
+            amount in any_u256(),
+            numerator in -1_000_000_000i128..1_000_000_000i128,
+            decimals in 0u8..=30u8,
+        ) {
+            let scale = UiAmountScale::exp(numerator, 1_000_000).unwrap();
+            let ui_amount = scale.amount_to_ui_amount(amount, decimals);
+            let round_trip = scale.try_ui_amount_into_amount(&ui_amount, decimals).unwrap();
+            prop_assert_eq!(round_trip, amount);
+        }
+    }
+}
@@ -2,8 +2,11 @@
 
 mod action;
 use {
-    solana_program_test::{processor, tokio, ProgramTest},
+    ethnum::U256,
+    solana_program_test::{processor, tokio, ProgramTest, ProgramTestContext},
     solana_sdk::{
+        clock::Clock,
+        instruction::Instruction,
         program_pack::Pack,
         pubkey::Pubkey,
         signature::{Keypair, Signer},
@@ -11,6 +14,7 @@ use {
         transaction::Transaction,
     },
     spl_token_2022::{
+        extension::{interest_bearing_mint, scaled_ui_amount, ExtensionType},
         id, instruction,
         processor::Processor,
         state::{Account, Mint},
@@ -400,3 +404,139 @@ async fn close_account() {
     );
     banks_client.process_transaction(transaction).await.unwrap();
 }
+
+/// Every UI amount conversion must fit in the default compute budget of a
+/// single instruction
+const UI_AMOUNT_MAX_UNITS: u64 = 200_000;
+
+async fn create_mint_with_extension(
+    context: &mut ProgramTestContext,
+    mint: &Keypair,
+    extension_type: ExtensionType,
+    initialize_extension: Instruction,
+    decimals: u8,
+) {
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[extension_type]).unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            system_instruction::create_account(
+                &context.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(space),
+                space as u64,
+                &id(),
+            ),
+            initialize_extension,
+            instruction::initialize_mint(
+                &id(),
+                &mint.pubkey(),
+                &context.payer.pubkey(),
+                None,
+                decimals,
+            )
+            .unwrap(),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, mint],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+async fn process_instruction(context: &mut ProgramTestContext, instruction: Instruction) {
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn amount_to_ui_amount_max_multiplier() {
+    let mut pt = ProgramTest::new("spl_token_2022", id(), processor!(Processor::process));
+    pt.set_compute_max_units(UI_AMOUNT_MAX_UNITS);
+    let mut context = pt.start_with_context().await;
+
+    // the largest multiplier with the most decimals and the largest amount
+    // produces the longest UI amount
+    let mint = Keypair::new();
+    create_mint_with_extension(
+        &mut context,
+        &mint,
+        ExtensionType::ScaledUiAmount,
+        scaled_ui_amount::instruction::initialize(&id(), &mint.pubkey(), None, f64::MAX).unwrap(),
+        u8::MAX,
+    )
+    .await;
+
+    process_instruction(
+        &mut context,
+        instruction::amount_to_ui_amount(&id(), &mint.pubkey(), U256::MAX).unwrap(),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn ui_amount_to_amount_min_exponent() {
+    let mut pt = ProgramTest::new("spl_token_2022", id(), processor!(Processor::process));
+    pt.set_compute_max_units(UI_AMOUNT_MAX_UNITS);
+    let mut context = pt.start_with_context().await;
+
+    // the smallest supported exponent requires the largest power of ten
+    let mint = Keypair::new();
+    create_mint_with_extension(
+        &mut context,
+        &mint,
+        ExtensionType::ScaledUiAmount,
+        scaled_ui_amount::instruction::initialize(&id(), &mint.pubkey(), None, f64::MAX).unwrap(),
+        u8::MAX,
+    )
+    .await;
+
+    process_instruction(
+        &mut context,
+        instruction::ui_amount_to_amount(&id(), &mint.pubkey(), "1e-4096").unwrap(),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn amount_to_ui_amount_max_interest() {
+    let mut pt = ProgramTest::new("spl_token_2022", id(), processor!(Processor::process));
+    pt.set_compute_max_units(UI_AMOUNT_MAX_UNITS);
+    let mut context = pt.start_with_context().await;
+
+    let mint = Keypair::new();
+    create_mint_with_extension(
+        &mut context,
+        &mint,
+        ExtensionType::InterestBearingConfig,
+        interest_bearing_mint::instruction::initialize(&id(), &mint.pubkey(), None, i16::MAX)
+            .unwrap(),
+        u8::MAX,
+    )
+    .await;
+
+    // move the clock forward to just below the largest supported exponent,
+    // which requires the most terms and squarings in `exp`
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += 2_047 * 31_556_736 * 10_000 / i16::MAX as i64;
+    context.set_sysvar(&clock);
+
+    process_instruction(
+        &mut context,
+        instruction::amount_to_ui_amount(&id(), &mint.pubkey(), U256::MAX).unwrap(),
+    )
+    .await;
+}